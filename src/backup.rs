@@ -0,0 +1,138 @@
+use crate::config;
+use crate::db::sqlite::SqliteStore;
+use crate::time;
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+// Number of backups kept, older ones are deleted
+pub const KEEP: usize = 10;
+
+// Changes to the database between two backups
+pub const EVERY: usize = 50;
+
+// Backup of the database
+pub struct Backup {
+    pub path: String,
+    pub time: i64,
+    pub stacks: i64,
+    pub cards: i64,
+}
+
+// Open the database of the config, backing it up on start if it changed and as
+// it changes, unless backups are turned off
+pub fn open_store(startup: bool) -> rusqlite::Result<SqliteStore> {
+    let db_file = config::get_db_file();
+    let store = SqliteStore::open(&db_file)?;
+    if !config::load().0.backups {
+        return Ok(store);
+    }
+    let dir = config::get_backup_dir();
+    if startup {
+        let _ = create_on_startup(&db_file, &dir);
+    }
+    Ok(store.with_backups(&dir))
+}
+
+// Copy the database of a connection into the backup directory and delete old backups
+pub fn create(conn: &Connection, dir: &str) -> io::Result<String> {
+    fs::create_dir_all(dir)?;
+    let path = format!("{}/cards-{}.db", dir, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    if !Path::new(&path).exists() {
+        // VACUUM INTO writes a consistent copy even while the database is in use
+        conn.execute("VACUUM INTO ?1", (&path,)).map_err(io::Error::other)?;
+    }
+    rotate(dir)?;
+
+    Ok(path)
+}
+
+// Back up the database file unless it didn't change since the last backup
+pub fn create_on_startup(db_file: &str, dir: &str) -> io::Result<()> {
+    let modified = match fs::metadata(db_file) {
+        Ok(metadata) => modified(&metadata),
+        Err(_) => return Ok(()),
+    };
+    if files(dir).first().map(|(_, time)| *time >= modified).unwrap_or(false) {
+        return Ok(());
+    }
+    let conn = Connection::open_with_flags(db_file, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(io::Error::other)?;
+    create(&conn, dir)?;
+
+    Ok(())
+}
+
+// Backups in the backup directory with their stack and card counts, newest first
+pub fn list(dir: &str) -> Vec<Backup> {
+    files(dir)
+        .into_iter()
+        .map(|(path, time)| {
+            let (stacks, cards) = count(&path).unwrap_or((-1, -1));
+            Backup {
+                path,
+                time,
+                stacks,
+                cards,
+            }
+        })
+        .collect()
+}
+
+// Backup files with their time, newest first
+fn files(dir: &str) -> Vec<(String, i64)> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut files: Vec<(String, i64)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".db"))
+        .filter_map(|entry| Some((entry.path().to_string_lossy().to_string(), modified(&entry.metadata().ok()?))))
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+
+    files
+}
+
+// Name of a backup for lists
+pub fn name(backup: &Backup) -> String {
+    match backup.cards < 0 {
+        true => format!("{}  (unreadable)", time::format_date_time(backup.time)),
+        false => format!(
+            "{}  {} stacks, {} cards",
+            time::format_date_time(backup.time),
+            backup.stacks,
+            backup.cards
+        ),
+    }
+}
+
+// Number of stacks and cards in a backup
+fn count(path: &str) -> rusqlite::Result<(i64, i64)> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let stacks = conn.query_row("SELECT count(*) FROM stack", (), |row| row.get(0))?;
+    let cards = conn.query_row("SELECT count(*) FROM card", (), |row| row.get(0))?;
+
+    Ok((stacks, cards))
+}
+
+// Delete all but the newest backups
+fn rotate(dir: &str) -> io::Result<()> {
+    for (path, _) in files(dir).into_iter().skip(KEEP) {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+// Last modification of a file as unix time
+fn modified(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,793 @@
+use crate::config;
+use crate::db::card::Card;
+use crate::db::review::Review;
+use crate::db::stack::Stack;
+use crate::db::Store;
+use crate::export::{self, Format};
+use crate::import::{self, Report, Target};
+use crate::review;
+use crate::scheduler;
+use crate::sync;
+use crate::time;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+
+// Commands to script the collection without the ui
+
+// Version of the JSON output, raised when fields are renamed or removed
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+// Error of a command whose output was closed before it was done, not a failure
+pub const OUTPUT_CLOSED: &str = "Output closed";
+
+pub const USAGE: &str = "Usage: cards [--db <file>] [--config <file>] [--profile <name>] [command]
+
+Without a command the terminal ui starts.
+
+Options:
+  --db <file>                                 Use this database, also set with CARDS_DB
+  --config <file>                             Use this config file, also set with CARDS_CONFIG
+  --profile <name>                            Use this profile once, also set with CARDS_PROFILE
+
+Commands:
+  stacks [--format json]                      List stacks with their card counts
+  add --stack <name> --front <text> --back <text> [--tags <tags>]
+                                              Add a card, creating the stack if needed
+  list [--stack <name>] [--format json]       List cards
+  due [--stack <name>] [--format json]        List reviewed cards due for revision
+  stats [--stack <name>] [--format json]      Show card and review counts
+  review <stack> [--all]                      Review due and new cards line by line, --all for every card
+  import <file> [--stack <name>] [--no-history]
+                                              Import a CSV, TSV, JSON, Markdown, Org or Anki file
+  export <file> [--stack <name>] [--substacks]
+                                              Export to a .csv, .tsv, .json, .md, .org, .apkg or .html file
+  paste --stack <name> [--term <separator>] [--cards <separator>]
+                                              Import term and definition pairs from stdin
+  sync <dir>                                  Sync with a directory of Markdown deck files
+  profiles                                    List profiles, the one in use is marked with *
+  profile <name>                              Switch to a profile, creating it if needed
+  help                                        Show this help
+
+--stack of list, due and stats also takes the child stacks of a stack, named like \"Parent::Child\",
+export takes them with --substacks.
+--format json prints a JSON document with a \"schema_version\" field, see the README.";
+
+// Output of listing commands
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Output {
+    Text,
+    Json,
+}
+
+// Command given on the command line
+pub enum Command {
+    Ui,
+    Help,
+    Profiles,
+    Profile {
+        name: String,
+    },
+    Stacks {
+        output: Output,
+    },
+    Add {
+        stack: String,
+        front: String,
+        back: String,
+        tags: Option<String>,
+    },
+    List {
+        stack: Option<String>,
+        output: Output,
+    },
+    Due {
+        stack: Option<String>,
+        output: Output,
+    },
+    Stats {
+        stack: Option<String>,
+        output: Output,
+    },
+    Review {
+        stack: String,
+        all: bool,
+    },
+    Import {
+        path: String,
+        stack: Option<String>,
+        history: bool,
+    },
+    Export {
+        path: String,
+        stack: Option<String>,
+        substacks: bool,
+    },
+    Paste {
+        stack: String,
+        term: String,
+        cards: String,
+    },
+    Sync {
+        dir: String,
+    },
+}
+
+// Arguments of a command split into positional arguments, "--name value" options and "--name" flags
+struct Args {
+    positional: Vec<String>,
+    values: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    // Split args, options not in values or flags are errors
+    fn parse(args: &[String], values: &[&str], flags: &[&str]) -> Result<Args, String> {
+        let mut parsed = Args {
+            positional: vec![],
+            values: HashMap::new(),
+            flags: vec![],
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = match arg.strip_prefix("--") {
+                Some(name) => name,
+                None => {
+                    parsed.positional.push(arg.to_string());
+                    continue;
+                }
+            };
+            let (name, inline) = match name.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (name, None),
+            };
+            if values.contains(&name) {
+                let value = match inline {
+                    Some(value) => value,
+                    None => args.next().cloned().ok_or(format!("Missing value for --{}", name))?,
+                };
+                parsed.values.insert(name.to_string(), value);
+            } else if flags.contains(&name) && inline.is_none() {
+                parsed.flags.push(name.to_string());
+            } else {
+                return Err(format!("Unknown option --{}", name));
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        self.values.get(name).cloned()
+    }
+
+    fn required(&self, name: &str) -> Result<String, String> {
+        self.value(name).ok_or(format!("Missing --{}", name))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    // The only positional argument
+    fn single(&self, name: &str) -> Result<String, String> {
+        match self.positional.as_slice() {
+            [value] => Ok(value.to_string()),
+            [] => Err(format!("Missing <{}>", name)),
+            _ => Err(format!("Unexpected argument \"{}\"", self.positional[1])),
+        }
+    }
+
+    // Output picked with --format
+    fn output(&self) -> Result<Output, String> {
+        match self.value("format").as_deref() {
+            None | Some("text") => Ok(Output::Text),
+            Some("json") => Ok(Output::Json),
+            Some(format) => Err(format!("Unknown format \"{}\", use text or json", format)),
+        }
+    }
+
+    fn none(&self) -> Result<(), String> {
+        match self.positional.first() {
+            Some(arg) => Err(format!("Unexpected argument \"{}\"", arg)),
+            None => Ok(()),
+        }
+    }
+}
+
+// Environment variables with their values
+pub type Vars = Vec<(&'static str, String)>;
+
+// Options before the command as environment variables to set, and the arguments left
+pub fn global_options(args: &[String]) -> Result<(Vars, &[String]), String> {
+    let mut vars = vec![];
+    let mut rest = args;
+    while let Some((arg, after)) = rest.split_first() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let var = match name {
+            "--db" => config::DB_VAR,
+            "--config" => config::CONFIG_VAR,
+            "--profile" => config::PROFILE_VAR,
+            _ => break,
+        };
+        let (value, after) = match inline {
+            Some(value) => (value, after),
+            None => match after.split_first() {
+                Some((value, after)) => (value.to_string(), after),
+                None => return Err(format!("Missing value for {}", name)),
+            },
+        };
+        let value = match var {
+            config::PROFILE_VAR => {
+                config::check_profile(&value)?;
+                value
+            }
+            // Paths stay valid when the working directory changes
+            _ => env::current_dir()
+                .map(|dir| dir.join(&value).to_string_lossy().to_string())
+                .unwrap_or(value),
+        };
+        vars.push((var, value));
+        rest = after;
+    }
+
+    Ok((vars, rest))
+}
+
+// Command of the arguments after the program name
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let (name, rest) = match args.split_first() {
+        Some((name, rest)) => (name.as_str(), rest),
+        None => return Ok(Command::Ui),
+    };
+
+    let name = match name {
+        "-h" | "--help" => "help",
+        name => name,
+    };
+
+    match name {
+        "help" => Ok(Command::Help),
+        "profiles" => {
+            let args = Args::parse(rest, &[], &[])?;
+            args.none()?;
+            Ok(Command::Profiles)
+        }
+        "profile" => {
+            let args = Args::parse(rest, &[], &[])?;
+            Ok(Command::Profile {
+                name: args.single("name")?,
+            })
+        }
+        "stacks" => {
+            let args = Args::parse(rest, &["format"], &[])?;
+            args.none()?;
+            Ok(Command::Stacks {
+                output: args.output()?,
+            })
+        }
+        "add" => {
+            let args = Args::parse(rest, &["stack", "front", "back", "tags"], &[])?;
+            args.none()?;
+            Ok(Command::Add {
+                stack: args.required("stack")?,
+                front: args.required("front")?,
+                back: args.required("back")?,
+                tags: args.value("tags"),
+            })
+        }
+        "list" | "due" | "stats" => {
+            let args = Args::parse(rest, &["stack", "format"], &[])?;
+            args.none()?;
+            let stack = args.value("stack");
+            let output = args.output()?;
+            Ok(match name {
+                "list" => Command::List { stack, output },
+                "due" => Command::Due { stack, output },
+                _ => Command::Stats { stack, output },
+            })
+        }
+        "review" => {
+            let args = Args::parse(rest, &[], &["all"])?;
+            Ok(Command::Review {
+                stack: args.single("stack")?,
+                all: args.flag("all"),
+            })
+        }
+        "import" => {
+            let args = Args::parse(rest, &["stack"], &["no-history"])?;
+            Ok(Command::Import {
+                path: args.single("file")?,
+                stack: args.value("stack"),
+                history: !args.flag("no-history"),
+            })
+        }
+        "export" => {
+            let args = Args::parse(rest, &["stack"], &["substacks"])?;
+            Ok(Command::Export {
+                path: args.single("file")?,
+                stack: args.value("stack"),
+                substacks: args.flag("substacks"),
+            })
+        }
+        "paste" => {
+            let args = Args::parse(rest, &["stack", "term", "cards"], &[])?;
+            args.none()?;
+            Ok(Command::Paste {
+                stack: args.required("stack")?,
+                term: args
+                    .value("term")
+                    .unwrap_or_else(|| import::paste::TERM_SEPARATORS[0].1.to_string()),
+                cards: args
+                    .value("cards")
+                    .unwrap_or_else(|| import::paste::CARD_SEPARATORS[0].1.to_string()),
+            })
+        }
+        "sync" => {
+            let args = Args::parse(rest, &[], &[])?;
+            Ok(Command::Sync {
+                dir: args.single("dir")?,
+            })
+        }
+        name => Err(format!("Unknown command \"{}\"", name)),
+    }
+}
+
+// Run a profile command, they don't need the database
+pub fn run_profile(command: Command, out: &mut dyn Write) -> Result<(), String> {
+    match command {
+        Command::Profiles => {
+            let current = config::get_profile();
+            for profile in config::get_profiles() {
+                let mark = if profile == current { "*" } else { " " };
+                writeln!(out, "{} {}", mark, profile).map_err(error)?;
+            }
+            Ok(())
+        }
+        Command::Profile { name } => {
+            config::set_profile(&name)?;
+            writeln!(out, "Switched to profile {}, database {}", name, config::get_db_file()).map_err(error)
+        }
+        _ => Ok(()),
+    }
+}
+
+// Run a command other than the ui, writing its output to out
+pub fn run(command: Command, store: &mut dyn Store, out: &mut dyn Write) -> Result<(), String> {
+    match command {
+        Command::Ui => Ok(()),
+        Command::Help => writeln!(out, "{}", USAGE).map_err(error),
+        Command::Profiles | Command::Profile { .. } => run_profile(command, out),
+        Command::Stacks { output } => stacks(store, out, output).map_err(error),
+        Command::Add {
+            stack,
+            front,
+            back,
+            tags,
+        } => add(store, out, &stack, front, back, tags),
+        Command::List { stack, output } => {
+            let cards = cards(store, stack.as_deref())?;
+            list(store, out, &cards, output).map_err(error)
+        }
+        Command::Due { stack, output } => {
+            let now = time::now();
+            let mut cards = cards(store, stack.as_deref())?;
+            cards.retain(|(_, card)| !card.suspended && scheduler::is_due(&card.schedule, now));
+            cards.sort_by_key(|(_, card)| card.schedule.due);
+            match cards.is_empty() && output == Output::Text {
+                true => writeln!(out, "No cards due").map_err(error),
+                false => list(store, out, &cards, output).map_err(error),
+            }
+        }
+        Command::Stats { stack, output } => {
+            let stacks = collect(store, stack.as_deref())?;
+            stats(store, out, &stacks, output).map_err(error)
+        }
+        Command::Review { stack, all } => {
+            let now = time::now();
+            let mut cards = cards(store, Some(&stack))?;
+            cards.retain(|(_, card)| {
+                !card.suspended && (all || scheduler::is_due(&card.schedule, now) || scheduler::is_new(&card.schedule))
+            });
+            // Due cards first, then the new ones
+            if !all {
+                cards.sort_by_key(|(_, card)| (scheduler::is_new(&card.schedule), card.schedule.due));
+            }
+            if cards.is_empty() {
+                return writeln!(out, "No cards due in {}", stack).map_err(error);
+            }
+            review::session(store, &cards, &mut io::stdin().lock(), out).map_err(error)?;
+            Ok(())
+        }
+        Command::Import {
+            path,
+            stack,
+            history,
+        } => import(store, out, &path, stack, history),
+        Command::Export {
+            path,
+            stack,
+            substacks,
+        } => {
+            let format = Format::from_path(&path)
+                .ok_or("Unknown export format, use a .csv, .tsv, .json, .md, .org, .apkg or .html file")?;
+            let stack_id = match stack {
+                Some(name) => Some(find_stack(store, &name)?.id),
+                None => None,
+            };
+            let count = export::to_file(store, stack_id, substacks, &path, format).map_err(|err| format!("Export failed: {}", err))?;
+            writeln!(out, "Exported {} cards to {}", count, path).map_err(error)
+        }
+        Command::Paste { stack, term, cards } => {
+            let mut content = String::new();
+            io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|err| format!("Could not read input: {}", err))?;
+            let target = target(store, &stack);
+            let report = import::paste::import(store, &target, &content, &term, &cards);
+            writeln!(out, "Imported {} cards into {}, skipped {}", report.added, stack, report.skipped.len()).map_err(error)?;
+            skipped(&report, "card").map_err(error)
+        }
+        Command::Sync { dir } => {
+            let report = sync::sync(store, &dir).map_err(|err| format!("Sync failed: {}", err))?;
+            writeln!(
+                out,
+                "Collection: {} added, {} updated, {} deleted",
+                report.added_to_db, report.updated_in_db, report.deleted_from_db
+            )
+            .map_err(error)?;
+            writeln!(
+                out,
+                "Files: {} added, {} updated, {} deleted",
+                report.added_to_files, report.updated_in_files, report.deleted_from_files
+            )
+            .map_err(error)?;
+            for conflict in &report.conflicts {
+                writeln!(out, "Conflict: {}", conflict).map_err(error)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn error(err: io::Error) -> String {
+    // Output piped into a command that stopped reading, like head
+    if err.kind() == io::ErrorKind::BrokenPipe {
+        return OUTPUT_CLOSED.to_string();
+    }
+    err.to_string()
+}
+
+// Stack with a name
+fn find_stack(store: &dyn Store, name: &str) -> Result<Stack, String> {
+    store
+        .stacks()
+        .into_iter()
+        .find(|stack| stack.name == name)
+        .ok_or(format!("No stack named \"{}\"", name))
+}
+
+// Stack with a name, or a new one if there is none
+fn target(store: &dyn Store, name: &str) -> Target {
+    match find_stack(store, name) {
+        Ok(stack) => Target::Stack(stack.id),
+        Err(_) => Target::New(name.to_string()),
+    }
+}
+
+// A stack and its child stacks, or every stack
+fn collect(store: &dyn Store, stack: Option<&str>) -> Result<Vec<(Stack, Vec<Card>)>, String> {
+    let stack_id = match stack {
+        Some(name) => Some(find_stack(store, name)?.id),
+        None => None,
+    };
+    Ok(export::collect(store, stack_id, true))
+}
+
+// Cards with the name of their stack
+fn cards(store: &dyn Store, stack: Option<&str>) -> Result<Vec<(String, Card)>, String> {
+    Ok(collect(store, stack)?
+        .into_iter()
+        .flat_map(|(stack, cards)| cards.into_iter().map(move |card| (stack.name.to_string(), card)))
+        .collect())
+}
+
+// Rows with columns padded to the same width
+fn table(out: &mut dyn Write, header: &[&str], rows: &[Vec<String>]) -> io::Result<()> {
+    let mut widths: Vec<usize> = header.iter().map(|name| name.chars().count()).collect();
+    for row in rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.chars().count());
+        }
+    }
+    let line = |fields: Vec<&str>| {
+        let last = fields.len() - 1;
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match i == last {
+                true => field.to_string(),
+                false => format!("{}{}", field, " ".repeat(widths[i] - field.chars().count())),
+            })
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    writeln!(out, "{}", line(header.to_vec()))?;
+    for row in rows {
+        writeln!(out, "{}", line(row.iter().map(|field| field.as_str()).collect()))?;
+    }
+
+    Ok(())
+}
+
+// When a card is due, "new" if it was never reviewed
+fn due(card: &Card) -> String {
+    match card.schedule.reps {
+        0 => "new".to_string(),
+        _ => time::format_date(card.schedule.due),
+    }
+}
+
+// Stack with its card counts
+#[derive(Serialize)]
+struct StackRow<'a> {
+    #[serde(flatten)]
+    stack: &'a Stack,
+    cards: usize,
+    due: usize,
+    new: usize,
+}
+
+// Card with its stack and reviews
+#[derive(Serialize)]
+struct CardRow<'a> {
+    #[serde(flatten)]
+    card: &'a Card,
+    stack: &'a str,
+    reviews: Vec<Review>,
+}
+
+// Counts of the stats command
+#[derive(Serialize)]
+struct Stats {
+    stacks: usize,
+    cards: usize,
+    new: usize,
+    due: usize,
+    suspended: usize,
+    reviews: usize,
+    reviews_today: usize,
+    lapses: i32,
+    // Average ease of reviewed cards, None if no card was reviewed
+    average_ease: Option<f64>,
+}
+
+// JSON document with the schema version first and one named field
+#[derive(Serialize)]
+struct Document<'a, T: Serialize> {
+    schema_version: u32,
+    #[serde(flatten)]
+    body: HashMap<&'a str, T>,
+}
+
+fn json(out: &mut dyn Write, name: &str, value: impl Serialize) -> io::Result<()> {
+    let document = Document {
+        schema_version: JSON_SCHEMA_VERSION,
+        body: HashMap::from([(name, value)]),
+    };
+    writeln!(out, "{}", serde_json::to_string_pretty(&document).unwrap())
+}
+
+fn stacks(store: &dyn Store, out: &mut dyn Write, output: Output) -> io::Result<()> {
+    let now = time::now();
+    let stacks = store.stacks();
+    let rows: Vec<StackRow> = stacks
+        .iter()
+        .map(|stack| {
+            let cards = store.cards(stack.id);
+            let active: Vec<&Card> = cards.iter().filter(|card| !card.suspended).collect();
+            StackRow {
+                stack,
+                cards: cards.len(),
+                due: active.iter().filter(|card| scheduler::is_due(&card.schedule, now)).count(),
+                new: active.iter().filter(|card| scheduler::is_new(&card.schedule)).count(),
+            }
+        })
+        .collect();
+    if output == Output::Json {
+        return json(out, "stacks", rows);
+    }
+
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.stack.id.to_string(),
+                row.stack.name.to_string(),
+                row.cards.to_string(),
+                row.due.to_string(),
+                row.new.to_string(),
+            ]
+        })
+        .collect();
+    table(out, &["ID", "STACK", "CARDS", "DUE", "NEW"], &rows)
+}
+
+fn add(
+    store: &mut dyn Store,
+    out: &mut dyn Write,
+    stack: &str,
+    front: String,
+    back: String,
+    tags: Option<String>,
+) -> Result<(), String> {
+    if front.trim().is_empty() || back.trim().is_empty() {
+        return Err("The front and back of a card can't be empty".to_string());
+    }
+    let stack_id = target(store, stack).stack_id(store);
+    let id = store.add_card(stack_id, front, back);
+    if let Some(tags) = tags.filter(|tags| !tags.trim().is_empty()) {
+        let mut cards = store.cards(stack_id);
+        cards.retain(|card| card.id == id);
+        for card in cards.iter_mut() {
+            for tag in tags.split(|c: char| c.is_whitespace() || c == ',').filter(|tag| !tag.is_empty()) {
+                card.add_tag(tag);
+            }
+        }
+        store.update_cards(&cards);
+    }
+    writeln!(out, "Added card {} to {}", id, stack).map_err(error)
+}
+
+fn list(store: &dyn Store, out: &mut dyn Write, cards: &[(String, Card)], output: Output) -> io::Result<()> {
+    if output == Output::Json {
+        let rows: Vec<CardRow> = cards
+            .iter()
+            .map(|(stack, card)| CardRow {
+                card,
+                stack,
+                reviews: store.reviews(card.id),
+            })
+            .collect();
+        return json(out, "cards", rows);
+    }
+
+    let rows: Vec<Vec<String>> = cards
+        .iter()
+        .map(|(stack, card)| {
+            vec![
+                card.id.to_string(),
+                stack.to_string(),
+                card.title.replace('\n', " "),
+                match card.suspended {
+                    true => "suspended".to_string(),
+                    false => due(card),
+                },
+                card.tags.to_string(),
+            ]
+        })
+        .collect();
+    table(out, &["ID", "STACK", "TITLE", "DUE", "TAGS"], &rows)
+}
+
+fn stats(store: &dyn Store, out: &mut dyn Write, stacks: &[(Stack, Vec<Card>)], output: Output) -> io::Result<()> {
+    let now = time::now();
+    let today = time::start_of_day(now);
+    let cards: Vec<&Card> = stacks.iter().flat_map(|(_, cards)| cards).collect();
+    let active: Vec<&&Card> = cards.iter().filter(|card| !card.suspended).collect();
+    let reviewed: Vec<&&Card> = cards.iter().filter(|card| card.schedule.reps > 0).collect();
+    let reviews: Vec<i64> = cards
+        .iter()
+        .flat_map(|card| store.reviews(card.id))
+        .map(|review| review.time)
+        .collect();
+    let stats = Stats {
+        stacks: stacks.len(),
+        cards: cards.len(),
+        new: active.iter().filter(|card| scheduler::is_new(&card.schedule)).count(),
+        due: active.iter().filter(|card| scheduler::is_due(&card.schedule, now)).count(),
+        suspended: cards.len() - active.len(),
+        reviews: reviews.len(),
+        reviews_today: reviews.iter().filter(|time| **time >= today).count(),
+        lapses: cards.iter().map(|card| card.schedule.lapses).sum(),
+        average_ease: match reviewed.is_empty() {
+            true => None,
+            false => Some(
+                reviewed.iter().map(|card| card.schedule.ease as f64).sum::<f64>() / reviewed.len() as f64 / 1000.0,
+            ),
+        },
+    };
+    if output == Output::Json {
+        return json(out, "stats", stats);
+    }
+
+    let rows = [
+        ("Stacks", stats.stacks.to_string()),
+        ("Cards", stats.cards.to_string()),
+        ("New", stats.new.to_string()),
+        ("Due", stats.due.to_string()),
+        ("Suspended", stats.suspended.to_string()),
+        ("Reviews", stats.reviews.to_string()),
+        ("Reviews today", stats.reviews_today.to_string()),
+        ("Lapses", stats.lapses.to_string()),
+        (
+            "Average ease",
+            stats.average_ease.map(|ease| format!("{:.2}", ease)).unwrap_or("-".to_string()),
+        ),
+    ];
+    for (name, value) in rows {
+        writeln!(out, "{:<15}{}", format!("{}:", name), value)?;
+    }
+
+    Ok(())
+}
+
+fn import(
+    store: &mut dyn Store,
+    out: &mut dyn Write,
+    path: &str,
+    stack: Option<String>,
+    history: bool,
+) -> Result<(), String> {
+    let path = import::expand_path(path);
+    let read = |path: &str| fs::read_to_string(path).map_err(|err| format!("Can't read {}: {}", path, err));
+    let default_name = import::markdown::stack_name(&path);
+    let decks = if import::anki::is_package(&path) {
+        Some(import::anki::read(&path).map_err(|err| format!("Can't read {}: {}", path, err))?)
+    } else if import::json::is_json(&path) {
+        Some(import::json::parse(&read(&path)?).map_err(|err| format!("Can't parse {}: {}", path, err))?)
+    } else if import::org::is_org(&path) {
+        Some(import::org::parse(&read(&path)?, &default_name))
+    } else if import::markdown::is_markdown(&path) {
+        Some(import::markdown::parse(&read(&path)?, &default_name))
+    } else {
+        None
+    };
+
+    // Decks go into stacks of the same name
+    if let Some(decks) = decks {
+        if stack.is_some() {
+            return Err("--stack only applies to CSV and TSV files, decks go into stacks of the same name".to_string());
+        }
+        let reports = import::import_decks(store, decks, history);
+        writeln!(
+            out,
+            "Imported {} cards into {} stacks",
+            reports.iter().map(|(_, report)| report.added).sum::<usize>(),
+            reports.len()
+        )
+        .map_err(error)?;
+        for (name, report) in &reports {
+            writeln!(out, "{}: {} added, {} skipped", name, report.added, report.skipped.len()).map_err(error)?;
+            skipped(report, &format!("{} card", name)).map_err(error)?;
+        }
+        return Ok(());
+    }
+
+    let content = read(&path)?;
+    let delimiter = import::csv::detect_delimiter(&path, &content);
+    let rows = import::csv::parse(&content, delimiter).map_err(|err| format!("Can't parse {}: {}", path, err))?;
+    let has_header = import::csv::detect_header(&rows);
+    let columns = import::csv::default_columns(&rows, has_header);
+    let name = stack.unwrap_or(default_name);
+    let target = target(store, &name);
+    let report = import::csv::import(store, &target, &rows, &columns, has_header);
+    writeln!(out, "Imported {} cards into {}, skipped {} rows", report.added, name, report.skipped.len()).map_err(error)?;
+    skipped(&report, "row").map_err(error)
+}
+
+// Print what was left out of an import on stderr
+fn skipped(report: &Report, what: &str) -> io::Result<()> {
+    for skipped in &report.skipped {
+        writeln!(io::stderr(), "{} {}: {}", what, skipped.row, skipped.reason)?;
+    }
+
+    Ok(())
+}
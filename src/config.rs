@@ -1,71 +1,595 @@
+use crate::keymap::Keymap;
+use crate::theme;
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::fmt;
 use std::fs;
-use std::io::Write;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-const CONFIG_DIR: &str = ".config/cards/";
+// Files follow the XDG base directories:
+//
+// - config: $XDG_CONFIG_HOME/cards/, or ~/.config/cards/, with config.toml
+// - data: $XDG_DATA_HOME/cards/, or ~/.local/share/cards/, with the database
+// - state: $XDG_STATE_HOME/cards/, or ~/.local/state/cards/, with the backups
+//
+// Profiles other than the default one use a profiles/<name>/ directory in each.
+// Without HOME and the XDG variables they fall back to a directory in /tmp.
 
+// Settings of the config file, see SETTINGS for what they mean
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub db_file: String,
+    pub backups: bool,
+    pub export_dir: String,
+    pub stack_sort: String,
+    pub theme: String,
     pub highlight_color: u8,
+    // Colors replacing those of the theme, see theme
+    pub colors: toml::Table,
+    // Key bindings, see keymap
+    pub keys: toml::Table,
 }
 
-pub fn init() {
-    let home_dir: String = env::var("HOME").unwrap();
-    if std::path::Path::new(format!("{}/{}", home_dir, CONFIG_DIR).as_str()).exists() {
-    } else {
-        fs::create_dir(format!("{}/{}", home_dir, CONFIG_DIR)).unwrap();
-        let mut file = fs::File::create(format!("{}/{}{}", home_dir, CONFIG_DIR, "config.json")).unwrap();
-        file.write(b"{\n \"db_file\": \".config/cards/cards.db\",\n \"highlight_color\": 4\n}").unwrap();
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            db_file: get_profile_dir(Dir::Data).join("cards.db").to_string_lossy().to_string(),
+            backups: true,
+            export_dir: String::new(),
+            stack_sort: "created".to_string(),
+            theme: "default".to_string(),
+            highlight_color: DEFAULT_HIGHLIGHT_COLOR,
+            colors: toml::Table::new(),
+            keys: toml::Table::new(),
+        }
     }
 }
 
-pub fn get_db_file() -> String {
-    let home_dir: String = env::var("HOME").unwrap();
-    let json: String = fs::read_to_string(format!("{}/{}{}", home_dir, CONFIG_DIR, "config.json")).unwrap();
-    let config: Config = serde_json::from_str(json.as_str()).unwrap_or(
-        Config {
-            db_file: format!("{}/{}{}", home_dir, CONFIG_DIR, "cards.db"),
-            highlight_color: 7
+// Kind of value of a setting, deciding how it is checked and edited
+pub enum Kind {
+    Text,
+    Number { min: i64, max: i64 },
+    // Terminal color number
+    Color,
+    Bool,
+    Choice(&'static [&'static str]),
+}
+
+// Setting of the config file, shown in the settings window
+pub struct Setting {
+    pub key: &'static str,
+    pub section: &'static str,
+    pub help: &'static str,
+    pub kind: Kind,
+    // Check of a value beyond its kind
+    pub check: Option<Check>,
+}
+
+pub type Check = fn(&str) -> Result<(), String>;
+
+// Settings in the order of the config file and the settings window, new settings
+// need a field in Config and an entry here
+pub const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "db_file",
+        section: "Collection",
+        help: "Database file, absolute, starting with ~/ or relative to the home directory. CARDS_DB and --db take precedence.",
+        kind: Kind::Text,
+        check: Some(check_db_file),
+    },
+    Setting {
+        key: "backups",
+        section: "Collection",
+        help: "Back up the database when cards starts and after every 50 changes.",
+        kind: Kind::Bool,
+        check: None,
+    },
+    Setting {
+        key: "export_dir",
+        section: "Collection",
+        help: "Directory of the cards exported from the card list, absolute, starting with ~/ or relative to the home directory. Empty for exports/ in the data directory.",
+        kind: Kind::Text,
+        check: None,
+    },
+    Setting {
+        key: "stack_sort",
+        section: "Appearance",
+        help: "Order of the stacks, by creation or newest first. r switches it for a session.",
+        kind: Kind::Choice(&["created", "recent"]),
+        check: None,
+    },
+    Setting {
+        key: "theme",
+        section: "Appearance",
+        help: "Colors of the ui: default for dark terminals, light, high-contrast or mono without colors. NO_COLOR picks mono.",
+        kind: Kind::Choice(theme::THEMES),
+        check: None,
+    },
+    Setting {
+        key: "highlight_color",
+        section: "Appearance",
+        help: "Color of the selected window in every theme but mono, a terminal color number from 0 to 255.",
+        kind: Kind::Color,
+        check: None,
+    },
+];
+
+pub fn setting(key: &str) -> Option<&'static Setting> {
+    SETTINGS.iter().find(|setting| setting.key == key)
+}
+
+// Environment variables overriding the config file and the database, also set by
+// the --config and --db options
+pub const CONFIG_VAR: &str = "CARDS_CONFIG";
+pub const DB_VAR: &str = "CARDS_DB";
+// Profile to use instead of the one picked with `cards profile`, also set by --profile
+pub const PROFILE_VAR: &str = "CARDS_PROFILE";
+
+// Profile using the base directories themselves
+pub const DEFAULT_PROFILE: &str = "default";
+
+const CONFIG_FILE: &str = "config.toml";
+// Config file of older versions, migrated to CONFIG_FILE
+const JSON_CONFIG_FILE: &str = "config.json";
+
+// Create the config file of the profile in use, migrating a JSON config
+pub fn init() -> Result<(), String> {
+    // The database is created on first use, its directory must be there for checks
+    let _ = fs::create_dir_all(get_profile_dir(Dir::Data));
+
+    let config_file = PathBuf::from(get_config_file());
+    if config_file.exists() {
+        return Ok(());
+    }
+    let failed = |err: io::Error| format!("Could not create the config file {}: {}", config_file.display(), err);
+    if let Some(dir) = config_file.parent() {
+        fs::create_dir_all(dir).map_err(failed)?;
+    }
+
+    let json_file = config_file.with_file_name(JSON_CONFIG_FILE);
+    let config = match fs::read_to_string(&json_file).map(|json| serde_json::from_str::<Config>(&json)) {
+        // Keep the JSON file around under another name, so nothing reads it again
+        Ok(Ok(config)) if var(CONFIG_VAR).is_none() => {
+            fs::rename(&json_file, json_file.with_extension("json.old")).map_err(failed)?;
+            config
         }
-    ); 
+        _ => Config::default(),
+    };
+    write(&config_file, &config).map_err(failed)
+}
+
+// Value of an environment variable, if set and not empty
+fn var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn home_dir() -> Option<PathBuf> {
+    var("HOME").map(PathBuf::from)
+}
+
+// Directory used when neither HOME nor an XDG variable is set
+fn fallback_dir() -> PathBuf {
+    env::temp_dir().join("cards")
+}
+
+// Warning to show when files go to the fallback directory
+pub fn home_warning() -> Option<String> {
+    match home_dir() {
+        Some(_) => None,
+        None => Some(format!(
+            "HOME is not set, using {} for files not placed by XDG_CONFIG_HOME, XDG_DATA_HOME or XDG_STATE_HOME",
+            fallback_dir().display()
+        )),
+    }
+}
 
-    if std::path::Path::new(format!("{}/{}", &home_dir, &config.db_file).as_str()).exists() {
-        format!("{}/{}", &home_dir, &config.db_file)
-    } else {
-        format!("{}/{}{}", &home_dir, CONFIG_DIR, "cards.db")
+// Base directories
+#[derive(Clone, Copy)]
+enum Dir {
+    Config,
+    Data,
+    State,
+}
+
+// Base directory of cards, XDG variables with relative paths are ignored like the spec says
+fn base_dir(dir: Dir) -> PathBuf {
+    let (name, default) = match dir {
+        Dir::Config => ("XDG_CONFIG_HOME", ".config"),
+        Dir::Data => ("XDG_DATA_HOME", ".local/share"),
+        Dir::State => ("XDG_STATE_HOME", ".local/state"),
+    };
+    match (var(name).map(PathBuf::from).filter(|path| path.is_absolute()), home_dir()) {
+        (Some(path), _) => path.join("cards"),
+        (None, home) => home.unwrap_or_else(fallback_dir).join(default).join("cards"),
     }
 }
 
-pub fn get_db_file_raw() -> String {
-    let home_dir: String = env::var("HOME").unwrap();
-    let json: String = fs::read_to_string(format!("{}/{}{}", home_dir, CONFIG_DIR, "config.json")).unwrap();
-    let config: Config = serde_json::from_str(json.as_str()).unwrap(); 
-    
-    config.db_file
+// Directory of the profile in use
+fn get_profile_dir(dir: Dir) -> PathBuf {
+    match get_profile().as_str() {
+        DEFAULT_PROFILE => base_dir(dir),
+        profile => base_dir(dir).join("profiles").join(profile),
+    }
+}
+
+// Name of the profile in use
+pub fn get_profile() -> String {
+    if let Some(profile) = var(PROFILE_VAR) {
+        return profile;
+    }
+    match fs::read_to_string(base_dir(Dir::Config).join("profile")) {
+        Ok(profile) if !profile.trim().is_empty() => profile.trim().to_string(),
+        _ => DEFAULT_PROFILE.to_string(),
+    }
+}
+
+// Pick the profile used from now on, creating it if needed
+pub fn set_profile(profile: &str) -> Result<(), String> {
+    check_profile(profile)?;
+    let dir = base_dir(Dir::Config);
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    fs::write(dir.join("profile"), profile).map_err(|err| err.to_string())?;
+    env::set_var(PROFILE_VAR, profile);
+    init()
+}
+
+// Profile names are used as directory names
+pub fn check_profile(profile: &str) -> Result<(), String> {
+    let valid = profile.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    match !profile.is_empty() && valid {
+        true => Ok(()),
+        false => Err(format!(
+            "Invalid profile name \"{}\", use letters, numbers, \"-\" and \"_\"",
+            profile
+        )),
+    }
+}
+
+// Names of the profiles, the default one first
+pub fn get_profiles() -> Vec<String> {
+    let mut profiles: Vec<String> = fs::read_dir(base_dir(Dir::Config).join("profiles"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    profiles
+}
+
+pub fn get_config_file() -> String {
+    var(CONFIG_VAR).unwrap_or_else(|| get_profile_dir(Dir::Config).join(CONFIG_FILE).to_string_lossy().to_string())
+}
+
+// Problem with the config file or one of its settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub file: String,
+    // Setting with the problem, None for the whole file
+    pub key: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "{}: {}: {}", self.file, key, self.reason),
+            None => write!(f, "{}: {}", self.file, self.reason),
+        }
+    }
+}
+
+pub const DEFAULT_HIGHLIGHT_COLOR: u8 = 4;
+
+// Check a db_file setting, the database is created if missing but not its directory
+pub fn check_db_file(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    let path = resolve(value);
+    if path.is_dir() {
+        return Err(format!("{} is a directory", path.display()));
+    }
+    match path.parent() {
+        Some(dir) if !dir.is_dir() => Err(format!("directory {} does not exist", dir.display())),
+        _ => Ok(()),
+    }
+}
+
+// Check a highlight_color setting, a terminal color number
+pub fn check_highlight_color(value: &str) -> Result<u8, String> {
+    number(value, 0, 255).map(|color| color as u8)
+}
+
+// Number typed in a setting
+fn number(text: &str, min: i64, max: i64) -> Result<i64, String> {
+    match text.trim().parse::<i64>() {
+        Ok(number) if (min..=max).contains(&number) => Ok(number),
+        Ok(number) => Err(format!("must be from {} to {}, got {}", min, max, number)),
+        Err(_) => Err(format!("must be a number from {} to {}, got \"{}\"", min, max, text)),
+    }
+}
+
+// Value of a setting typed in the settings window
+pub fn parse_setting(setting: &Setting, text: &str) -> Result<toml::Value, String> {
+    let value = match &setting.kind {
+        Kind::Text => toml::Value::String(text.trim().to_string()),
+        Kind::Number { min, max } => toml::Value::Integer(number(text, *min, *max)?),
+        Kind::Color => toml::Value::Integer(number(text, 0, 255)?),
+        Kind::Bool => match text.trim() {
+            "true" => toml::Value::Boolean(true),
+            "false" => toml::Value::Boolean(false),
+            _ => return Err(format!("must be true or false, got \"{}\"", text)),
+        },
+        Kind::Choice(choices) => match choices.contains(&text.trim()) {
+            true => toml::Value::String(text.trim().to_string()),
+            false => return Err(format!("must be one of {}, got \"{}\"", choices.join(", "), text)),
+        },
+    };
+    if let Some(check) = setting.check {
+        check(text)?;
+    }
+    Ok(value)
+}
+
+// Check a value read from the config file
+fn check_setting(setting: &Setting, value: &toml::Value) -> Result<toml::Value, String> {
+    let expected = match &setting.kind {
+        Kind::Text => "a text".to_string(),
+        Kind::Number { min, max } => format!("a number from {} to {}", min, max),
+        Kind::Color => "a number from 0 to 255".to_string(),
+        Kind::Bool => "true or false".to_string(),
+        Kind::Choice(choices) => format!("one of {}", choices.join(", ")),
+    };
+    match (&setting.kind, value) {
+        (Kind::Text | Kind::Choice(_), toml::Value::String(text)) => parse_setting(setting, text),
+        (Kind::Number { .. } | Kind::Color, toml::Value::Integer(number)) => parse_setting(setting, &number.to_string()),
+        (Kind::Bool, toml::Value::Boolean(_)) => Ok(value.clone()),
+        _ => Err(format!("must be {}, got {}", expected, value)),
+    }
+}
+
+// Settings of a config by key
+fn to_table(config: &Config) -> Result<toml::Table, String> {
+    toml::Table::try_from(config).map_err(|err| err.to_string())
+}
+
+// Value of a setting as typed in the settings window
+pub fn setting_text(config: &Config, key: &str) -> Result<String, String> {
+    Ok(match to_table(config)?.get(key) {
+        Some(toml::Value::String(text)) => text.to_string(),
+        Some(value) => value.to_string(),
+        None => String::new(),
+    })
+}
+
+// Config with settings replaced by values, the values must be checked
+fn with_values(config: &Config, values: toml::Table) -> Result<Config, String> {
+    let mut table = to_table(config)?;
+    table.extend(values);
+    table.try_into().map_err(|err: toml::de::Error| err.message().to_string())
+}
+
+// Read the config file, settings with errors keep their default value
+pub fn load() -> (Config, Vec<ConfigError>) {
+    load_file(Path::new(&get_config_file()))
+}
+
+// Read a config file, settings with errors keep their default value
+pub fn load_file(path: &Path) -> (Config, Vec<ConfigError>) {
+    let error = |key: Option<&str>, reason: String| ConfigError {
+        file: path.display().to_string(),
+        key: key.map(|key| key.to_string()),
+        reason,
+    };
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => return (Config::default(), vec![error(None, format!("could not be read: {}", err))]),
+    };
+    let settings = match parse(path, &content) {
+        Ok(settings) => settings,
+        Err(reason) => return (Config::default(), vec![error(None, reason)]),
+    };
+
+    let mut values = toml::Table::new();
+    let mut errors = vec![];
+    for (key, value) in settings {
+        let checked = match (setting(&key), &value) {
+            (Some(setting), _) => check_setting(setting, &value),
+            // Bindings with errors are left out by the keymap itself
+            (None, toml::Value::Table(keys)) if key == "keys" => {
+                for (key, reason) in Keymap::new(keys).1 {
+                    errors.push(error(Some(&key), reason));
+                }
+                Ok(value)
+            }
+            // Colors with errors are left out by the theme
+            (None, toml::Value::Table(colors)) if key == "colors" => {
+                for (key, reason) in theme::check_colors(colors) {
+                    errors.push(error(Some(&key), reason));
+                }
+                Ok(value)
+            }
+            (None, _) if key == "keys" || key == "colors" => Err(format!("must be a table, got {}", value)),
+            (None, _) => Err("unknown setting".to_string()),
+        };
+        match checked {
+            Ok(value) => {
+                values.insert(key, value);
+            }
+            Err(reason) => errors.push(error(Some(&key), reason)),
+        }
+    }
+
+    match with_values(&Config::default(), values) {
+        Ok(config) => (config, errors),
+        Err(reason) => {
+            errors.push(error(None, reason));
+            (Config::default(), errors)
+        }
+    }
+}
+
+// Settings of a TOML file, or a JSON one for files ending with .json, errors give the line
+fn parse(path: &Path, content: &str) -> Result<toml::Table, String> {
+    match is_json(path) {
+        true => serde_json::from_str::<toml::Table>(content)
+            .map_err(|err| format!("invalid JSON, {}", err)),
+        false => content.parse::<toml::Table>().map_err(|err| {
+            let line = match err.span() {
+                Some(span) => content[..span.start].lines().count().max(1),
+                None => 1,
+            };
+            format!("line {}: invalid TOML, {}", line, err.message())
+        }),
+    }
+}
+
+fn write(path: &Path, config: &Config) -> io::Result<()> {
+    let content = match is_json(path) {
+        true => serde_json::to_string_pretty(config).map_err(io::Error::from)?,
+        false => to_toml(config).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+    };
+    let mut file = fs::File::create(path)?;
+    file.write_all(content.as_bytes())
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+// TOML config with the help of each setting as comment
+fn to_toml(config: &Config) -> Result<String, String> {
+    let table = to_table(config)?;
+    let mut out = "# Config file for cards.rs\n".to_string();
+    let mut section = "";
+    for setting in SETTINGS {
+        if setting.section != section {
+            section = setting.section;
+            out.push_str(&format!("\n# {}\n", section));
+        }
+        out.push('\n');
+        for line in wrap(setting.help, 78) {
+            out.push_str(&format!("# {}\n", line));
+        }
+        if let Some(value) = table.get(setting.key) {
+            out.push_str(&format!("{} = {}\n", setting.key, value));
+        }
+    }
+    for (key, comment, value) in [("colors", "Colors", &config.colors), ("keys", "Key bindings", &config.keys)] {
+        if value.is_empty() {
+            continue;
+        }
+        let mut tables = toml::Table::new();
+        tables.insert(key.to_string(), toml::Value::Table(value.clone()));
+        out.push_str(&format!("\n# {}\n\n", comment));
+        out.push_str(&toml::to_string(&tables).map_err(|err| err.to_string())?);
+    }
+    Ok(out)
+}
+
+// Words of a text in lines of at most width characters
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+// Path of a file setting, ~/ and relative paths are in the home directory
+fn resolve(db_file: &str) -> PathBuf {
+    let home = home_dir().unwrap_or_else(fallback_dir);
+    match db_file.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => home.join(db_file),
+    }
+}
+
+pub fn get_db_file() -> String {
+    if let Some(db_file) = var(DB_VAR) {
+        return db_file;
+    }
+    let default = get_profile_dir(Dir::Data).join("cards.db");
+    if let Some(dir) = default.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    // The database is created in the directory of db_file if it isn't there yet
+    let db_file = load().0.db_file;
+    let path = resolve(&db_file);
+    match !db_file.is_empty() && path.parent().is_some_and(|dir| dir.is_dir()) {
+        true => path,
+        false => default,
+    }
+    .to_string_lossy()
+    .to_string()
 }
 
 pub fn get_highlight_color() -> u8 {
-    let home_dir: String = env::var("HOME").unwrap();
-    let json: String = fs::read_to_string(format!("{}/{}{}", home_dir, CONFIG_DIR, "config.json")).unwrap();
-    let config: Config = serde_json::from_str(json.as_str()).unwrap_or(Config {
-        db_file: "".to_string(),
-        highlight_color: 7
-    }); 
-
-    config.highlight_color
-}
-
-pub fn set_config(db_file: String, highlight_color: u8) {
-   let config = Config {
-       db_file,
-       highlight_color,
-   }; 
-
-   let home_dir: String = env::var("HOME").unwrap();
-   let json = serde_json::to_string_pretty(&config).unwrap();
-   fs::remove_file(format!("{}/{}{}", home_dir, CONFIG_DIR, "config.json")).unwrap();
-   let mut file = fs::File::create(format!("{}/{}{}", home_dir, CONFIG_DIR, "config.json")).unwrap();
-   file.write(json.as_bytes()).unwrap();
+    load().0.highlight_color
+}
+
+// Save settings typed in the settings window, nothing is saved when one is invalid
+pub fn set_config(values: &[(&str, &str)]) -> Result<(), Vec<ConfigError>> {
+    set_config_file(Path::new(&get_config_file()), values)
+}
+
+// Save settings to a config file, nothing is saved when one is invalid
+pub fn set_config_file(file: &Path, values: &[(&str, &str)]) -> Result<(), Vec<ConfigError>> {
+    let error = |key: Option<&str>, reason: String| ConfigError {
+        file: file.display().to_string(),
+        key: key.map(|key| key.to_string()),
+        reason,
+    };
+
+    let mut checked = toml::Table::new();
+    let mut errors = vec![];
+    for (key, text) in values {
+        let value = match setting(key) {
+            Some(setting) => parse_setting(setting, text),
+            None => Err("unknown setting".to_string()),
+        };
+        match value {
+            Ok(value) => {
+                checked.insert(key.to_string(), value);
+            }
+            Err(reason) => errors.push(error(Some(key), reason)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let config = with_values(&load_file(file).0, checked).map_err(|reason| vec![error(None, reason)])?;
+    write(file, &config).map_err(|err| vec![error(None, format!("could not be saved: {}", err))])
+}
+
+pub fn get_export_dir() -> PathBuf {
+    match load().0.export_dir.as_str() {
+        "" => get_profile_dir(Dir::Data).join("exports"),
+        export_dir => resolve(export_dir),
+    }
+}
+
+pub fn get_backup_dir() -> String {
+    // A database given with CARDS_DB keeps its backups next to it, away from
+    // the backups of the profile that would rotate them out
+    if let Some(db_file) = var(DB_VAR) {
+        return format!("{}.backups", db_file);
+    }
+    get_profile_dir(Dir::State).join("backups").to_string_lossy().to_string()
 }
@@ -6,6 +6,88 @@ pub mod stack;
 // Card module
 pub mod card;
 
+// Review module
+pub mod review;
+
+// SQLite store
+pub mod sqlite;
+
+// In-memory store
+pub mod memory;
+
+use card::{Card, Schedule};
+use review::Review;
+use stack::Stack;
+
+// Storage backend used by the app
+pub trait Store {
+    // Get all stacks
+    fn stacks(&self) -> Vec<Stack>;
+
+    // Add stack and return its id
+    fn add_stack(&mut self, name: String) -> i32;
+
+    // Delete stack with all of its cards
+    fn delete_stack(&mut self, id: i32);
+
+    // Edit stack
+    fn edit_stack(&mut self, id: i32, name: String);
+
+    // List cards of a stack
+    fn cards(&self, stack_id: i32) -> Vec<Card>;
+
+    // Add card and return its id
+    fn add_card(&mut self, stack_id: i32, title: String, text: String) -> i32;
+
+    // Add many cards in one go and return their ids
+    fn add_cards(&mut self, stack_id: i32, cards: Vec<(String, String)>) -> Vec<i32>;
+
+    // Delete card
+    fn delete_card(&mut self, id: i32);
+
+    // Delete many cards in one go
+    fn delete_cards(&mut self, ids: &[i32]);
+
+    // Edit card
+    fn edit_card(&mut self, id: i32, title: String, text: String);
+
+    // Update stack, content, tags and schedule of many cards in one go
+    fn update_cards(&mut self, cards: &[Card]);
+
+    // Set scheduling state of a card
+    fn set_schedule(&mut self, id: i32, schedule: &Schedule);
+
+    // Record a review of a card and return its id
+    fn add_review(&mut self, card_id: i32, grade: u8, time: i64) -> i32;
+
+    // Record many reviews given as (card id, grade, time) in one go
+    fn add_reviews(&mut self, reviews: &[(i32, u8, i64)]);
+
+    // List reviews of a card, oldest first
+    fn reviews(&self, card_id: i32) -> Vec<Review>;
+
+    // Replace all data with a backup file
+    fn restore(&mut self, path: &str) -> std::io::Result<()>;
+
+    // Start a transaction, changes until commit are saved together
+    fn begin(&mut self);
+
+    // Save the changes since begin
+    fn commit(&mut self);
+}
+
+// Run changes in a transaction unless one was already started
+pub fn batch<T>(conn: &Connection, changes: impl FnOnce(&Connection) -> T) -> T {
+    if !conn.is_autocommit() {
+        return changes(conn);
+    }
+    let tx = conn.unchecked_transaction().unwrap();
+    let result = changes(&tx);
+    tx.commit().unwrap();
+
+    result
+}
+
 // Return connection
 pub fn init(path: &str) -> Result<Connection, rusqlite::Error> {
     let conn = connect_db(path)?;
@@ -15,7 +97,34 @@ pub fn init(path: &str) -> Result<Connection, rusqlite::Error> {
 // Connect to db if exists or create db with tables
 fn connect_db(path: &str) -> Result<Connection, Error> {
     let conn = Connection::open(path)?;
+    conn.execute_batch("PRAGMA foreign_keys = ON")?;
     conn.execute("CREATE TABLE IF NOT EXISTS stack (id    INTEGER PRIMARY KEY, name    TEXT NOT NULL)", ())?;
     conn.execute("CREATE TABLE IF NOT EXISTS card (id    INTEGER PRIMARY KEY, title    TEXT NOT NULL, text    TEXT NOT NULL, stack_id   INTEGER NOT NULL, FOREIGN KEY(stack_id) REFERENCES stack(id) ON DELETE CASCADE)", ())?;
+    conn.execute("CREATE TABLE IF NOT EXISTS review (id    INTEGER PRIMARY KEY, card_id    INTEGER NOT NULL, grade    INTEGER NOT NULL, time    INTEGER NOT NULL, FOREIGN KEY(card_id) REFERENCES card(id) ON DELETE CASCADE)", ())?;
+    add_column(&conn, "stack", "created_at", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "stack", "updated_at", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "card", "created_at", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "card", "updated_at", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "card", "tags", "TEXT NOT NULL DEFAULT ''")?;
+    add_column(&conn, "card", "suspended", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "card", "due", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "card", "interval", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "card", "ease", "INTEGER NOT NULL DEFAULT 2500")?;
+    add_column(&conn, "card", "reps", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(&conn, "card", "lapses", "INTEGER NOT NULL DEFAULT 0")?;
+    conn.execute("CREATE INDEX IF NOT EXISTS card_stack_id ON card (stack_id)", ())?;
+    conn.execute("CREATE INDEX IF NOT EXISTS review_card_id ON review (card_id)", ())?;
     Ok(conn)
 }
+
+// Add column to a table of an older db if it is missing
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), Error> {
+    let mut columns = conn.prepare(format!("PRAGMA table_info({})", table).as_str())?;
+    let exists = columns
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|name| name.map(|name| name == column).unwrap_or(false));
+    if !exists {
+        conn.execute(format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition).as_str(), ())?;
+    }
+    Ok(())
+}
@@ -1,82 +1,163 @@
+use crate::db::batch;
+use crate::time;
 use rusqlite::Connection;
+use serde::Serialize;
 
 // Card Struct
+#[derive(Clone, Serialize)]
 pub struct Card {
     pub id: i32,
     pub title: String,
     pub text: String,
     pub stack_id: i32,
+    pub created_at: i64,
+    pub updated_at: i64,
+    // Space separated tags
+    pub tags: String,
+    // Suspended cards are left out of revisions
+    pub suspended: bool,
+    pub schedule: Schedule,
+}
+
+impl Card {
+    // Tags of the card as a list
+    pub fn tag_list(&self) -> Vec<&str> {
+        self.tags.split_whitespace().collect()
+    }
+
+    // Add tag if the card doesn't have it yet
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.tag_list().contains(&tag) {
+            let mut tags = self.tag_list();
+            tags.push(tag);
+            self.tags = tags.join(" ");
+        }
+    }
+
+    // Remove tag from the card
+    pub fn remove_tag(&mut self, tag: &str) {
+        let tags: Vec<&str> = self.tag_list().into_iter().filter(|i| *i != tag).collect();
+        self.tags = tags.join(" ");
+    }
+}
+
+// Scheduling state of a card
+#[derive(Clone, Serialize)]
+pub struct Schedule {
+    // Unix time the card is due, 0 for new cards
+    pub due: i64,
+    // Days between reviews
+    pub interval: i32,
+    // Ease factor in permille, lower is more difficult
+    pub ease: i32,
+    pub reps: i32,
+    pub lapses: i32,
+}
+
+impl Default for Schedule {
+    fn default() -> Schedule {
+        Schedule {
+            due: 0,
+            interval: 0,
+            ease: 2500,
+            reps: 0,
+            lapses: 0,
+        }
+    }
 }
 
 // Add card
-pub fn add(conn: &Connection, stack_id: i32, title: String, text: String) {
-    let card = Card {
-        id: 0,
-        title,
-        text,
-        stack_id,
-    };
-
-    conn.execute(
-        "INSERT INTO card (title, text, stack_id) VALUES (?1, ?2, ?3)",
-        (&card.title, &card.text, &card.stack_id)
-    ).unwrap();
+pub fn add(conn: &Connection, stack_id: i32, title: String, text: String) -> i32 {
+    let now = time::now();
+    conn.prepare_cached("INSERT INTO card (title, text, stack_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)")
+        .unwrap()
+        .execute((&title, &text, &stack_id, &now))
+        .unwrap();
+
+    conn.last_insert_rowid() as i32
+}
+
+// Add many cards in one transaction
+pub fn add_many(conn: &Connection, stack_id: i32, cards: Vec<(String, String)>) -> Vec<i32> {
+    batch(conn, |tx| {
+        cards
+            .into_iter()
+            .map(|(title, text)| add(tx, stack_id, title, text))
+            .collect()
+    })
 }
 
 // List cards
 pub fn list(conn: &Connection, stack_id: i32) -> Vec<Card> {
-    let mut raw_cards = conn.prepare(format!("SELECT * FROM card WHERE stack_id={}", stack_id).as_str()).unwrap();
-    let card_result = raw_cards.query_map([], |row| {
+    let mut raw_cards = conn
+        .prepare_cached("SELECT id, title, text, stack_id, created_at, updated_at, tags, suspended, due, interval, ease, reps, lapses FROM card WHERE stack_id=(?1) ORDER BY id")
+        .unwrap();
+    let card_result = raw_cards.query_map((&stack_id, ), |row| {
         Ok(Card {
             id: row.get(0)?,
             title: row.get(1)?,
             text: row.get(2)?,
             stack_id: row.get(3)?,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+            tags: row.get(6)?,
+            suspended: row.get(7)?,
+            schedule: Schedule {
+                due: row.get(8)?,
+                interval: row.get(9)?,
+                ease: row.get(10)?,
+                reps: row.get(11)?,
+                lapses: row.get(12)?,
+            },
         })
     }).unwrap();
-    let mut cards = Vec::new();
-    for card in card_result {
-        let i_unwraped = card.unwrap();
-        let i: Card = Card {
-            id: i_unwraped.id,
-            title: i_unwraped.title,
-            text: i_unwraped.text,
-            stack_id: i_unwraped.stack_id,
-        };
-        cards.push(i);
-    }
 
-    cards
+    card_result.map(|card| card.unwrap()).collect()
 }
 
 // Delete card
 pub fn delete(conn: &Connection, id: i32) {
-    let card = Card {
-        id,
-        text: String::new(),
-        title: String::new(),
-        stack_id: 0,
-    };
-
-    conn.execute(
-        "DELETE FROM card WHERE id=(?1)",
-        (&card.id, )
-    )
-        .unwrap(); 
+    conn.prepare_cached("DELETE FROM card WHERE id=(?1)")
+        .unwrap()
+        .execute((&id, ))
+        .unwrap();
+}
+
+// Delete many cards in one transaction
+pub fn delete_many(conn: &Connection, ids: &[i32]) {
+    batch(conn, |tx| {
+        for id in ids {
+            delete(tx, *id);
+        }
+    })
+}
+
+// Set scheduling state of a card
+pub fn set_schedule(conn: &Connection, id: i32, schedule: &Schedule) {
+    conn.prepare_cached("UPDATE card SET due=(?1), interval=(?2), ease=(?3), reps=(?4), lapses=(?5) WHERE id=(?6)")
+        .unwrap()
+        .execute((&schedule.due, &schedule.interval, &schedule.ease, &schedule.reps, &schedule.lapses, &id))
+        .unwrap();
+}
+
+// Update stack, content, tags and schedule of many cards in one transaction
+pub fn update_many(conn: &Connection, cards: &[Card]) {
+    let now = time::now();
+    batch(conn, |tx| {
+        for card in cards {
+            tx.prepare_cached("UPDATE card SET title=(?1), text=(?2), stack_id=(?3), tags=(?4), suspended=(?5), updated_at=(?6) WHERE id=(?7)")
+                .unwrap()
+                .execute((&card.title, &card.text, &card.stack_id, &card.tags, &card.suspended, &now, &card.id))
+                .unwrap();
+            set_schedule(tx, card.id, &card.schedule);
+        }
+    })
 }
 
 // Edit card
 pub fn edit(conn: &Connection, id: i32, title: String, text: String) {
-    let card = Card {
-        id,
-        title,
-        text,
-        stack_id: 0,
-    };
-
-    conn.execute(
-        "UPDATE card SET title=(?1), text=(?2) WHERE id=(?3)",
-        (&card.title, &card.text, &card.id)
-    )
+    conn.prepare_cached("UPDATE card SET title=(?1), text=(?2), updated_at=(?3) WHERE id=(?4)")
+        .unwrap()
+        .execute((&title, &text, &time::now(), &id))
         .unwrap();
 }
@@ -0,0 +1,172 @@
+use crate::db::card::{Card, Schedule};
+use crate::db::review::Review;
+use crate::db::stack::Stack;
+use crate::db::Store;
+use crate::time;
+
+// Store that keeps everything in memory
+#[derive(Default)]
+pub struct MemoryStore {
+    stacks: Vec<Stack>,
+    cards: Vec<Card>,
+    reviews: Vec<Review>,
+    last_id: i32,
+}
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+
+    // Next free id, shared by all tables
+    fn next_id(&mut self) -> i32 {
+        self.last_id += 1;
+        self.last_id
+    }
+}
+
+impl Store for MemoryStore {
+    fn stacks(&self) -> Vec<Stack> {
+        self.stacks.clone()
+    }
+
+    fn add_stack(&mut self, name: String) -> i32 {
+        let id = self.next_id();
+        let now = time::now();
+        self.stacks.push(Stack {
+            id,
+            name,
+            created_at: now,
+            updated_at: now,
+        });
+        id
+    }
+
+    fn delete_stack(&mut self, id: i32) {
+        let card_ids: Vec<i32> = self
+            .cards
+            .iter()
+            .filter(|card| card.stack_id == id)
+            .map(|card| card.id)
+            .collect();
+        self.reviews.retain(|review| !card_ids.contains(&review.card_id));
+        self.cards.retain(|card| card.stack_id != id);
+        self.stacks.retain(|stack| stack.id != id);
+    }
+
+    fn edit_stack(&mut self, id: i32, name: String) {
+        if let Some(stack) = self.stacks.iter_mut().find(|stack| stack.id == id) {
+            stack.name = name;
+            stack.updated_at = time::now();
+        }
+    }
+
+    fn cards(&self, stack_id: i32) -> Vec<Card> {
+        self.cards
+            .iter()
+            .filter(|card| card.stack_id == stack_id)
+            .cloned()
+            .collect()
+    }
+
+    fn add_card(&mut self, stack_id: i32, title: String, text: String) -> i32 {
+        let id = self.next_id();
+        let now = time::now();
+        self.cards.push(Card {
+            id,
+            title,
+            text,
+            stack_id,
+            created_at: now,
+            updated_at: now,
+            tags: String::new(),
+            suspended: false,
+            schedule: Schedule::default(),
+        });
+        id
+    }
+
+    fn add_cards(&mut self, stack_id: i32, cards: Vec<(String, String)>) -> Vec<i32> {
+        cards
+            .into_iter()
+            .map(|(title, text)| self.add_card(stack_id, title, text))
+            .collect()
+    }
+
+    fn delete_card(&mut self, id: i32) {
+        self.reviews.retain(|review| review.card_id != id);
+        self.cards.retain(|card| card.id != id);
+    }
+
+    fn delete_cards(&mut self, ids: &[i32]) {
+        self.reviews.retain(|review| !ids.contains(&review.card_id));
+        self.cards.retain(|card| !ids.contains(&card.id));
+    }
+
+    fn edit_card(&mut self, id: i32, title: String, text: String) {
+        if let Some(card) = self.cards.iter_mut().find(|card| card.id == id) {
+            card.title = title;
+            card.text = text;
+            card.updated_at = time::now();
+        }
+    }
+
+    fn update_cards(&mut self, cards: &[Card]) {
+        let now = time::now();
+        for updated in cards {
+            if let Some(card) = self.cards.iter_mut().find(|card| card.id == updated.id) {
+                *card = Card {
+                    created_at: card.created_at,
+                    updated_at: now,
+                    ..updated.clone()
+                };
+            }
+        }
+    }
+
+    fn set_schedule(&mut self, id: i32, schedule: &Schedule) {
+        if let Some(card) = self.cards.iter_mut().find(|card| card.id == id) {
+            card.schedule = schedule.clone();
+        }
+    }
+
+    fn add_review(&mut self, card_id: i32, grade: u8, time: i64) -> i32 {
+        let id = self.next_id();
+        self.reviews.push(Review {
+            id,
+            card_id,
+            grade,
+            time,
+        });
+        id
+    }
+
+    fn add_reviews(&mut self, reviews: &[(i32, u8, i64)]) {
+        for (card_id, grade, time) in reviews {
+            self.add_review(*card_id, *grade, *time);
+        }
+    }
+
+    fn restore(&mut self, _path: &str) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "memory stores have no backups",
+        ))
+    }
+
+    fn reviews(&self, card_id: i32) -> Vec<Review> {
+        let mut reviews: Vec<Review> = self
+            .reviews
+            .iter()
+            .filter(|review| review.card_id == card_id)
+            .cloned()
+            .collect();
+        reviews.sort_by_key(|review| review.time);
+        reviews
+    }
+
+    // Changes to memory can't fail halfway, so there is nothing to do
+    fn begin(&mut self) {}
+
+    fn commit(&mut self) {}
+}
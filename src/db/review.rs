@@ -0,0 +1,46 @@
+use crate::db::batch;
+use rusqlite::Connection;
+use serde::Serialize;
+
+// Review struct
+#[derive(Clone, Serialize)]
+pub struct Review {
+    pub id: i32,
+    pub card_id: i32,
+    pub grade: u8,
+    pub time: i64,
+}
+
+// Add review
+pub fn add(conn: &Connection, card_id: i32, grade: u8, time: i64) -> i32 {
+    conn.prepare_cached("INSERT INTO review (card_id, grade, time) VALUES (?1, ?2, ?3)")
+        .unwrap()
+        .execute((&card_id, &grade, &time))
+        .unwrap();
+
+    conn.last_insert_rowid() as i32
+}
+
+// Add many reviews in one transaction
+pub fn add_many(conn: &Connection, reviews: &[(i32, u8, i64)]) {
+    batch(conn, |tx| {
+        for (card_id, grade, time) in reviews {
+            add(tx, *card_id, *grade, *time);
+        }
+    })
+}
+
+// List reviews of a card
+pub fn list(conn: &Connection, card_id: i32) -> Vec<Review> {
+    let mut raw_reviews = conn.prepare_cached("SELECT id, card_id, grade, time FROM review WHERE card_id=(?1) ORDER BY time").unwrap();
+    let review_result = raw_reviews.query_map((&card_id, ), |row| {
+        Ok(Review {
+            id: row.get(0)?,
+            card_id: row.get(1)?,
+            grade: row.get(2)?,
+            time: row.get(3)?,
+        })
+    }).unwrap();
+
+    review_result.map(|review| review.unwrap()).collect()
+}
@@ -0,0 +1,162 @@
+use crate::backup;
+use crate::db::card::{self, Card, Schedule};
+use crate::db::review::{self, Review};
+use crate::db::stack::{self, Stack};
+use crate::db::{init, Store};
+use rusqlite::Connection;
+use std::fs;
+use std::io;
+
+// Store backed by an SQLite database
+pub struct SqliteStore {
+    pub conn: Connection,
+    path: String,
+    // Directory of automatic backups
+    backup_dir: Option<String>,
+    // Changes since the last backup
+    changes: usize,
+}
+
+impl SqliteStore {
+    // Open or create the database at path
+    pub fn open(path: &str) -> Result<SqliteStore, rusqlite::Error> {
+        Ok(SqliteStore {
+            conn: init(path)?,
+            path: path.to_string(),
+            backup_dir: None,
+            changes: 0,
+        })
+    }
+
+    // Back up the database into dir after every few changes
+    pub fn with_backups(mut self, dir: &str) -> SqliteStore {
+        self.backup_dir = Some(dir.to_string());
+        self
+    }
+
+    // Count a change and back up the database if there were enough of them
+    fn changed(&mut self) {
+        self.changes += 1;
+        self.back_up();
+    }
+
+    // Back up the database if there were enough changes since the last backup
+    fn back_up(&mut self) {
+        if let Some(dir) = &self.backup_dir {
+            // VACUUM INTO can't run inside a transaction, so the backup waits for the commit
+            if self.changes >= backup::EVERY && self.conn.is_autocommit() {
+                self.changes = 0;
+                let _ = backup::create(&self.conn, dir);
+            }
+        }
+    }
+}
+
+impl Store for SqliteStore {
+    fn stacks(&self) -> Vec<Stack> {
+        stack::get_all(&self.conn)
+    }
+
+    fn add_stack(&mut self, name: String) -> i32 {
+        self.changed();
+        stack::add(&self.conn, name)
+    }
+
+    fn delete_stack(&mut self, id: i32) {
+        self.changed();
+        stack::delete(&self.conn, id);
+    }
+
+    fn edit_stack(&mut self, id: i32, name: String) {
+        self.changed();
+        stack::edit(&self.conn, id, name);
+    }
+
+    fn cards(&self, stack_id: i32) -> Vec<Card> {
+        card::list(&self.conn, stack_id)
+    }
+
+    fn add_card(&mut self, stack_id: i32, title: String, text: String) -> i32 {
+        self.changed();
+        card::add(&self.conn, stack_id, title, text)
+    }
+
+    fn add_cards(&mut self, stack_id: i32, cards: Vec<(String, String)>) -> Vec<i32> {
+        self.changed();
+        card::add_many(&self.conn, stack_id, cards)
+    }
+
+    fn delete_card(&mut self, id: i32) {
+        self.changed();
+        card::delete(&self.conn, id);
+    }
+
+    fn delete_cards(&mut self, ids: &[i32]) {
+        self.changed();
+        card::delete_many(&self.conn, ids);
+    }
+
+    fn edit_card(&mut self, id: i32, title: String, text: String) {
+        self.changed();
+        card::edit(&self.conn, id, title, text);
+    }
+
+    fn update_cards(&mut self, cards: &[Card]) {
+        self.changed();
+        card::update_many(&self.conn, cards);
+    }
+
+    fn set_schedule(&mut self, id: i32, schedule: &Schedule) {
+        self.changed();
+        card::set_schedule(&self.conn, id, schedule);
+    }
+
+    fn add_review(&mut self, card_id: i32, grade: u8, time: i64) -> i32 {
+        self.changed();
+        review::add(&self.conn, card_id, grade, time)
+    }
+
+    fn add_reviews(&mut self, reviews: &[(i32, u8, i64)]) {
+        self.changed();
+        review::add_many(&self.conn, reviews)
+    }
+
+    fn reviews(&self, card_id: i32) -> Vec<Review> {
+        review::list(&self.conn, card_id)
+    }
+
+    fn restore(&mut self, path: &str) -> io::Result<()> {
+        // Read the backup first since backing up the current state may rotate it out
+        let data = fs::read(path)?;
+        if let Some(dir) = &self.backup_dir {
+            backup::create(&self.conn, dir)?;
+        }
+
+        // The backup is written next to the database and renamed over it, so a
+        // failed write leaves the database as it was
+        let restored = format!("{}.restore", self.path);
+        fs::write(&restored, data)?;
+
+        // Close the database before its file is replaced, and open it again
+        // whether that worked or not
+        self.conn = Connection::open_in_memory().map_err(io::Error::other)?;
+        let replaced = fs::rename(&restored, &self.path);
+        if replaced.is_err() {
+            let _ = fs::remove_file(&restored);
+        }
+        self.conn = init(&self.path).map_err(io::Error::other)?;
+        replaced?;
+        self.changes = 0;
+
+        Ok(())
+    }
+
+    fn begin(&mut self) {
+        self.conn.execute_batch("BEGIN").unwrap();
+    }
+
+    fn commit(&mut self) {
+        self.conn.execute_batch("COMMIT").unwrap();
+        self.back_up();
+    }
+}
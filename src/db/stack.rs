@@ -1,70 +1,53 @@
+use crate::time;
 use rusqlite::Connection;
+use serde::Serialize;
 
 // Stack struct
+#[derive(Clone, Serialize)]
 pub struct Stack {
     pub id: i32,
     pub name: String,
+    pub created_at: i64,
+    pub updated_at: i64,
 }
 
 // Get all stacks
 pub fn get_all(conn: &Connection) -> Vec<Stack> {
-    let mut raw_stacks = conn.prepare("SELECT * FROM stack").unwrap();
+    let mut raw_stacks = conn.prepare_cached("SELECT id, name, created_at, updated_at FROM stack ORDER BY id").unwrap();
     let stacks_result = raw_stacks.query_map([], |row| {
         Ok(Stack {
             id: row.get(0)?,
             name: row.get(1)?,
+            created_at: row.get(2)?,
+            updated_at: row.get(3)?,
         })
     }).unwrap();
-    let mut stacks = Vec::new();
-    for stack in stacks_result {
-        let i_unwraped = stack.unwrap();
-        let i: Stack = Stack {
-            id: i_unwraped.id,
-            name: i_unwraped.name,
-        };
-        stacks.push(i);
-    }
 
-    stacks
+    stacks_result.map(|stack| stack.unwrap()).collect()
 }
 
 // Add stack 
-pub fn add(conn: &Connection, name: String) {
-    let stack = Stack {
-        name,
-        id: 0
-    };
+pub fn add(conn: &Connection, name: String) -> i32 {
+    conn.prepare_cached("INSERT INTO stack (name, created_at, updated_at) VALUES (?1, ?2, ?2)")
+        .unwrap()
+        .execute((&name, &time::now()))
+        .unwrap();
 
-    conn.execute(
-        "INSERT INTO stack (name) VALUES (?1)",
-        (&stack.name, )
-    ).unwrap();
+    conn.last_insert_rowid() as i32
 }
 
 // Delete stack 
 pub fn delete(conn: &Connection, id: i32) {
-    let stack = Stack {
-        name: String::from(""),
-        id,
-    };
-
-    conn.execute(
-        "DELETE FROM stack WHERE id=(?1)",
-        (&stack.id, )
-    )
-        .unwrap(); 
+    conn.prepare_cached("DELETE FROM stack WHERE id=(?1)")
+        .unwrap()
+        .execute((&id, ))
+        .unwrap();
 }
 
 // Edit stack
 pub fn edit(conn: &Connection, id: i32, name: String) {
-    let stack = Stack {
-       id,
-       name,
-    };
-
-    conn.execute(
-        "UPDATE stack SET name=(?1) WHERE id=(?2)",
-        (stack.name, stack.id)
-    )
+    conn.prepare_cached("UPDATE stack SET name=(?1), updated_at=(?2) WHERE id=(?3)")
+        .unwrap()
+        .execute((&name, &time::now(), &id))
         .unwrap();
 }
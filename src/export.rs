@@ -0,0 +1,125 @@
+use crate::db::card::Card;
+use crate::db::stack::Stack;
+use crate::db::Store;
+use std::fs;
+use std::path::Path;
+
+// Anki package export
+pub mod anki;
+
+// CSV and TSV export
+pub mod csv;
+
+// Static HTML export
+pub mod html;
+
+// JSON export
+pub mod json;
+
+// Markdown export
+pub mod markdown;
+
+// Org-mode drill export
+pub mod org;
+
+// Export format
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Csv,
+    Tsv,
+    Json,
+    Markdown,
+    Org,
+    Anki,
+    Html,
+}
+
+impl Format {
+    // Format matching the file extension
+    pub fn from_path(path: &str) -> Option<Format> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            "json" => Some(Format::Json),
+            "md" | "markdown" => Some(Format::Markdown),
+            "org" => Some(Format::Org),
+            "apkg" => Some(Format::Anki),
+            "html" | "htm" => Some(Format::Html),
+            _ => None,
+        }
+    }
+
+    // Next format in the cycle
+    pub fn next(&self) -> Format {
+        match self {
+            Format::Csv => Format::Tsv,
+            Format::Tsv => Format::Json,
+            Format::Json => Format::Markdown,
+            Format::Markdown => Format::Org,
+            Format::Org => Format::Anki,
+            Format::Anki => Format::Html,
+            Format::Html => Format::Csv,
+        }
+    }
+
+    // File extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Tsv => "tsv",
+            Format::Json => "json",
+            Format::Markdown => "md",
+            Format::Org => "org",
+            Format::Anki => "apkg",
+            Format::Html => "html",
+        }
+    }
+}
+
+// One stack with its cards, or every stack if no id is given
+// Its "Stack::Child" stacks are taken too if substacks is set
+pub fn collect(store: &dyn Store, stack_id: Option<i32>, substacks: bool) -> Vec<(Stack, Vec<Card>)> {
+    let stacks = store.stacks();
+    let parent = stack_id.and_then(|id| stacks.iter().find(|stack| stack.id == id).map(|stack| stack.name.to_string()));
+    stacks
+        .into_iter()
+        .filter(|stack| match &parent {
+            Some(parent) => stack.name == *parent || (substacks && stack.name.starts_with(&format!("{}::", parent))),
+            None => stack_id.is_none(),
+        })
+        .map(|stack| {
+            let cards = store.cards(stack.id);
+            (stack, cards)
+        })
+        .collect()
+}
+
+// Export one stack or every stack to a file and return the number of cards
+pub fn to_file(
+    store: &dyn Store,
+    stack_id: Option<i32>,
+    substacks: bool,
+    path: &str,
+    format: Format,
+) -> std::io::Result<usize> {
+    let stacks = collect(store, stack_id, substacks);
+    match format {
+        Format::Csv => fs::write(path, csv::to_string(&stacks, b','))?,
+        Format::Tsv => fs::write(path, csv::to_string(&stacks, b'\t'))?,
+        Format::Json => fs::write(path, json::to_string(&stacks))?,
+        Format::Markdown => fs::write(path, markdown::to_string(&stacks))?,
+        Format::Org => fs::write(path, org::to_string(&stacks))?,
+        Format::Anki => anki::to_file(&stacks, path)?,
+        Format::Html => fs::write(path, html::to_string(&stacks))?,
+    }
+
+    Ok(stacks.iter().map(|(_, cards)| cards.len()).sum())
+}
+
+// File name made of letters and numbers of a stack name
+pub fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,274 @@
+use crate::db::card::Card;
+use crate::db::stack::Stack;
+use crate::import::anki::TempFile;
+use crate::time;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// Fixed id of the note type so repeated imports into Anki reuse it
+const MODEL_ID: i64 = 1_670_000_000_000;
+
+const DAY: i64 = 24 * 60 * 60;
+
+// Tables of a collection in the format older Anki versions use, which all
+// versions can import
+const SCHEMA: &str = "
+CREATE TABLE col (id INTEGER PRIMARY KEY, crt INTEGER NOT NULL, mod INTEGER NOT NULL, scm INTEGER NOT NULL, ver INTEGER NOT NULL, dty INTEGER NOT NULL, usn INTEGER NOT NULL, ls INTEGER NOT NULL, conf TEXT NOT NULL, models TEXT NOT NULL, decks TEXT NOT NULL, dconf TEXT NOT NULL, tags TEXT NOT NULL);
+CREATE TABLE notes (id INTEGER PRIMARY KEY, guid TEXT NOT NULL, mid INTEGER NOT NULL, mod INTEGER NOT NULL, usn INTEGER NOT NULL, tags TEXT NOT NULL, flds TEXT NOT NULL, sfld INTEGER NOT NULL, csum INTEGER NOT NULL, flags INTEGER NOT NULL, data TEXT NOT NULL);
+CREATE TABLE cards (id INTEGER PRIMARY KEY, nid INTEGER NOT NULL, did INTEGER NOT NULL, ord INTEGER NOT NULL, mod INTEGER NOT NULL, usn INTEGER NOT NULL, type INTEGER NOT NULL, queue INTEGER NOT NULL, due INTEGER NOT NULL, ivl INTEGER NOT NULL, factor INTEGER NOT NULL, reps INTEGER NOT NULL, lapses INTEGER NOT NULL, left INTEGER NOT NULL, odue INTEGER NOT NULL, odid INTEGER NOT NULL, flags INTEGER NOT NULL, data TEXT NOT NULL);
+CREATE TABLE revlog (id INTEGER PRIMARY KEY, cid INTEGER NOT NULL, usn INTEGER NOT NULL, ease INTEGER NOT NULL, ivl INTEGER NOT NULL, lastIvl INTEGER NOT NULL, factor INTEGER NOT NULL, time INTEGER NOT NULL, type INTEGER NOT NULL);
+CREATE TABLE graves (usn INTEGER NOT NULL, oid INTEGER NOT NULL, type INTEGER NOT NULL);
+CREATE INDEX ix_notes_usn ON notes (usn);
+CREATE INDEX ix_cards_usn ON cards (usn);
+CREATE INDEX ix_revlog_usn ON revlog (usn);
+CREATE INDEX ix_cards_nid ON cards (nid);
+CREATE INDEX ix_cards_sched ON cards (did, queue, due);
+CREATE INDEX ix_revlog_cid ON revlog (cid);
+CREATE INDEX ix_notes_csum ON notes (csum);
+";
+
+// Write stacks as an Anki package with one deck per stack and Basic notes.
+// Reviewed cards keep their interval, ease and due day, suspended cards stay
+// suspended. Past reviews are not exported.
+pub fn to_file(stacks: &[(Stack, Vec<Card>)], path: &str) -> io::Result<()> {
+    let tmp = TempFile::new("anki2")?;
+    let conn = Connection::open(tmp.path()).map_err(io::Error::other)?;
+    write_collection(&conn, stacks).map_err(io::Error::other)?;
+    drop(conn);
+    let collection = fs::read(tmp.path())?;
+
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("collection.anki2", options)?;
+    zip.write_all(&collection)?;
+    zip.start_file("media", options)?;
+    zip.write_all(b"{}")?;
+    zip.finish()?;
+
+    Ok(())
+}
+
+// Fill an empty collection with the stacks
+fn write_collection(conn: &Connection, stacks: &[(Stack, Vec<Card>)]) -> rusqlite::Result<()> {
+    conn.execute_batch(SCHEMA)?;
+    let now = time::now();
+    let now_ms = now * 1000;
+    // Review cards are due on a day counted from the creation of the collection
+    let created = now - now % DAY;
+
+    // Decks, with parents of "Parent::Child" stacks and the default deck Anki expects
+    let mut decks = vec![deck(1, "Default", now)];
+    let mut deck_ids = vec![];
+    for (stack, _) in stacks {
+        let mut name = String::new();
+        let mut deck_id = 1;
+        for part in stack.name.split("::") {
+            if !name.is_empty() {
+                name.push_str("::");
+            }
+            name.push_str(part);
+            deck_id = match decks.iter().find(|deck| deck["name"] == name.as_str()) {
+                Some(deck) => deck["id"].as_i64().unwrap(),
+                None => {
+                    let id = now_ms + decks.len() as i64;
+                    decks.push(deck(id, &name, now));
+                    id
+                }
+            };
+        }
+        deck_ids.push(deck_id);
+    }
+    let decks: serde_json::Map<String, Value> = decks
+        .into_iter()
+        .map(|deck| (deck["id"].to_string(), deck))
+        .collect();
+
+    conn.execute(
+        "INSERT INTO col VALUES (1, ?1, ?2, ?2, 11, 0, 0, 0, ?3, ?4, ?5, ?6, '{}')",
+        (
+            created,
+            now_ms,
+            conf(stacks.iter().map(|(_, cards)| cards.len()).sum::<usize>() + 1).to_string(),
+            json!({ MODEL_ID.to_string(): model(now) }).to_string(),
+            Value::Object(decks).to_string(),
+            deck_conf(now).to_string(),
+        ),
+    )?;
+
+    let tx = conn.unchecked_transaction()?;
+    let mut position = 0;
+    for ((_, cards), deck_id) in stacks.iter().zip(deck_ids) {
+        for card in cards {
+            position += 1;
+            let id = now_ms + position;
+            let front = to_html(&card.title);
+            let tags = match card.tags.trim().is_empty() {
+                true => String::new(),
+                false => format!(" {} ", card.tags.trim()),
+            };
+            tx.prepare_cached("INSERT INTO notes VALUES (?1, ?2, ?3, ?4, -1, ?5, ?6, ?7, ?8, 0, '')")?
+                .execute((
+                    id,
+                    format!("cards.rs-{}", card.id),
+                    MODEL_ID,
+                    now,
+                    tags,
+                    format!("{}\x1f{}", front, to_html(&card.text)),
+                    &card.title,
+                    checksum(&card.title),
+                ))?;
+            // New cards are due in order of position, suspended cards have queue -1
+            let schedule = &card.schedule;
+            let (kind, due, interval, factor) = match schedule.reps {
+                0 => (0, position, 0, 0),
+                _ => (2, (schedule.due - created).div_euclid(DAY), schedule.interval.max(1), schedule.ease),
+            };
+            let queue = if card.suspended { -1 } else { kind };
+            tx.prepare_cached("INSERT INTO cards VALUES (?1, ?1, ?2, 0, ?3, -1, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 0, 0, 0, 0, '')")?
+                .execute((id, deck_id, now, kind, queue, due, interval, factor, schedule.reps, schedule.lapses))?;
+        }
+    }
+    tx.commit()
+}
+
+// Plain text as a note field
+fn to_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+// First 8 hex digits of the SHA-1 of the first field, used by Anki to find duplicates
+fn checksum(field: &str) -> i64 {
+    let digest = sha1_smol::Sha1::from(field).digest().to_string();
+    i64::from_str_radix(&digest[..8], 16).unwrap()
+}
+
+// Collection settings
+fn conf(next_position: usize) -> Value {
+    json!({
+        "activeDecks": [1],
+        "curDeck": 1,
+        "newSpread": 0,
+        "collapseTime": 1200,
+        "timeLim": 0,
+        "estTimes": true,
+        "dueCounts": true,
+        "curModel": MODEL_ID,
+        "nextPos": next_position,
+        "sortType": "noteFld",
+        "sortBackwards": false,
+        "addToCur": true
+    })
+}
+
+// Deck in the decks JSON
+fn deck(id: i64, name: &str, now: i64) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "desc": "",
+        "mod": now,
+        "usn": -1,
+        "conf": 1,
+        "dyn": 0,
+        "collapsed": false,
+        "browserCollapsed": false,
+        "extendNew": 10,
+        "extendRev": 50,
+        "newToday": [0, 0],
+        "revToday": [0, 0],
+        "lrnToday": [0, 0],
+        "timeToday": [0, 0]
+    })
+}
+
+// Default deck options
+fn deck_conf(now: i64) -> Value {
+    json!({
+        "1": {
+            "id": 1,
+            "name": "Default",
+            "mod": now,
+            "usn": 0,
+            "maxTaken": 60,
+            "autoplay": true,
+            "timer": 0,
+            "replayq": true,
+            "dyn": false,
+            "new": {
+                "bury": true,
+                "delays": [1.0, 10.0],
+                "initialFactor": 2500,
+                "ints": [1, 4, 7],
+                "order": 1,
+                "perDay": 20,
+                "separate": true
+            },
+            "lapse": {
+                "delays": [10.0],
+                "leechAction": 0,
+                "leechFails": 8,
+                "minInt": 1,
+                "mult": 0.0
+            },
+            "rev": {
+                "bury": true,
+                "ease4": 1.3,
+                "fuzz": 0.05,
+                "ivlFct": 1.0,
+                "maxIvl": 36500,
+                "minSpace": 1,
+                "perDay": 200
+            }
+        }
+    })
+}
+
+// Basic note type with a Front and a Back field
+fn model(now: i64) -> Value {
+    let field = |name: &str, ord: i32| {
+        json!({
+            "name": name,
+            "ord": ord,
+            "sticky": false,
+            "rtl": false,
+            "font": "Arial",
+            "size": 20,
+            "media": []
+        })
+    };
+    json!({
+        "id": MODEL_ID,
+        "name": "Basic (cards.rs)",
+        "type": 0,
+        "mod": now,
+        "usn": -1,
+        "sortf": 0,
+        "did": 1,
+        "tags": [],
+        "vers": [],
+        "flds": [field("Front", 0), field("Back", 1)],
+        "tmpls": [{
+            "name": "Card 1",
+            "ord": 0,
+            "qfmt": "{{Front}}",
+            "afmt": "{{FrontSide}}\n\n<hr id=answer>\n\n{{Back}}",
+            "bqfmt": "",
+            "bafmt": "",
+            "did": null,
+            "bfont": "",
+            "bsize": 0
+        }],
+        "css": ".card {\n font-family: arial;\n font-size: 20px;\n text-align: center;\n color: black;\n background-color: white;\n}\n",
+        "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+        "latexPost": "\\end{document}",
+        "latexsvg": false,
+        "req": [[0, "any", [0]]]
+    })
+}
@@ -0,0 +1,19 @@
+use crate::db::card::Card;
+use crate::db::stack::Stack;
+
+// Export cards as rows of stack, title, text and tags
+pub fn to_string(stacks: &[(Stack, Vec<Card>)], delimiter: u8) -> String {
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(vec![]);
+    writer.write_record(["stack", "title", "text", "tags"]).unwrap();
+    for (stack, cards) in stacks {
+        for card in cards {
+            writer
+                .write_record([&stack.name, &card.title, &card.text, &card.tags])
+                .unwrap();
+        }
+    }
+
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
@@ -0,0 +1,237 @@
+use crate::db::card::Card;
+use crate::db::stack::Stack;
+use crate::import::markdown::normalize_text;
+use serde_json::json;
+
+// Export stacks as a single HTML file with everything inline, so it can be
+// printed, mailed or opened offline. It has three views:
+//
+// - sheet: a two-column question and answer table, also used for printing
+// - cards: questions with their answers folded away
+// - self-test: one question at a time, scored, needs JavaScript
+//
+// Without JavaScript the sheet and the folded cards are shown one after the other.
+pub fn to_string(stacks: &[(Stack, Vec<Card>)]) -> String {
+    // A stack with its child stacks is named after the stack
+    let title = match stacks.first() {
+        Some((first, _)) if stacks[1..].iter().all(|(stack, _)| stack.name.starts_with(&format!("{}::", first.name))) => {
+            first.name.to_string()
+        }
+        _ => "Cards".to_string(),
+    };
+    let count: usize = stacks.iter().map(|(_, cards)| cards.len()).sum();
+
+    let mut sheet = String::new();
+    let mut folded = String::new();
+    for (stack, cards) in stacks.iter().filter(|(_, cards)| !cards.is_empty()) {
+        sheet.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n<thead><tr><th>Question</th><th>Answer</th></tr></thead>\n<tbody>\n",
+            escape(&stack.name)
+        ));
+        folded.push_str(&format!("<h2>{}</h2>\n", escape(&stack.name)));
+        for card in cards {
+            let title = escape(&card.title);
+            let text = escape(&normalize_text(&card.text));
+            sheet.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", title, text));
+            folded.push_str(&format!(
+                "<details><summary>{}</summary><div class=\"answer\">{}</div></details>\n",
+                title, text
+            ));
+        }
+        sheet.push_str("</tbody>\n</table>\n");
+    }
+
+    // Cards for the self-test, "<" is escaped so the data can't end the script element
+    let data = json!(stacks
+        .iter()
+        .flat_map(|(stack, cards)| cards.iter().map(move |card| json!({
+            "stack": stack.name,
+            "question": card.title,
+            "answer": normalize_text(&card.text),
+        })))
+        .collect::<Vec<_>>())
+    .to_string()
+    .replace('<', "\\u003c");
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+{STYLE}
+</style>
+</head>
+<body class="no-js">
+<header>
+<h1>{title}</h1>
+<p class="count">{count} cards</p>
+<nav>
+<button data-view="sheet">Sheet</button>
+<button data-view="cards">Cards</button>
+<button data-view="test">Self-test</button>
+<button onclick="window.print()">Print</button>
+</nav>
+</header>
+<main>
+<section id="sheet">
+{sheet}</section>
+<section id="cards">
+{folded}</section>
+<section id="test">
+<p id="test-progress"></p>
+<div id="test-card">
+<p class="stack" id="test-stack"></p>
+<p class="question" id="test-question"></p>
+<p class="answer" id="test-answer" hidden></p>
+</div>
+<div id="test-buttons">
+<button id="test-show">Show answer</button>
+<button id="test-right" hidden>I knew it</button>
+<button id="test-wrong" hidden>I didn't</button>
+</div>
+<div id="test-done" hidden>
+<p id="test-score"></p>
+<button id="test-retry">Retry missed cards</button>
+<button id="test-restart">Start over</button>
+</div>
+</section>
+</main>
+<script type="application/json" id="data">{data}</script>
+<script>
+{SCRIPT}
+</script>
+</body>
+</html>
+"#,
+        title = escape(&title),
+        count = count,
+        sheet = sheet,
+        folded = folded,
+        data = data,
+        STYLE = STYLE,
+        SCRIPT = SCRIPT,
+    )
+}
+
+// Text as HTML, line breaks are kept by the style
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = r#"body { font-family: system-ui, sans-serif; margin: 0 auto; max-width: 60rem; padding: 1rem; color: #222; }
+header { border-bottom: 1px solid #ccc; margin-bottom: 1rem; }
+h1 { margin-bottom: 0; }
+.count { color: #666; margin-top: 0.2rem; }
+nav { margin-bottom: 1rem; }
+button { font: inherit; padding: 0.3rem 0.8rem; margin-right: 0.3rem; cursor: pointer; }
+button.active { font-weight: bold; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5rem; }
+th, td { border: 1px solid #999; padding: 0.5rem; vertical-align: top; width: 50%; text-align: left; }
+td, .answer, .question { white-space: pre-wrap; }
+tr { break-inside: avoid; }
+details { border: 1px solid #ccc; border-radius: 4px; margin: 0.4rem 0; padding: 0.5rem; }
+summary { cursor: pointer; font-weight: bold; }
+details .answer { margin-top: 0.5rem; }
+#test-card { border: 1px solid #999; border-radius: 6px; padding: 1.5rem; margin: 1rem 0; min-height: 8rem; }
+#test-card .stack { color: #666; font-size: 0.9rem; margin-top: 0; }
+#test-card .question { font-size: 1.3rem; font-weight: bold; }
+#test-card .answer { border-top: 1px solid #ccc; padding-top: 1rem; }
+body.no-js nav, body.no-js #test { display: none; }
+body.view-sheet #cards, body.view-sheet #test,
+body.view-cards #sheet, body.view-cards #test,
+body.view-test #sheet, body.view-test #cards { display: none; }
+@media print {
+  nav, #cards, #test { display: none !important; }
+  #sheet { display: block !important; }
+  body { max-width: none; padding: 0; }
+}"#;
+
+const SCRIPT: &str = r#"(function () {
+  var cards = JSON.parse(document.getElementById('data').textContent);
+  var $ = function (id) { return document.getElementById(id); };
+  var queue = [], missed = [], position = 0, right = 0;
+
+  function show(view) {
+    document.body.className = 'view-' + view;
+    document.querySelectorAll('nav button[data-view]').forEach(function (button) {
+      button.classList.toggle('active', button.dataset.view === view);
+    });
+    if (view === 'test' && queue.length === 0) start(cards);
+  }
+
+  function shuffle(list) {
+    list = list.slice();
+    for (var i = list.length - 1; i > 0; i--) {
+      var j = Math.floor(Math.random() * (i + 1));
+      var swap = list[i]; list[i] = list[j]; list[j] = swap;
+    }
+    return list;
+  }
+
+  function start(list) {
+    queue = shuffle(list);
+    missed = [];
+    position = 0;
+    right = 0;
+    $('test-card').hidden = false;
+    $('test-buttons').hidden = false;
+    $('test-done').hidden = true;
+    next();
+  }
+
+  function next() {
+    if (position >= queue.length) return done();
+    var card = queue[position];
+    $('test-progress').textContent = 'Card ' + (position + 1) + ' of ' + queue.length + ', ' + right + ' right so far';
+    $('test-stack').textContent = card.stack;
+    $('test-question').textContent = card.question;
+    $('test-answer').textContent = card.answer;
+    $('test-answer').hidden = true;
+    $('test-show').hidden = false;
+    $('test-right').hidden = true;
+    $('test-wrong').hidden = true;
+  }
+
+  function answer(knew) {
+    if (knew) right++; else missed.push(queue[position]);
+    position++;
+    next();
+  }
+
+  function done() {
+    $('test-progress').textContent = '';
+    $('test-card').hidden = true;
+    $('test-buttons').hidden = true;
+    $('test-done').hidden = false;
+    $('test-score').textContent = right + ' of ' + queue.length + ' right';
+    $('test-retry').hidden = missed.length === 0;
+  }
+
+  $('test-show').onclick = function () {
+    $('test-answer').hidden = false;
+    $('test-show').hidden = true;
+    $('test-right').hidden = false;
+    $('test-wrong').hidden = false;
+  };
+  $('test-right').onclick = function () { answer(true); };
+  $('test-wrong').onclick = function () { answer(false); };
+  $('test-retry').onclick = function () { start(missed); };
+  $('test-restart').onclick = function () { start(cards); };
+  document.querySelectorAll('nav button[data-view]').forEach(function (button) {
+    button.onclick = function () { show(button.dataset.view); };
+  });
+  document.addEventListener('keydown', function (event) {
+    if (document.body.className !== 'view-test' || !$('test-done').hidden) return;
+    if (event.key === ' ' && !$('test-show').hidden) { event.preventDefault(); $('test-show').onclick(); }
+    else if (event.key === 'y' && !$('test-right').hidden) answer(true);
+    else if (event.key === 'n' && !$('test-wrong').hidden) answer(false);
+  });
+
+  show('sheet');
+})();"#;
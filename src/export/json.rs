@@ -0,0 +1,86 @@
+use crate::db::card::{Card, Schedule};
+use crate::db::stack::Stack;
+use serde::{Deserialize, Serialize};
+
+// JSON export format, see the Export section of the README
+//
+// Times are unix seconds, 0 when unknown. "due" is 0 for cards that were never
+// reviewed, "interval" is in days and "ease" in permille.
+
+// Version of the JSON export format
+pub const JSON_VERSION: u32 = 1;
+
+// JSON export file
+#[derive(Serialize, Deserialize)]
+pub struct JsonExport {
+    pub schema_version: u32,
+    pub stacks: Vec<JsonStack>,
+}
+
+// Stack in a JSON export
+#[derive(Serialize, Deserialize)]
+pub struct JsonStack {
+    pub name: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub cards: Vec<JsonCard>,
+}
+
+// Card in a JSON export
+#[derive(Serialize, Deserialize)]
+pub struct JsonCard {
+    pub title: String,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub suspended: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub due: i64,
+    pub interval: i32,
+    pub ease: i32,
+    pub reps: i32,
+    pub lapses: i32,
+}
+
+impl From<&Card> for JsonCard {
+    fn from(card: &Card) -> JsonCard {
+        let Schedule {
+            due,
+            interval,
+            ease,
+            reps,
+            lapses,
+        } = card.schedule;
+        JsonCard {
+            title: card.title.to_string(),
+            text: card.text.to_string(),
+            tags: card.tag_list().iter().map(|tag| tag.to_string()).collect(),
+            suspended: card.suspended,
+            created_at: card.created_at,
+            updated_at: card.updated_at,
+            due,
+            interval,
+            ease,
+            reps,
+            lapses,
+        }
+    }
+}
+
+// Export stacks with their cards as JSON
+pub fn to_string(stacks: &[(Stack, Vec<Card>)]) -> String {
+    let export = JsonExport {
+        schema_version: JSON_VERSION,
+        stacks: stacks
+            .iter()
+            .map(|(stack, cards)| JsonStack {
+                name: stack.name.to_string(),
+                created_at: stack.created_at,
+                updated_at: stack.updated_at,
+                cards: cards.iter().map(JsonCard::from).collect(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&export).unwrap()
+}
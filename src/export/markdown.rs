@@ -0,0 +1,53 @@
+use crate::db::card::Card;
+use crate::db::stack::Stack;
+use crate::import::markdown::{normalize_text, normalize_title};
+
+// Export stacks as Markdown, see import::markdown for the format
+pub fn to_string(stacks: &[(Stack, Vec<Card>)]) -> String {
+    stacks
+        .iter()
+        .map(|(stack, cards)| stack_to_string(stack, cards))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Export one stack as Markdown
+pub fn stack_to_string(stack: &Stack, cards: &[Card]) -> String {
+    let mut out = format!("# {}\n", normalize_title(&stack.name));
+    for card in cards {
+        out.push_str(&card_to_string(card, &[]));
+    }
+
+    out
+}
+
+// Export a card with extra "<!-- key: value -->" lines after its heading
+pub fn card_to_string(card: &Card, metadata: &[(&str, String)]) -> String {
+    let mut out = format!("\n## {}\n", normalize_title(&card.title));
+    for (key, value) in metadata {
+        out.push_str(&format!("<!-- {}: {} -->\n", key, value));
+    }
+    if !card.tags.trim().is_empty() {
+        out.push_str(&format!("<!-- tags: {} -->\n", card.tags.trim()));
+    }
+    out.push('\n');
+
+    let mut in_code = false;
+    for line in normalize_text(&card.text).lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        // Keep answer lines from being read as headings
+        if !in_code && line.trim_start_matches('\\').starts_with('#') {
+            out.push('\\');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    // A code block left open would take in the cards after it
+    if in_code {
+        out.push_str("```\n");
+    }
+
+    out
+}
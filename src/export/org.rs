@@ -0,0 +1,67 @@
+use crate::db::card::Card;
+use crate::db::stack::Stack;
+use crate::import::markdown::{normalize_text, normalize_title};
+use crate::import::org::DRILL_TAG;
+use chrono::{Local, TimeZone};
+
+// Export stacks as org-drill headings, see import::org for the format
+pub fn to_string(stacks: &[(Stack, Vec<Card>)]) -> String {
+    stacks
+        .iter()
+        .map(|(stack, cards)| stack_to_string(stack, cards))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Export one stack with a card heading for each card
+fn stack_to_string(stack: &Stack, cards: &[Card]) -> String {
+    let mut out = format!("* {}\n", normalize_title(&stack.name));
+    for card in cards {
+        out.push_str(&card_to_string(card));
+    }
+
+    out
+}
+
+// Export a card with its schedule as org-drill properties
+fn card_to_string(card: &Card) -> String {
+    let mut tags = vec![DRILL_TAG.to_string()];
+    tags.extend(card.tag_list().into_iter().map(tag));
+    let mut out = format!("\n** {} :{}:\n", normalize_title(&card.title), tags.join(":"));
+
+    // Cards that were never reviewed are new to org-drill too
+    let schedule = &card.schedule;
+    if schedule.reps > 0 {
+        if let Some(due) = Local.timestamp_opt(schedule.due, 0).single().filter(|_| schedule.due > 0) {
+            out.push_str(&format!("SCHEDULED: {}\n", due.format("<%Y-%m-%d %a>")));
+        }
+        out.push_str(":PROPERTIES:\n");
+        out.push_str(&format!(":DRILL_LAST_INTERVAL: {:.1}\n", schedule.interval as f64));
+        out.push_str(&format!(":DRILL_TOTAL_REPEATS: {}\n", schedule.reps));
+        out.push_str(&format!(":DRILL_FAILURE_COUNT: {}\n", schedule.lapses));
+        out.push_str(&format!(":DRILL_EASE: {}\n", schedule.ease as f64 / 1000.0));
+        out.push_str(":END:\n");
+    }
+
+    out.push_str("\n*** Answer\n");
+    for line in normalize_text(&card.text).lines() {
+        // Keep text lines from being read as headings
+        if line.trim_start_matches(',').starts_with('*') {
+            out.push(',');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    out
+}
+
+// Org tags only have letters, numbers, "_", "@", "#" and "%"
+fn tag(tag: &str) -> String {
+    tag.chars()
+        .map(|c| match c.is_alphanumeric() || "_@#%".contains(c) {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
@@ -0,0 +1,208 @@
+use crate::db::card::Schedule;
+use crate::db::Store;
+use std::collections::{HashMap, HashSet};
+use std::env;
+
+// Anki package import
+pub mod anki;
+
+// CSV and TSV import
+pub mod csv;
+
+// JSON export import
+pub mod json;
+
+// Markdown deck import
+pub mod markdown;
+
+// Org-mode drill import
+pub mod org;
+
+// Pasted term and definition lists
+pub mod paste;
+
+// Stack cards are imported into
+pub enum Target {
+    Stack(i32),
+    New(String),
+}
+
+impl Target {
+    // Id of the target stack, creating it if needed
+    pub fn stack_id(&self, store: &mut dyn Store) -> i32 {
+        match self {
+            Target::Stack(id) => *id,
+            Target::New(name) => store.add_stack(name.to_string()),
+        }
+    }
+}
+
+// Whether a file can keep the schedule and reviews of its cards
+pub fn has_history(path: &str) -> bool {
+    anki::is_package(path) || org::is_org(path) || json::is_json(path)
+}
+
+// Expand a leading "~" to the home directory
+pub fn expand_path(path: &str) -> String {
+    match (path.strip_prefix('~'), env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => format!("{}{}", home, rest),
+        _ => path.to_string(),
+    }
+}
+
+// Card read from an import source
+#[derive(Default)]
+pub struct NewCard {
+    // Id of the card it was exported from, if the source keeps ids
+    pub id: Option<i32>,
+    pub title: String,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub suspended: bool,
+    pub schedule: Schedule,
+    // Past reviews as (grade, time)
+    pub reviews: Vec<(u8, i64)>,
+}
+
+// Row left out of an import
+pub struct Skipped {
+    pub row: usize,
+    pub reason: String,
+}
+
+// Named group of cards imported into the stack of the same name
+pub struct Deck {
+    pub name: String,
+    // Cards with their position in the deck
+    pub cards: Vec<(usize, NewCard)>,
+    pub skipped: Vec<Skipped>,
+}
+
+impl Deck {
+    // Empty deck
+    pub fn new(name: &str) -> Deck {
+        Deck {
+            name: name.to_string(),
+            cards: vec![],
+            skipped: vec![],
+        }
+    }
+
+    // Number of cards that were reviewed before
+    pub fn reviewed(&self) -> usize {
+        self.cards.iter().filter(|(_, card)| !card.reviews.is_empty()).count()
+    }
+}
+
+// Outcome of an import
+pub struct Report {
+    pub added: usize,
+    pub skipped: Vec<Skipped>,
+}
+
+// Leave out cards without a title or text and duplicates of existing cards or earlier rows
+pub fn dedup(
+    cards: Vec<(usize, NewCard)>,
+    mut seen: HashSet<(String, String)>,
+    skipped: &mut Vec<Skipped>,
+) -> Vec<NewCard> {
+    let mut kept = vec![];
+    for (row, card) in cards {
+        if card.title.is_empty() || card.text.is_empty() {
+            skipped.push(Skipped {
+                row,
+                reason: "empty title or text".to_string(),
+            });
+            continue;
+        }
+        if !seen.insert((card.title.to_string(), card.text.to_string())) {
+            skipped.push(Skipped {
+                row,
+                reason: format!("duplicate of \"{}\"", card.title),
+            });
+            continue;
+        }
+        kept.push(card);
+    }
+
+    kept
+}
+
+// Import decks into stacks of the same name, with their schedule and reviews if history is set
+pub fn import_decks(store: &mut dyn Store, decks: Vec<Deck>, history: bool) -> Vec<(String, Report)> {
+    store.begin();
+    let stacks = store.stacks();
+    let mut reports = vec![];
+    for deck in decks {
+        let stack_id = match stacks.iter().find(|stack| stack.name == deck.name) {
+            Some(stack) => stack.id,
+            None => store.add_stack(deck.name.to_string()),
+        };
+        let existing = store
+            .cards(stack_id)
+            .into_iter()
+            .map(|card| (card.title, card.text))
+            .collect();
+        let cards = deck
+            .cards
+            .into_iter()
+            .map(|(i, mut card)| {
+                if !history {
+                    card.schedule = Schedule::default();
+                    card.reviews = vec![];
+                }
+                (i, card)
+            })
+            .collect();
+        let mut skipped = deck.skipped;
+        let cards = dedup(cards, existing, &mut skipped);
+        skipped.sort_by_key(|skipped| skipped.row);
+
+        let report = Report {
+            added: add_cards(store, stack_id, cards),
+            skipped,
+        };
+        reports.push((deck.name, report));
+    }
+    store.commit();
+
+    reports
+}
+
+// Add cards with their tags, schedule and reviews to a stack
+pub fn add_cards(store: &mut dyn Store, stack_id: i32, cards: Vec<NewCard>) -> usize {
+    let added = cards.len();
+    let ids = store.add_cards(
+        stack_id,
+        cards.iter().map(|card| (card.title.to_string(), card.text.to_string())).collect(),
+    );
+
+    // The rest is set in a second pass since adding cards only takes title and text
+    let plain = |card: &NewCard| card.tags.is_empty() && !card.suspended && card.schedule.reps == 0;
+    if !cards.iter().all(plain) {
+        let index: HashMap<i32, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut updated = vec![];
+        for mut card in store.cards(stack_id) {
+            if let Some(&i) = index.get(&card.id) {
+                for tag in &cards[i].tags {
+                    card.add_tag(tag);
+                }
+                card.suspended = cards[i].suspended;
+                card.schedule = cards[i].schedule.clone();
+                updated.push(card);
+            }
+        }
+        store.update_cards(&updated);
+    }
+
+    let reviews: Vec<(i32, u8, i64)> = ids
+        .iter()
+        .zip(&cards)
+        .flat_map(|(id, card)| card.reviews.iter().map(move |(grade, time)| (*id, *grade, *time)))
+        .collect();
+    if !reviews.is_empty() {
+        store.add_reviews(&reviews);
+    }
+
+    added
+}
@@ -0,0 +1,357 @@
+use crate::db::card::Schedule;
+use crate::import::{Deck, NewCard, Skipped};
+use regex::{Captures, Regex};
+use rusqlite::Connection;
+use serde::Deserialize;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+const DAY: i64 = 24 * 60 * 60;
+
+// Collections in a package, newest format first. Newer Anki versions write a
+// zstd compressed "anki21b" collection next to a stub for older versions.
+const COLLECTIONS: [&str; 3] = ["collection.anki21b", "collection.anki21", "collection.anki2"];
+
+// Error reading an Anki package
+pub enum Error {
+    Io(io::Error),
+    Zip(zip::result::ZipError),
+    Sqlite(rusqlite::Error),
+    Json(serde_json::Error),
+    NoCollection,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Zip(err) => write!(f, "{}", err),
+            Error::Sqlite(err) => write!(f, "{}", err),
+            Error::Json(err) => write!(f, "{}", err),
+            Error::NoCollection => write!(f, "no Anki collection in the package"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<zip::result::ZipError> for Error {
+    fn from(err: zip::result::ZipError) -> Error {
+        Error::Zip(err)
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Error {
+        Error::Sqlite(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Error {
+        Error::Json(err)
+    }
+}
+
+// Temporary file for an SQLite database, removed with its side files when dropped
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    // New empty file with a random name another process can't have made first
+    pub fn new(extension: &str) -> io::Result<TempFile> {
+        loop {
+            let suffix = RandomState::new().build_hasher().finish();
+            let path = env::temp_dir().join(format!("cards-{:016x}.{}", suffix, extension));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(TempFile { path }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let _ = fs::remove_file(format!("{}{}", self.path.display(), suffix));
+        }
+    }
+}
+
+// Deck in the JSON of older collections
+#[derive(Deserialize)]
+struct JsonDeck {
+    name: String,
+}
+
+// Whether a path looks like an Anki package
+pub fn is_package(path: &str) -> bool {
+    let path = path.to_lowercase();
+    path.ends_with(".apkg") || path.ends_with(".colpkg")
+}
+
+// Read the decks of an .apkg or .colpkg file
+pub fn read(path: &str) -> Result<Vec<Deck>, Error> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let mut data = None;
+    for name in COLLECTIONS {
+        if let Some(entry) = read_entry(&mut archive, name)? {
+            data = Some(match name.ends_with('b') {
+                true => zstd::decode_all(&entry[..])?,
+                false => entry,
+            });
+            break;
+        }
+    }
+    let data = data.ok_or(Error::NoCollection)?;
+
+    // SQLite needs a file to open, so the collection is copied to a temporary one
+    let tmp = TempFile::new("db")?;
+    fs::write(tmp.path(), data)?;
+    let conn = Connection::open(tmp.path())?;
+
+    read_collection(&conn)
+}
+
+// Read a file of the package if it's there
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> io::Result<Option<Vec<u8>>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(_) => return Ok(None),
+    };
+    let mut data = vec![];
+    entry.read_to_end(&mut data)?;
+
+    Ok(Some(data))
+}
+
+// Read cards of a collection grouped by deck
+fn read_collection(conn: &Connection) -> Result<Vec<Deck>, Error> {
+    let created: i64 = conn.query_row("SELECT crt FROM col", (), |row| row.get(0))?;
+    let deck_names = deck_names(conn)?;
+    let mut reviews = reviews(conn)?;
+    let html = Html::new();
+    let cloze = Regex::new(r"\{\{c\d+::").unwrap();
+
+    let mut decks: Vec<Deck> = vec![];
+    let mut raw_cards = conn.prepare(
+        "SELECT c.id, c.did, c.odid, c.ord, c.type, c.queue, c.due, c.ivl, c.factor, c.reps, c.lapses, n.flds, n.tags
+         FROM cards c JOIN notes n ON n.id = c.nid ORDER BY c.nid, c.ord",
+    )?;
+    let mut rows = raw_cards.query(())?;
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let did: i64 = row.get(1)?;
+        let odid: i64 = row.get(2)?;
+        let ord: i64 = row.get(3)?;
+        let fields: String = row.get(11)?;
+        let tags: String = row.get(12)?;
+
+        // Cards in filtered decks belong to their original deck
+        let deck_id = if odid != 0 { odid } else { did };
+        let name = deck_names.get(&deck_id).cloned().unwrap_or_else(|| "Anki".to_string());
+        let deck = match decks.iter().position(|deck| deck.name == name) {
+            Some(i) => &mut decks[i],
+            None => {
+                decks.push(Deck::new(&name));
+                decks.last_mut().unwrap()
+            }
+        };
+        let position = deck.cards.len() + deck.skipped.len() + 1;
+
+        if cloze.is_match(&fields) {
+            deck.skipped.push(Skipped {
+                row: position,
+                reason: "cloze notes are not supported".to_string(),
+            });
+            continue;
+        }
+        let fields: Vec<String> = fields.split('\x1f').map(|field| html.to_text(field)).collect();
+        let (title, text) = match ord {
+            // Front and back, with any extra fields added to the back
+            0 => (
+                fields[0].to_string(),
+                fields[1..]
+                    .iter()
+                    .filter(|field| !field.is_empty())
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join("\n\n"),
+            ),
+            // Reverse card
+            1 if fields.len() > 1 => (fields[1].to_string(), fields[0].to_string()),
+            _ => {
+                deck.skipped.push(Skipped {
+                    row: position,
+                    reason: format!("card template {} is not supported", ord + 1),
+                });
+                continue;
+            }
+        };
+
+        let kind: i64 = row.get(4)?;
+        let queue: i64 = row.get(5)?;
+        let schedule = match kind {
+            // New card
+            0 => Schedule::default(),
+            _ => {
+                // Learning cards are due at a unix time, others on a day counted from the collection creation
+                let due: i64 = row.get(6)?;
+                let factor: i32 = row.get(8)?;
+                Schedule {
+                    due: if due > 1_000_000_000 { due } else { created + due * DAY },
+                    interval: row.get::<_, i32>(7)?.max(0),
+                    ease: if factor > 0 { factor } else { Schedule::default().ease },
+                    reps: row.get(9)?,
+                    lapses: row.get(10)?,
+                }
+            }
+        };
+        deck.cards.push((
+            position,
+            NewCard {
+                id: None,
+                title,
+                text,
+                tags: tags.split_whitespace().map(|tag| tag.to_string()).collect(),
+                suspended: queue == -1,
+                schedule,
+                reviews: reviews.remove(&id).unwrap_or_default(),
+            },
+        ));
+    }
+    decks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(decks)
+}
+
+// Deck names by id, with "::" between parent and child decks
+fn deck_names(conn: &Connection) -> Result<HashMap<i64, String>, Error> {
+    // Newer collections keep decks in a table, older ones as JSON in the col table
+    let has_table: i64 = conn.query_row(
+        "SELECT count(*) FROM sqlite_master WHERE type='table' AND name='decks'",
+        (),
+        |row| row.get(0),
+    )?;
+    if has_table > 0 {
+        let mut raw_decks = conn.prepare("SELECT id, name FROM decks")?;
+        let decks = raw_decks.query_map((), |row| {
+            Ok((row.get(0)?, row.get::<_, String>(1)?.replace('\x1f', "::")))
+        })?;
+        return Ok(decks.collect::<Result<_, _>>()?);
+    }
+
+    let json: String = conn.query_row("SELECT decks FROM col", (), |row| row.get(0))?;
+    let decks: HashMap<String, JsonDeck> = serde_json::from_str(&json)?;
+    Ok(decks
+        .into_iter()
+        .filter_map(|(id, deck)| Some((id.parse().ok()?, deck.name)))
+        .collect())
+}
+
+// Reviews as (grade, time) by card id, leaving out manual reschedules
+fn reviews(conn: &Connection) -> Result<HashMap<i64, Vec<(u8, i64)>>, Error> {
+    let mut reviews: HashMap<i64, Vec<(u8, i64)>> = HashMap::new();
+    let mut raw_reviews = conn.prepare("SELECT cid, ease, id FROM revlog WHERE ease > 0 ORDER BY id")?;
+    let mut rows = raw_reviews.query(())?;
+    while let Some(row) = rows.next()? {
+        let grade: i64 = row.get(1)?;
+        let time: i64 = row.get(2)?;
+        reviews
+            .entry(row.get(0)?)
+            .or_default()
+            .push((grade.min(4) as u8, time / 1000));
+    }
+
+    Ok(reviews)
+}
+
+// Converts the HTML of note fields to plain text
+struct Html {
+    hidden: Regex,
+    line_break: Regex,
+    block_pair: Regex,
+    block: Regex,
+    image: Regex,
+    tag: Regex,
+    sound: Regex,
+    entity: Regex,
+}
+
+impl Html {
+    fn new() -> Html {
+        Html {
+            hidden: Regex::new(r"(?is)<(style|script)[^>]*>.*?</(style|script)>").unwrap(),
+            line_break: Regex::new(r"(?i)<br\s*/?>").unwrap(),
+            block_pair: Regex::new(r"(?i)</(div|p|li|tr|h[1-6])>\s*<(div|p|li|tr|h[1-6])(\s[^>]*)?>").unwrap(),
+            block: Regex::new(r"(?i)</?(div|p|li|tr|ul|ol|table|h[1-6])(\s[^>]*)?>").unwrap(),
+            image: Regex::new(r#"(?i)<img[^>]*src="([^"]*)"[^>]*>"#).unwrap(),
+            tag: Regex::new(r"<[^>]*>").unwrap(),
+            sound: Regex::new(r"\[sound:[^\]]*\]").unwrap(),
+            entity: Regex::new(r"&(#[xX][0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap(),
+        }
+    }
+
+    fn to_text(&self, html: &str) -> String {
+        let text = self.hidden.replace_all(html, "");
+        let text = self.line_break.replace_all(&text, "\n");
+        let text = self.block_pair.replace_all(&text, "\n");
+        let text = self.block.replace_all(&text, "\n");
+        let text = self.image.replace_all(&text, "[image: $1]");
+        let text = self.tag.replace_all(&text, "");
+        let text = self.sound.replace_all(&text, "");
+        let text = self.entity.replace_all(&text, |captures: &Captures| {
+            let entity = &captures[1];
+            let code = match entity.strip_prefix('#') {
+                Some(number) => match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => number.parse().ok(),
+                },
+                None => match entity {
+                    "nbsp" => Some(' ' as u32),
+                    "amp" => Some('&' as u32),
+                    "lt" => Some('<' as u32),
+                    "gt" => Some('>' as u32),
+                    "quot" => Some('"' as u32),
+                    "apos" => Some('\'' as u32),
+                    _ => None,
+                },
+            };
+            match code.and_then(char::from_u32) {
+                Some(c) => c.to_string(),
+                None => captures[0].to_string(),
+            }
+        });
+
+        // Trim lines and keep at most one empty line in a row
+        let mut lines: Vec<&str> = vec![];
+        for line in text.lines().map(|line| line.trim()) {
+            if !line.is_empty() || lines.last().map(|last| !last.is_empty()).unwrap_or(false) {
+                lines.push(line);
+            }
+        }
+        while lines.last() == Some(&"") {
+            lines.pop();
+        }
+
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,174 @@
+use crate::db::Store;
+use crate::import::{add_cards, dedup, NewCard, Report, Skipped, Target};
+use std::path::Path;
+
+// What a column is imported as
+#[derive(Clone, PartialEq)]
+pub enum Column {
+    Ignore,
+    Title,
+    Text,
+    Tags,
+}
+
+impl Column {
+    // Next role in the cycle
+    pub fn next(&self) -> Column {
+        match self {
+            Column::Ignore => Column::Title,
+            Column::Title => Column::Text,
+            Column::Text => Column::Tags,
+            Column::Tags => Column::Ignore,
+        }
+    }
+
+    // Name shown in the import popup
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Ignore => "ignore",
+            Column::Title => "title",
+            Column::Text => "text",
+            Column::Tags => "tags",
+        }
+    }
+}
+
+// Guess the delimiter from the file extension or the first line
+pub fn detect_delimiter(path: &str, content: &str) -> u8 {
+    if Path::new(path)
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("tsv"))
+        .unwrap_or(false)
+    {
+        return b'\t';
+    }
+    let first_line = content.lines().next().unwrap_or_default();
+    [b'\t', b',', b';', b'|']
+        .into_iter()
+        .max_by_key(|delimiter| first_line.matches(*delimiter as char).count())
+        .filter(|delimiter| first_line.contains(*delimiter as char))
+        .unwrap_or(b',')
+}
+
+// Guess if the first row names the columns
+pub fn detect_header(rows: &[Vec<String>]) -> bool {
+    match rows.first() {
+        Some(row) => row.iter().any(|name| {
+            matches!(
+                name.to_lowercase().as_str(),
+                "title" | "front" | "question" | "term" | "text" | "back" | "answer" | "definition" | "tags" | "tag"
+            )
+        }),
+        None => false,
+    }
+}
+
+// Parse delimited text into rows
+pub fn parse(content: &str, delimiter: u8) -> Result<Vec<Vec<String>>, ::csv::Error> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let mut rows = vec![];
+    for record in reader.records() {
+        rows.push(record?.iter().map(|field| field.trim().to_string()).collect());
+    }
+
+    Ok(rows)
+}
+
+// Guess column roles from the header names, else first column title and second text
+pub fn default_columns(rows: &[Vec<String>], has_header: bool) -> Vec<Column> {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut columns = vec![Column::Ignore; width];
+    if has_header {
+        for (i, name) in rows[0].iter().enumerate() {
+            columns[i] = match name.to_lowercase().as_str() {
+                "title" | "front" | "question" | "term" => Column::Title,
+                "text" | "back" | "answer" | "definition" => Column::Text,
+                "tags" | "tag" => Column::Tags,
+                _ => Column::Ignore,
+            };
+        }
+    }
+    if !columns.contains(&Column::Title) && !columns.contains(&Column::Text) {
+        for (i, column) in columns.iter_mut().enumerate().take(2) {
+            *column = match i {
+                0 => Column::Title,
+                _ => Column::Text,
+            };
+        }
+    }
+
+    columns
+}
+
+// Cards the rows map to, with the reasons rows can't be used
+pub fn map_rows(rows: &[Vec<String>], columns: &[Column], has_header: bool) -> (Vec<(usize, NewCard)>, Vec<Skipped>) {
+    let mut cards = vec![];
+    let mut skipped = vec![];
+    let first = if has_header { 1 } else { 0 };
+    for (i, row) in rows.iter().enumerate().skip(first) {
+        if row.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        if row.len() < columns.len() && columns[row.len()..].iter().any(|column| *column != Column::Ignore) {
+            skipped.push(Skipped {
+                row: i + 1,
+                reason: format!("only {} of {} columns", row.len(), columns.len()),
+            });
+            continue;
+        }
+        let mut card = NewCard::default();
+        for (field, column) in row.iter().zip(columns) {
+            match column {
+                Column::Title => append(&mut card.title, field),
+                Column::Text => append(&mut card.text, field),
+                Column::Tags => card
+                    .tags
+                    .extend(field.split(|c: char| c.is_whitespace() || c == ',').filter(|tag| !tag.is_empty()).map(|tag| tag.to_string())),
+                Column::Ignore => {}
+            }
+        }
+        cards.push((i + 1, card));
+    }
+
+    (cards, skipped)
+}
+
+// Join several columns mapped to the same field with new lines
+fn append(value: &mut String, field: &str) {
+    if field.is_empty() {
+        return;
+    }
+    if !value.is_empty() {
+        value.push('\n');
+    }
+    value.push_str(field);
+}
+
+// Import rows into a stack
+pub fn import(
+    store: &mut dyn Store,
+    target: &Target,
+    rows: &[Vec<String>],
+    columns: &[Column],
+    has_header: bool,
+) -> Report {
+    store.begin();
+    let stack_id = target.stack_id(store);
+    let existing = store
+        .cards(stack_id)
+        .into_iter()
+        .map(|card| (card.title, card.text))
+        .collect();
+    let (cards, mut skipped) = map_rows(rows, columns, has_header);
+    let cards = dedup(cards, existing, &mut skipped);
+    skipped.sort_by_key(|skipped| skipped.row);
+
+    let added = add_cards(store, stack_id, cards);
+    store.commit();
+
+    Report { added, skipped }
+}
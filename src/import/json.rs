@@ -0,0 +1,51 @@
+use crate::db::card::Schedule;
+use crate::export::json::{JsonExport, JSON_VERSION};
+use crate::import::{Deck, NewCard};
+use serde::de::Error;
+
+// Whether a file is a JSON export
+pub fn is_json(path: &str) -> bool {
+    path.to_lowercase().ends_with(".json")
+}
+
+// Decks of a JSON export with the schedule of their cards
+pub fn parse(content: &str) -> Result<Vec<Deck>, serde_json::Error> {
+    let export: JsonExport = serde_json::from_str(content)?;
+    if export.schema_version > JSON_VERSION {
+        return Err(serde_json::Error::custom(format!(
+            "schema_version {} is newer than {}",
+            export.schema_version, JSON_VERSION
+        )));
+    }
+
+    Ok(export
+        .stacks
+        .into_iter()
+        .map(|stack| {
+            let mut deck = Deck::new(&stack.name);
+            deck.cards = stack
+                .cards
+                .into_iter()
+                .enumerate()
+                .map(|(i, card)| {
+                    let new_card = NewCard {
+                        title: card.title,
+                        text: card.text,
+                        tags: card.tags,
+                        suspended: card.suspended,
+                        schedule: Schedule {
+                            due: card.due,
+                            interval: card.interval,
+                            ease: card.ease,
+                            reps: card.reps,
+                            lapses: card.lapses,
+                        },
+                        ..NewCard::default()
+                    };
+                    (i + 1, new_card)
+                })
+                .collect();
+            deck
+        })
+        .collect())
+}
@@ -0,0 +1,156 @@
+use crate::import::{Deck, NewCard};
+use std::path::Path;
+
+// Markdown deck format, see the Markdown section of the README
+//
+// # Stack name
+//
+// ## Question
+// <!-- id: 12 -->
+// <!-- tags: one two -->
+//
+// Answer, up to the next "## " heading
+//
+// Lines of an answer starting with "#" are escaped with a backslash, except in code blocks.
+// A "## " heading after an empty line ends a code block that was left open.
+
+// Whether a path looks like a Markdown file
+pub fn is_markdown(path: &str) -> bool {
+    let path = path.to_lowercase();
+    path.ends_with(".md") || path.ends_with(".markdown")
+}
+
+// Stack name for files without a "# " heading
+pub fn stack_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("Markdown")
+        .to_string()
+}
+
+// Value of a "<!-- key: value -->" line
+pub fn metadata<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim()
+        .strip_prefix(key)?
+        .strip_prefix(':')
+        .map(|value| value.trim())
+}
+
+// Whether a line is a "<!-- key: value -->" line
+pub fn is_metadata(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with("<!--") && line.ends_with("-->") && line.contains(':')
+}
+
+// Card being read
+struct Section {
+    card: NewCard,
+    lines: Vec<String>,
+    // Metadata comes right after the heading
+    in_metadata: bool,
+}
+
+// Read the decks of a Markdown file, using default_name if it has no "# " heading
+pub fn parse(content: &str, default_name: &str) -> Vec<Deck> {
+    let mut decks: Vec<Deck> = vec![];
+    let mut section: Option<Section> = None;
+    let mut in_code = false;
+    let mut blank = false;
+
+    for line in content.lines() {
+        // Cards start after an empty line, even in a code block without its end
+        if in_code && blank && line.starts_with("## ") {
+            in_code = false;
+        }
+        blank = line.trim().is_empty();
+        if !in_code {
+            if let Some(name) = line.strip_prefix("# ") {
+                finish(&mut decks, section.take());
+                decks.push(Deck::new(&normalize_title(name)));
+                continue;
+            }
+            if let Some(title) = line.strip_prefix("## ") {
+                finish(&mut decks, section.take());
+                if decks.is_empty() {
+                    decks.push(Deck::new(default_name));
+                }
+                section = Some(Section {
+                    card: NewCard {
+                        title: normalize_title(title),
+                        ..NewCard::default()
+                    },
+                    lines: vec![],
+                    in_metadata: true,
+                });
+                continue;
+            }
+        }
+
+        // Text before the first question is left out
+        let section = match section.as_mut() {
+            Some(section) => section,
+            None => continue,
+        };
+        if section.in_metadata && !in_code && is_metadata(line) {
+            if let Some(tags) = metadata(line, "tags") {
+                section.card.tags = tags.split_whitespace().map(|tag| tag.to_string()).collect();
+            }
+            if let Some(id) = metadata(line, "id") {
+                section.card.id = id.parse().ok();
+            }
+            continue;
+        }
+        section.in_metadata = false;
+
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        section.lines.push(match in_code {
+            true => line.to_string(),
+            false => unescape(line),
+        });
+    }
+    finish(&mut decks, section);
+
+    decks
+}
+
+// Add the card being read to the last deck
+fn finish(decks: &mut [Deck], section: Option<Section>) {
+    let (deck, mut section) = match (decks.last_mut(), section) {
+        (Some(deck), Some(section)) => (deck, section),
+        _ => return,
+    };
+
+    section.card.text = normalize_text(&section.lines.join("\n"));
+    let position = deck.cards.len() + 1;
+    deck.cards.push((position, section.card));
+}
+
+// Title as it is kept in a heading
+pub fn normalize_title(title: &str) -> String {
+    title.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// Text without leading and trailing empty lines, which are not part of an answer
+pub fn normalize_text(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().position(|line| !line.trim().is_empty());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+// Remove the backslash that keeps an answer line from being a heading
+fn unescape(line: &str) -> String {
+    match line.strip_prefix('\\') {
+        Some(rest) if rest.trim_start_matches('\\').starts_with('#') => rest.to_string(),
+        _ => line.to_string(),
+    }
+}
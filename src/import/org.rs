@@ -0,0 +1,275 @@
+use crate::import::markdown::{normalize_text, normalize_title};
+use crate::import::{Deck, NewCard};
+use crate::scheduler;
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use std::collections::HashMap;
+
+// Org-mode files in the format of org-drill
+//
+// * Stack name
+// ** Question                                              :drill:tag:
+// SCHEDULED: <2022-12-05 Mon>
+// :PROPERTIES:
+// :DRILL_LAST_INTERVAL: 4.0
+// :DRILL_EASE: 2.5
+// :END:
+//
+// Text of the question
+//
+// *** Answer
+// Answer
+//
+// Headings tagged drill are cards, the headings above them name the stack
+// ("Parent::Child" when nested). The body and the subheadings of a card are its
+// text, subheadings other than "Answer" keep their heading as first line.
+// Lines of a text starting with "*" are escaped with a comma.
+
+// Tag of the headings that are cards
+pub const DRILL_TAG: &str = "drill";
+
+// Whether a path looks like an Org file
+pub fn is_org(path: &str) -> bool {
+    path.to_lowercase().ends_with(".org")
+}
+
+// Heading level, title and tags of a heading line
+pub fn heading(line: &str) -> Option<(usize, String, Vec<String>)> {
+    let level = line.chars().take_while(|c| *c == '*').count();
+    if level == 0 || !line[level..].starts_with(' ') {
+        return None;
+    }
+    let mut title = line[level..].trim();
+    let mut tags = vec![];
+    if let Some((rest, last)) = title.rsplit_once(|c: char| c.is_whitespace()) {
+        if last.len() > 2 && last.starts_with(':') && last.ends_with(':') {
+            tags = last
+                .split(':')
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| tag.to_string())
+                .collect();
+            title = rest.trim();
+        }
+    } else if title.len() > 2 && title.starts_with(':') && title.ends_with(':') {
+        // Heading made of tags only
+        tags = title.split(':').filter(|tag| !tag.is_empty()).map(|tag| tag.to_string()).collect();
+        title = "";
+    }
+
+    Some((level, normalize_title(title), tags))
+}
+
+// Part of a card being read, the body or a subheading
+struct Part {
+    title: Option<String>,
+    lines: Vec<String>,
+    // Planning lines and property drawers come right after the heading
+    in_header: bool,
+    in_drawer: bool,
+}
+
+impl Part {
+    fn new(title: Option<String>) -> Part {
+        Part {
+            title,
+            lines: vec![],
+            in_header: true,
+            in_drawer: false,
+        }
+    }
+}
+
+// Card being read
+struct Item {
+    level: usize,
+    stack: String,
+    card: NewCard,
+    properties: HashMap<String, String>,
+    scheduled: Option<i64>,
+    parts: Vec<Part>,
+}
+
+// Read the decks of an Org file, using default_name for cards without a heading above them
+pub fn parse(content: &str, default_name: &str) -> Vec<Deck> {
+    let mut decks: Vec<Deck> = vec![];
+    let mut parents: Vec<(usize, String)> = vec![];
+    let mut item: Option<Item> = None;
+
+    for line in content.lines() {
+        if let Some((level, title, tags)) = heading(line) {
+            // Subheadings of a card are parts of its text
+            if let Some(item) = item.as_mut().filter(|item| level > item.level) {
+                item.parts.push(Part::new(Some(title)));
+                continue;
+            }
+            finish(&mut decks, item.take());
+
+            parents.retain(|(other, _)| *other < level);
+            if tags.iter().any(|tag| tag == DRILL_TAG) {
+                let stack = match parents.is_empty() {
+                    true => default_name.to_string(),
+                    false => parents.iter().map(|(_, name)| name.as_str()).collect::<Vec<&str>>().join("::"),
+                };
+                item = Some(Item {
+                    level,
+                    stack,
+                    card: NewCard {
+                        title,
+                        tags: tags.into_iter().filter(|tag| tag != DRILL_TAG).collect(),
+                        ..NewCard::default()
+                    },
+                    properties: HashMap::new(),
+                    scheduled: None,
+                    parts: vec![Part::new(None)],
+                });
+            } else {
+                parents.push((level, title));
+            }
+            continue;
+        }
+
+        // Text outside of cards is left out
+        let item = match item.as_mut() {
+            Some(item) => item,
+            None => continue,
+        };
+        let is_body = item.parts.len() == 1;
+        let part = item.parts.last_mut().unwrap();
+        let trimmed = line.trim();
+        if part.in_drawer {
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                part.in_drawer = false;
+            } else if let (true, Some((key, value))) = (is_body, property(trimmed)) {
+                item.properties.insert(key, value);
+            }
+            continue;
+        }
+        if part.in_header {
+            if trimmed.eq_ignore_ascii_case(":PROPERTIES:") {
+                part.in_drawer = true;
+                continue;
+            }
+            if ["SCHEDULED:", "DEADLINE:", "CLOSED:"].iter().any(|keyword| trimmed.starts_with(keyword)) {
+                if let (true, Some(scheduled)) = (is_body, trimmed.split("SCHEDULED:").nth(1)) {
+                    item.scheduled = timestamp(scheduled.trim());
+                }
+                continue;
+            }
+            if trimmed.is_empty() {
+                continue;
+            }
+            part.in_header = false;
+        }
+        part.lines.push(unescape(line));
+    }
+    finish(&mut decks, item);
+
+    decks
+}
+
+// Key and value of a ":KEY: value" property line
+fn property(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.strip_prefix(':')?.split_once(':')?;
+    Some((key.to_uppercase(), value.trim().to_string()))
+}
+
+// Unix time of an org timestamp like "<2022-12-05 Mon>" or "[2022-12-01 Thu 10:00]"
+pub fn timestamp(text: &str) -> Option<i64> {
+    let inner = text.get(1..)?.split(['>', ']']).next()?;
+    let date = NaiveDate::parse_from_str(inner.get(..10)?, "%Y-%m-%d").ok()?;
+    let time = inner
+        .split_whitespace()
+        .find(|part| part.contains(':'))
+        .and_then(|part| NaiveDateTime::parse_from_str(&format!("{} {}", date, part.get(..5).unwrap_or(part)), "%Y-%m-%d %H:%M").ok())
+        .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap());
+    Local.from_local_datetime(&time).earliest().map(|time| time.timestamp())
+}
+
+// Grade of an org-drill quality from 0 to 5
+fn grade(quality: &str) -> u8 {
+    match quality.parse::<f64>().map(|quality| quality.round() as i32) {
+        Ok(5) => scheduler::EASY,
+        Ok(4) => scheduler::GOOD,
+        Ok(3) => scheduler::HARD,
+        Ok(_) => scheduler::AGAIN,
+        Err(_) => scheduler::GOOD,
+    }
+}
+
+// Add the card being read to the deck of its stack
+fn finish(decks: &mut Vec<Deck>, item: Option<Item>) {
+    let mut item = match item {
+        Some(item) => item,
+        None => return,
+    };
+
+    // Text made of the body and the subheadings
+    let mut sections = vec![];
+    for part in &item.parts {
+        let text = normalize_text(&dedent(&part.lines));
+        match &part.title {
+            Some(title) if title.eq_ignore_ascii_case("answer") => sections.push(text),
+            Some(title) if text.is_empty() => sections.push(title.to_string()),
+            Some(title) => sections.push(format!("{}\n{}", title, text)),
+            None => sections.push(text),
+        }
+    }
+    let sections: Vec<String> = sections.into_iter().filter(|section| !section.is_empty()).collect();
+    item.card.text = sections.join("\n\n");
+
+    // Scheduling data kept by org-drill
+    let number = |key: &str| item.properties.get(key).and_then(|value| value.parse::<f64>().ok());
+    let schedule = &mut item.card.schedule;
+    if let Some(interval) = number("DRILL_LAST_INTERVAL") {
+        schedule.interval = interval.round() as i32;
+    }
+    if let Some(ease) = number("DRILL_EASE") {
+        schedule.ease = (ease * 1000.0).round() as i32;
+    }
+    if let Some(reps) = number("DRILL_TOTAL_REPEATS") {
+        schedule.reps = reps as i32;
+    }
+    if let Some(lapses) = number("DRILL_FAILURE_COUNT") {
+        schedule.lapses = lapses as i32;
+    }
+    if let Some(due) = item.scheduled.filter(|_| schedule.reps > 0) {
+        schedule.due = due;
+    }
+    let last_reviewed = item.properties.get("DRILL_LAST_REVIEWED").and_then(|value| timestamp(value));
+    if let Some(time) = last_reviewed {
+        let quality = item.properties.get("DRILL_LAST_QUALITY").map(|value| value.as_str()).unwrap_or("");
+        item.card.reviews.push((grade(quality), time));
+    }
+
+    let deck = match decks.iter().position(|deck| deck.name == item.stack) {
+        Some(i) => &mut decks[i],
+        None => {
+            decks.push(Deck::new(&item.stack));
+            decks.last_mut().unwrap()
+        }
+    };
+    let position = deck.cards.len() + 1;
+    deck.cards.push((position, item.card));
+}
+
+// Lines without the indentation they have in common
+fn dedent(lines: &[String]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+// Remove the comma that keeps a line of a text from being a heading
+fn unescape(line: &str) -> String {
+    match line.strip_prefix(',') {
+        Some(rest) if rest.trim_start_matches(',').starts_with('*') => rest.to_string(),
+        _ => line.to_string(),
+    }
+}
@@ -0,0 +1,113 @@
+use crate::db::Store;
+use crate::import::markdown::normalize_text;
+use crate::import::{add_cards, dedup, NewCard, Report, Skipped, Target};
+
+// Pasted "term<separator>definition" lists like Quizlet exports them
+//
+// Separators are written escaped, "\t" for a tab and "\n" for a new line,
+// so they can be typed in the import popup and passed on the command line.
+
+// Separators between term and definition to pick from, as (name, escaped separator)
+pub const TERM_SEPARATORS: [(&str, &str); 4] = [("tab", "\\t"), ("comma", ","), ("dash", " - "), ("new line", "\\n")];
+
+// Separators between cards to pick from
+pub const CARD_SEPARATORS: [(&str, &str); 3] = [("new line", "\\n"), ("semicolon", ";"), ("blank line", "\\n\\n")];
+
+// Separator from its escaped form
+pub fn unescape(separator: &str) -> String {
+    let mut out = String::new();
+    let mut chars = separator.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+// Name of a separator for the import popup
+pub fn separator_name(separator: &str, presets: &[(&str, &str)]) -> String {
+    match presets.iter().find(|(_, preset)| *preset == separator) {
+        Some((name, _)) => name.to_string(),
+        None => format!("\"{}\"", separator),
+    }
+}
+
+// Next preset separator after the given one
+pub fn next_separator(separator: &str, presets: &[(&str, &str)], back: bool) -> String {
+    let len = presets.len();
+    let i = match presets.iter().position(|(_, preset)| *preset == separator) {
+        Some(i) if back => (i + len - 1) % len,
+        Some(i) => (i + 1) % len,
+        None => 0,
+    };
+    presets[i].1.to_string()
+}
+
+// Read cards from pasted text with escaped separators
+pub fn parse(content: &str, term_separator: &str, card_separator: &str) -> (Vec<(usize, NewCard)>, Vec<Skipped>) {
+    let term_separator = unescape(term_separator);
+    let card_separator = unescape(card_separator);
+    let mut cards = vec![];
+    let mut skipped = vec![];
+    if term_separator.is_empty() || card_separator.is_empty() {
+        return (cards, skipped);
+    }
+
+    let content = content.replace("\r\n", "\n");
+    for (i, chunk) in content.split(card_separator.as_str()).enumerate() {
+        let chunk = chunk.trim_matches(|c: char| c == '\n' || c == '\r' || c == ' ');
+        if chunk.trim().is_empty() {
+            continue;
+        }
+        match chunk.split_once(term_separator.as_str()) {
+            Some((term, definition)) => cards.push((
+                i + 1,
+                NewCard {
+                    title: term.split_whitespace().collect::<Vec<&str>>().join(" "),
+                    text: normalize_text(definition.trim()),
+                    ..NewCard::default()
+                },
+            )),
+            None => skipped.push(Skipped {
+                row: i + 1,
+                reason: format!("no separator in \"{}\"", chunk.lines().next().unwrap_or_default()),
+            }),
+        }
+    }
+
+    (cards, skipped)
+}
+
+// Import pasted text into a stack
+pub fn import(
+    store: &mut dyn Store,
+    target: &Target,
+    content: &str,
+    term_separator: &str,
+    card_separator: &str,
+) -> Report {
+    store.begin();
+    let stack_id = target.stack_id(store);
+    let existing = store
+        .cards(stack_id)
+        .into_iter()
+        .map(|card| (card.title, card.text))
+        .collect();
+    let (cards, mut skipped) = parse(content, term_separator, card_separator);
+    let cards = dedup(cards, existing, &mut skipped);
+    skipped.sort_by_key(|skipped| skipped.row);
+
+    let added = add_cards(store, stack_id, cards);
+    store.commit();
+
+    Report { added, skipped }
+}
@@ -0,0 +1,453 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+// Keys of the ui are bound to named actions, per window. The [keys] table of the
+// config replaces the keys of an action in every window, and a [keys.<window>]
+// table in one window only:
+//
+//   [keys]
+//   up = ["C-p", "Up"]
+//   down = ["C-n", "Down"]
+//
+//   [keys.card_list]
+//   delete = "x"
+//
+// A key bound to two actions of a window is a conflict, the binding of the config
+// wins over the default one.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Leave the app
+    Quit,
+    Up,
+    Down,
+    Left,
+    Right,
+    // Next and previous window or field
+    Next,
+    Previous,
+    Confirm,
+    Back,
+    // Switch the focused option
+    Toggle,
+    // Stacks and cards
+    Add,
+    Delete,
+    Edit,
+    Sort,
+    Import,
+    Paste,
+    Export,
+    Backups,
+    Replace,
+    Settings,
+    List,
+    Study,
+    // Card list
+    Mark,
+    Visual,
+    MarkAll,
+    Bulk,
+    Filter,
+}
+
+use Action::*;
+
+// Names of the actions in the config
+const ACTIONS: &[(Action, &str)] = &[
+    (Quit, "quit"),
+    (Up, "up"),
+    (Down, "down"),
+    (Left, "left"),
+    (Right, "right"),
+    (Next, "next"),
+    (Previous, "previous"),
+    (Confirm, "confirm"),
+    (Back, "back"),
+    (Toggle, "toggle"),
+    (Add, "add"),
+    (Delete, "delete"),
+    (Edit, "edit"),
+    (Sort, "sort"),
+    (Import, "import"),
+    (Paste, "paste"),
+    (Export, "export"),
+    (Backups, "backups"),
+    (Replace, "replace"),
+    (Settings, "settings"),
+    (List, "list"),
+    (Study, "study"),
+    (Mark, "mark"),
+    (Visual, "visual"),
+    (MarkAll, "mark_all"),
+    (Bulk, "bulk"),
+    (Filter, "filter"),
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        ACTIONS.iter().find(|(action, _)| *action == self).map(|(_, name)| *name).unwrap()
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|(_, action)| *action == name).map(|(action, _)| *action)
+    }
+}
+
+// Keys of the actions unless a window has its own
+pub const DEFAULT_KEYS: &[(Action, &[&str])] = &[
+    (Quit, &["q"]),
+    (Up, &["k", "Up"]),
+    (Down, &["j", "Down"]),
+    (Left, &["Left"]),
+    (Right, &["Right"]),
+    (Next, &["Tab"]),
+    (Previous, &["BackTab"]),
+    (Confirm, &["Enter"]),
+    (Back, &["Esc"]),
+    (Toggle, &["Space"]),
+    (Add, &["a"]),
+    (Delete, &["d"]),
+    (Edit, &["e"]),
+    (Sort, &["r"]),
+    (Import, &["i"]),
+    (Paste, &["p"]),
+    (Export, &["x"]),
+    (Backups, &["b"]),
+    (Replace, &["f"]),
+    (Settings, &["c"]),
+    (List, &["l"]),
+    (Study, &["s"]),
+    (Mark, &["Space"]),
+    (Visual, &["V"]),
+    (MarkAll, &["a"]),
+    (Bulk, &["b"]),
+    (Filter, &["/"]),
+];
+
+// Window of the ui with the actions it has
+pub struct Window {
+    // Name in the config, the name of its module
+    pub name: &'static str,
+    pub actions: &'static [Action],
+    // Keys differing from DEFAULT_KEYS
+    pub keys: &'static [(Action, &'static [&'static str])],
+    // Whether printable keys are always typed, so the config can't bind them
+    pub typing: bool,
+}
+
+const fn window(name: &'static str, actions: &'static [Action], typing: bool) -> Window {
+    Window {
+        name,
+        actions,
+        keys: &[],
+        typing,
+    }
+}
+
+pub const WINDOWS: &[Window] = &[
+    window(
+        "main",
+        &[Quit, Up, Down, Next, Confirm, Add, Delete, Edit, Sort, Import, Paste, Export, Backups, Replace, Settings],
+        false,
+    ),
+    window("side", &[Quit, Next, Back, Add, List, Study], false),
+    window("stack_name_input", &[Confirm, Back], true),
+    window("delete_stack_popup", &[Confirm, Back], false),
+    window("edit_stack_popup", &[Confirm, Back], true),
+    window("add_card", &[Confirm, Back, Next], true),
+    window(
+        "card_list",
+        &[Back, Confirm, Next, Up, Down, Mark, Visual, MarkAll, Bulk, Filter, Sort, Delete, Edit],
+        false,
+    ),
+    window("delete_card", &[Confirm, Back], false),
+    window("edit_card", &[Confirm, Back, Next], true),
+    window("revision_title", &[Confirm, Back], false),
+    window("revision_text", &[Confirm, Back], false),
+    Window {
+        name: "settings",
+        actions: &[Confirm, Back, Up, Down, Next, Previous, Left, Right, Toggle],
+        keys: &[(Up, &["Up"]), (Down, &["Down"])],
+        typing: false,
+    },
+    window("bulk_actions", &[Confirm, Back, Up, Down], false),
+    window("move_cards", &[Confirm, Back, Up, Down], false),
+    window("tag_cards", &[Confirm, Back], true),
+    window("find_replace", &[Confirm, Back, Next, Previous, Left, Right, Toggle], true),
+    window("replace_preview", &[Confirm, Back, Up, Down], false),
+    Window {
+        name: "import_file",
+        actions: &[Confirm, Back, Next, Left, Right, Toggle],
+        keys: &[(Left, &["Left", "h"]), (Right, &["Right", "l"])],
+        typing: false,
+    },
+    window("export_file", &[Confirm, Back, Next, Left, Right, Toggle], false),
+    window("restore_backup", &[Confirm, Back, Up, Down], false),
+    window("paste_import", &[Confirm, Back, Next, Left, Right], true),
+];
+
+pub fn get_window(name: &str) -> Option<&'static Window> {
+    WINDOWS.iter().find(|window| window.name == name)
+}
+
+// Key with its modifiers, shift only counts for keys without a character
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+const NAMED_KEYS: &[(KeyCode, &str)] = &[
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Esc, "Esc"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::BackTab, "BackTab"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::Char(' '), "Space"),
+];
+
+impl Key {
+    pub fn from_event(event: KeyEvent) -> Key {
+        let modifiers = match event.code {
+            KeyCode::Char(_) | KeyCode::BackTab => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+        Key {
+            code: event.code,
+            modifiers: modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        }
+    }
+
+    // Key as written in the config: a character, a name like Enter or F5, with C- for
+    // control, M- for alt and S- for shift in front
+    pub fn parse(text: &str) -> Result<Key, String> {
+        let unknown = || format!("unknown key \"{}\"", text);
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        while rest.chars().count() > 2 {
+            let modifier = match rest.get(..2) {
+                Some("C-") => KeyModifiers::CONTROL,
+                Some("M-") => KeyModifiers::ALT,
+                Some("S-") => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            modifiers |= modifier;
+            rest = &rest[2..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_whitespace() => KeyCode::Char(c),
+            _ => match NAMED_KEYS.iter().find(|(_, name)| name.eq_ignore_ascii_case(rest)) {
+                Some((code, _)) => *code,
+                None => match rest.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(unknown()),
+                },
+            },
+        };
+        if modifiers.contains(KeyModifiers::SHIFT) && matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            return Err(format!("\"{}\" can't have S-, write the shifted key instead", text));
+        }
+        Ok(Key { code, modifiers })
+    }
+
+    // Key typed as text, without control or alt
+    pub fn is_printable(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, prefix) in [
+            (KeyModifiers::CONTROL, "C-"),
+            (KeyModifiers::ALT, "M-"),
+            (KeyModifiers::SHIFT, "S-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}", prefix)?;
+            }
+        }
+        match (self.code, NAMED_KEYS.iter().find(|(code, _)| *code == self.code)) {
+            (_, Some((_, name))) => write!(f, "{}", name),
+            (KeyCode::Char(c), None) => write!(f, "{}", c),
+            (KeyCode::F(n), None) => write!(f, "F{}", n),
+            (code, None) => write!(f, "{:?}", code),
+        }
+    }
+}
+
+// Character typed by a key, for the text fields of the windows
+pub fn typed(event: KeyEvent) -> Option<char> {
+    match Key::from_event(event) {
+        Key {
+            code: KeyCode::Char(c),
+            modifiers,
+        } if modifiers.is_empty() => Some(c),
+        _ => None,
+    }
+}
+
+// Keys bound to the actions of each window
+pub struct Keymap {
+    windows: HashMap<&'static str, Vec<(Key, Action)>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::new(&toml::Table::new()).0
+    }
+}
+
+// Keys bound to an action by the config, with the setting binding them
+type Binding = (Vec<Key>, String);
+
+// Keys of a binding in the config, a key or a list of them
+fn parse_keys(value: &toml::Value) -> Result<Vec<Key>, String> {
+    match value {
+        toml::Value::String(text) => Ok(vec![Key::parse(text)?]),
+        toml::Value::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                toml::Value::String(text) => Key::parse(text),
+                _ => Err(format!("must be a key or a list of keys, got {}", value)),
+            })
+            .collect(),
+        _ => Err(format!("must be a key or a list of keys, got {}", value)),
+    }
+}
+
+impl Keymap {
+    // Keymap of the [keys] table of the config, with the errors of the table as
+    // setting and reason, bindings with errors keep their default keys
+    pub fn new(keys: &toml::Table) -> (Keymap, Vec<(String, String)>) {
+        let mut errors: Vec<(String, String)> = vec![];
+
+        // Bindings of the config with their setting
+        let mut bindings: HashMap<(Option<&str>, Action), Binding> = HashMap::new();
+        let mut bind = |window: Option<&'static Window>, name: &str, value: &toml::Value, errors: &mut Vec<(String, String)>| {
+            let setting = match window {
+                Some(window) => format!("keys.{}.{}", window.name, name),
+                None => format!("keys.{}", name),
+            };
+            let action = match (Action::from_name(name), window) {
+                (None, _) => return errors.push((setting, "unknown action".to_string())),
+                (Some(action), Some(window)) if !window.actions.contains(&action) => {
+                    return errors.push((setting, format!("{} has no {} action", window.name, name)))
+                }
+                (Some(action), _) => action,
+            };
+            match parse_keys(value) {
+                Ok(keys) => {
+                    bindings.insert((window.map(|window| window.name), action), (keys, setting));
+                }
+                Err(reason) => errors.push((setting, reason)),
+            }
+        };
+        for (name, value) in keys {
+            match (value, get_window(name)) {
+                (toml::Value::Table(table), Some(window)) => {
+                    for (name, value) in table {
+                        bind(Some(window), name, value, &mut errors);
+                    }
+                }
+                (toml::Value::Table(_), None) => errors.push((format!("keys.{}", name), "unknown window".to_string())),
+                _ => bind(None, name, value, &mut errors),
+            }
+        }
+
+        let mut windows = HashMap::new();
+        for window in WINDOWS {
+            // Keys of each action, from the config first
+            let mut actions: Vec<(Action, Vec<Key>, Option<&String>)> = vec![];
+            for action in window.actions {
+                let binding = bindings
+                    .get(&(Some(window.name), *action))
+                    .or_else(|| bindings.get(&(None, *action)));
+                let mut keys = vec![];
+                if let Some((config_keys, setting)) = binding {
+                    for key in config_keys {
+                        // Printable keys are typed in typing windows, their default
+                        // keys are kept when none of the config is left
+                        match window.typing && key.is_printable() {
+                            true => errors.push((setting.to_string(), format!("{} is typed in {}", key, window.name))),
+                            false => keys.push(*key),
+                        }
+                    }
+                }
+                match (binding, keys.is_empty()) {
+                    (Some((_, setting)), false) => actions.push((*action, keys, Some(setting))),
+                    _ => {
+                        let keys = window
+                            .keys
+                            .iter()
+                            .chain(DEFAULT_KEYS)
+                            .find(|(default, _)| default == action)
+                            .map(|(_, keys)| keys.iter().map(|key| Key::parse(key).unwrap()).collect())
+                            .unwrap_or_default();
+                        actions.push((*action, keys, None));
+                    }
+                }
+            }
+            actions.sort_by_key(|(_, _, setting)| setting.is_none());
+
+            // The first binding of a key keeps it
+            let mut bound: Vec<(Key, Action, Option<&String>)> = vec![];
+            for (action, keys, setting) in actions {
+                for key in keys {
+                    match bound.iter().find(|(other, _, _)| *other == key) {
+                        None => bound.push((key, action, setting)),
+                        Some((_, other, _)) if *other == action => {}
+                        // Conflicts are reported on the binding of the config
+                        Some((_, other, other_setting)) => {
+                            let (setting, other) = match (setting, other_setting) {
+                                (Some(setting), _) => (setting, *other),
+                                (None, Some(other_setting)) => (*other_setting, action),
+                                (None, None) => unreachable!("default keys conflict in {}", window.name),
+                            };
+                            errors.push((
+                                setting.to_string(),
+                                format!("{} is also bound to {} in {}", key, other.name(), window.name),
+                            ));
+                        }
+                    }
+                }
+            }
+            windows.insert(window.name, bound.into_iter().map(|(key, action, _)| (key, action)).collect());
+        }
+
+        (Keymap { windows }, errors)
+    }
+
+    // Action of a key in a window
+    pub fn action(&self, window: &str, event: KeyEvent) -> Option<Action> {
+        let key = Key::from_event(event);
+        self.windows[window].iter().find(|(bound, _)| *bound == key).map(|(_, action)| *action)
+    }
+
+    // Keys of an action in a window
+    pub fn keys(&self, window: &str, action: Action) -> Vec<Key> {
+        self.windows[window].iter().filter(|(_, bound)| *bound == action).map(|(key, _)| *key).collect()
+    }
+
+    // First key of an action, for the help of a window
+    pub fn key(&self, window: &str, action: Action) -> String {
+        match self.keys(window, action).first() {
+            Some(key) => key.to_string(),
+            None => "-".to_string(),
+        }
+    }
+}
@@ -9,3 +9,35 @@ pub mod state;
 
 // Config 
 pub mod config;
+
+// Time helpers
+pub mod time;
+
+// Revision scheduling
+pub mod scheduler;
+
+// Export formats
+pub mod export;
+
+// Find and replace
+pub mod replace;
+
+// Import formats
+pub mod import;
+
+// Sync with deck files
+pub mod sync;
+// Backups
+pub mod backup;
+
+// Command line interface
+pub mod cli;
+
+// Line-based revision
+pub mod review;
+
+// Colors of the ui
+pub mod theme;
+
+// Key bindings of the ui
+pub mod keymap;
@@ -1,7 +1,60 @@
-use cards::ui;
+use cards::backup;
+use cards::cli::{self, Command};
 use cards::config;
+use cards::ui;
+use std::env;
+use std::io;
+use std::process;
 
 fn main() {
-    config::init();
-    ui::run_ui().unwrap();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (vars, args) = match cli::global_options(&args) {
+        Ok(options) => options,
+        Err(err) => exit(&format!("{}\n\n{}", err, cli::USAGE)),
+    };
+    // Options override the environment variables of the same settings
+    for (var, value) in vars {
+        env::set_var(var, value);
+    }
+    if let Some(warning) = config::home_warning() {
+        eprintln!("{}", warning);
+    }
+    if let Err(err) = config::init() {
+        eprintln!("{}", err);
+    }
+    for error in config::load().1 {
+        eprintln!("Config error in {}", error);
+    }
+
+    let command = match cli::parse(args) {
+        Ok(command) => command,
+        Err(err) => exit(&format!("{}\n\n{}", err, cli::USAGE)),
+    };
+    match command {
+        Command::Ui => ui::run_ui().unwrap(),
+        Command::Help => println!("{}", cli::USAGE),
+        Command::Profiles | Command::Profile { .. } => {
+            if let Err(err) = cli::run_profile(command, &mut io::stdout()) {
+                exit(&err);
+            }
+        }
+        command => {
+            let mut store = match backup::open_store(false) {
+                Ok(store) => store,
+                Err(err) => exit(&format!("Could not open database: {}", err)),
+            };
+            if let Err(err) = cli::run(command, &mut store, &mut io::stdout()) {
+                exit(&err);
+            }
+        }
+    }
+}
+
+// Print an error and exit, closed output is not one
+fn exit(message: &str) -> ! {
+    if message == cli::OUTPUT_CLOSED {
+        process::exit(0);
+    }
+    eprintln!("{}", message);
+    process::exit(1);
 }
@@ -0,0 +1,81 @@
+use crate::db::card::Card;
+use crate::db::Store;
+use regex::Regex;
+
+// Cards a find and replace looks at
+pub enum Scope {
+    Stack(i32),
+    Tag(String),
+    All,
+}
+
+// Find and replace query
+pub struct Replace {
+    pub find: String,
+    pub replace: String,
+    pub regex: bool,
+    pub scope: Scope,
+}
+
+// Card changed by a find and replace
+pub struct Change {
+    pub card: Card,
+    pub old_title: String,
+    pub old_text: String,
+}
+
+// Cards in scope
+fn cards_in_scope(store: &dyn Store, scope: &Scope) -> Vec<Card> {
+    match scope {
+        Scope::Stack(id) => store.cards(*id),
+        Scope::Tag(tag) => store
+            .stacks()
+            .iter()
+            .flat_map(|stack| store.cards(stack.id))
+            .filter(|card| card.tag_list().contains(&tag.as_str()))
+            .collect(),
+        Scope::All => store
+            .stacks()
+            .iter()
+            .flat_map(|stack| store.cards(stack.id))
+            .collect(),
+    }
+}
+
+// Find every card the query changes without changing anything
+pub fn preview(store: &dyn Store, query: &Replace) -> Result<Vec<Change>, regex::Error> {
+    let regex = match query.regex {
+        true => Some(Regex::new(&query.find)?),
+        false => None,
+    };
+    let replace = |text: &str| match &regex {
+        Some(regex) => regex.replace_all(text, query.replace.as_str()).to_string(),
+        None => text.replace(&query.find, &query.replace),
+    };
+
+    if query.find.is_empty() {
+        return Ok(vec![]);
+    }
+
+    Ok(cards_in_scope(store, &query.scope)
+        .into_iter()
+        .filter_map(|card| {
+            let title = replace(&card.title);
+            let text = replace(&card.text);
+            if title == card.title && text == card.text {
+                return None;
+            }
+            Some(Change {
+                old_title: card.title.to_string(),
+                old_text: card.text.to_string(),
+                card: Card { title, text, ..card },
+            })
+        })
+        .collect())
+}
+
+// Apply changes in one transaction
+pub fn apply(store: &mut dyn Store, changes: &[Change]) {
+    let cards: Vec<Card> = changes.iter().map(|change| change.card.clone()).collect();
+    store.update_cards(&cards);
+}
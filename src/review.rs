@@ -0,0 +1,155 @@
+use crate::db::card::{Card, Schedule};
+use crate::db::Store;
+use crate::scheduler;
+use crate::time;
+use std::io::{self, BufRead, Write};
+
+// Revision session over plain lines of input and output, for terminals
+// without raw mode and for scripts. Each card goes:
+//
+// Card 1 of 3 (Spanish)
+// Q: hola
+// Enter: show answer, q: quit
+// >
+// A: hello
+// Grade 1 again, 2 hard, 3 good, 4 easy, s skip, q quit [3]
+// >
+// Next review in 3 days
+//
+// An empty grade is good. The session ends after the last card, on q or at
+// the end of the input, grades given until then are kept.
+
+// Outcome of a session
+#[derive(Default)]
+pub struct Summary {
+    // Number of cards graded with each grade, again to easy
+    pub grades: [usize; 4],
+    pub skipped: usize,
+    pub quit: bool,
+}
+
+impl Summary {
+    pub fn reviewed(&self) -> usize {
+        self.grades.iter().sum()
+    }
+}
+
+// What was typed at a prompt
+enum Answer {
+    Grade(u8),
+    Skip,
+    Quit,
+}
+
+// Grade a card, saving the review and its next schedule
+pub fn grade(store: &mut dyn Store, card: &Card, grade: u8, now: i64) -> Schedule {
+    let schedule = scheduler::review(&card.schedule, grade, now);
+    store.add_review(card.id, grade, now);
+    store.set_schedule(card.id, &schedule);
+    schedule
+}
+
+// Review cards with the name of their stack
+pub fn session(
+    store: &mut dyn Store,
+    cards: &[(String, Card)],
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Summary> {
+    let mut summary = Summary::default();
+    for (i, (stack, card)) in cards.iter().enumerate() {
+        writeln!(out, "\nCard {} of {} ({})", i + 1, cards.len(), stack)?;
+        writeln!(out, "Q: {}", card.title)?;
+        writeln!(out, "Enter: show answer, q: quit")?;
+        match read(input, out)? {
+            Some(line) if line.eq_ignore_ascii_case("q") => {
+                summary.quit = true;
+                break;
+            }
+            Some(_) => {}
+            None => {
+                summary.quit = true;
+                break;
+            }
+        }
+
+        writeln!(out, "A: {}", card.text)?;
+        let answer = loop {
+            writeln!(out, "Grade 1 again, 2 hard, 3 good, 4 easy, s skip, q quit [3]")?;
+            let line = match read(input, out)? {
+                Some(line) => line,
+                None => break Answer::Quit,
+            };
+            match parse(&line) {
+                Some(answer) => break answer,
+                None => writeln!(out, "Unknown grade \"{}\"", line)?,
+            }
+        };
+
+        match answer {
+            Answer::Grade(grade_given) => {
+                let now = time::now();
+                let schedule = grade(store, card, grade_given, now);
+                summary.grades[grade_given as usize - 1] += 1;
+                writeln!(out, "Next review in {}", until(schedule.due - now))?;
+            }
+            Answer::Skip => summary.skipped += 1,
+            Answer::Quit => {
+                summary.quit = true;
+                break;
+            }
+        }
+    }
+
+    writeln!(
+        out,
+        "\nReviewed {} cards: {} again, {} hard, {} good, {} easy",
+        summary.reviewed(),
+        summary.grades[0],
+        summary.grades[1],
+        summary.grades[2],
+        summary.grades[3]
+    )?;
+
+    Ok(summary)
+}
+
+// Prompt and read a line without its line break, None at the end of the input
+fn read(input: &mut dyn BufRead, out: &mut dyn Write) -> io::Result<Option<String>> {
+    write!(out, "> ")?;
+    out.flush()?;
+    let mut line = String::new();
+    match input.read_line(&mut line)? {
+        0 => {
+            writeln!(out)?;
+            Ok(None)
+        }
+        _ => Ok(Some(line.trim().to_string())),
+    }
+}
+
+// Answer to the grade prompt, by number or name
+fn parse(line: &str) -> Option<Answer> {
+    match line.to_lowercase().as_str() {
+        "" | "3" | "good" => Some(Answer::Grade(scheduler::GOOD)),
+        "1" | "again" => Some(Answer::Grade(scheduler::AGAIN)),
+        "2" | "hard" => Some(Answer::Grade(scheduler::HARD)),
+        "4" | "easy" => Some(Answer::Grade(scheduler::EASY)),
+        "s" | "skip" => Some(Answer::Skip),
+        "q" | "quit" => Some(Answer::Quit),
+        _ => None,
+    }
+}
+
+// Time until a review, like "10 minutes" or "3 days"
+fn until(seconds: i64) -> String {
+    let plural = |count: i64, unit: &str| match count {
+        1 => format!("1 {}", unit),
+        count => format!("{} {}s", count, unit),
+    };
+    match seconds {
+        seconds if seconds < 60 * 60 => plural((seconds / 60).max(1), "minute"),
+        seconds if seconds < 24 * 60 * 60 => plural(seconds / (60 * 60), "hour"),
+        seconds => plural(seconds / (24 * 60 * 60), "day"),
+    }
+}
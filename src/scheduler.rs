@@ -0,0 +1,72 @@
+use crate::db::card::Schedule;
+
+// Grades a card can get in a revision
+pub const AGAIN: u8 = 1;
+pub const HARD: u8 = 2;
+pub const GOOD: u8 = 3;
+pub const EASY: u8 = 4;
+
+const DAY: i64 = 24 * 60 * 60;
+const MIN_EASE: i32 = 1300;
+
+// Name of a grade
+pub fn grade_name(grade: u8) -> &'static str {
+    match grade {
+        AGAIN => "again",
+        HARD => "hard",
+        GOOD => "good",
+        EASY => "easy",
+        _ => "unknown",
+    }
+}
+
+// Schedule of a card after it was reviewed with grade at time now (SM-2)
+pub fn review(schedule: &Schedule, grade: u8, now: i64) -> Schedule {
+    let mut next = schedule.clone();
+    next.reps += 1;
+
+    match grade {
+        AGAIN => {
+            if schedule.reps > 0 {
+                next.lapses += 1;
+            }
+            next.interval = 0;
+            next.ease = (schedule.ease - 200).max(MIN_EASE);
+            next.due = now + 10 * 60;
+            return next;
+        }
+        HARD => {
+            next.interval = ((schedule.interval as f64 * 1.2) as i32).max(1);
+            next.ease = (schedule.ease - 150).max(MIN_EASE);
+        }
+        EASY => {
+            next.interval = (good_interval(schedule) as f64 * 1.3) as i32;
+            next.ease = schedule.ease + 150;
+        }
+        _ => {
+            next.interval = good_interval(schedule);
+        }
+    }
+    next.due = now + next.interval as i64 * DAY;
+
+    next
+}
+
+fn good_interval(schedule: &Schedule) -> i32 {
+    match schedule.interval {
+        0 => 1,
+        1 => 3,
+        interval => ((interval as f64 * schedule.ease as f64 / 1000.0) as i32).max(interval + 1),
+    }
+}
+
+// Check if a card was never reviewed
+pub fn is_new(schedule: &Schedule) -> bool {
+    schedule.reps == 0
+}
+
+// Check if a reviewed card is due at time now. New cards are counted apart
+// and never due, so due counts only the cards that are waiting on a review.
+pub fn is_due(schedule: &Schedule, now: i64) -> bool {
+    !is_new(schedule) && schedule.due <= now
+}
@@ -1,8 +1,15 @@
-use crate::config;
-use crate::db::card::Card;
+use crate::backup::{self, Backup};
+use crate::config::{self, Config, ConfigError, Kind};
+use crate::db::card::{Card, Schedule};
 use crate::db::stack::Stack;
-use crate::db::{card, init, stack};
-use rusqlite::Connection;
+use crate::db::Store;
+use crate::export::{self, Format};
+use crate::import::{self, csv::Column, Deck, Target};
+use crate::replace::{self, Change, Replace, Scope};
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use std::collections::HashSet;
+use std::fs;
 use tui::widgets::ListState;
 
 // Selected Window Enum
@@ -18,7 +25,16 @@ pub enum Selected {
     EditCard,
     RevisionTitle,
     RevisionText,
-    ConfigOptions,
+    Settings,
+    BulkActions,
+    MoveCards,
+    TagCards,
+    FindReplace,
+    ReplacePreview,
+    ImportFile,
+    ExportFile,
+    RestoreBackup,
+    PasteImport,
 }
 
 // Card Input Focus Enum
@@ -27,16 +43,117 @@ pub enum CardInputFocus {
     Text,
 }
 
-// Config focus
-pub enum ConfigFocus {
-    DbFile,
-    HighlightColor,
+// Sort order Enum
+#[derive(PartialEq)]
+pub enum SortOrder {
+    Default,
+    Recent,
+}
+
+// Sort order of a stack_sort setting
+fn sort_order(name: &str) -> SortOrder {
+    match name {
+        "recent" => SortOrder::Recent,
+        _ => SortOrder::Default,
+    }
+}
+
+// Card sort Enum
+#[derive(PartialEq)]
+pub enum CardSort {
+    Default,
+    Title,
+    Created,
+    // Last changed first
+    Recent,
+    Due,
+    Difficulty,
+    Lapses,
+}
+
+impl CardSort {
+    // Next sort in the cycle
+    pub fn next(&self) -> CardSort {
+        match self {
+            CardSort::Default => CardSort::Title,
+            CardSort::Title => CardSort::Created,
+            CardSort::Created => CardSort::Recent,
+            CardSort::Recent => CardSort::Due,
+            CardSort::Due => CardSort::Difficulty,
+            CardSort::Difficulty => CardSort::Lapses,
+            CardSort::Lapses => CardSort::Default,
+        }
+    }
+
+    // Name shown in the card list
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardSort::Default => "default",
+            CardSort::Title => "title",
+            CardSort::Created => "created",
+            CardSort::Recent => "recent",
+            CardSort::Due => "due",
+            CardSort::Difficulty => "difficulty",
+            CardSort::Lapses => "lapses",
+        }
+    }
+}
+
+// Find and replace focus Enum
+#[derive(PartialEq)]
+pub enum ReplaceFocus {
+    Find,
+    Replace,
+    Scope,
+    Regex,
+}
+
+// Find and replace scope Enum
+pub enum ReplaceScope {
+    Stack,
+    Tag,
+    All,
+}
+
+// Import focus Enum
+#[derive(PartialEq)]
+pub enum ImportFocus {
+    Path,
+    Delimiter,
+    Header,
+    Stack,
+    Columns,
+    History,
+}
+
+// Paste import focus Enum
+#[derive(PartialEq)]
+pub enum PasteFocus {
+    Text,
+    Term,
+    Card,
+}
+
+// Export focus Enum
+#[derive(PartialEq)]
+pub enum ExportFocus {
+    Path,
+    Format,
+    Scope,
+}
+
+// Export scope Enum
+#[derive(PartialEq)]
+pub enum ExportScope {
+    Stack,
+    SubStacks,
+    All,
 }
 
 pub struct App {
     pub items: Vec<Stack>,
     pub state: ListState,
-    pub db: Result<Connection, rusqlite::Error>,
+    pub db: Box<dyn Store>,
     pub selected_window: Selected,
     pub stack_name_input: String,
     pub card_title_input: String,
@@ -45,18 +162,72 @@ pub struct App {
     pub cards: Vec<Card>,
     pub cards_state: ListState,
     pub revision_index: usize,
-    pub config_input_1: String,
-    pub config_input_2: String,
-    pub config_input_focus: ConfigFocus,
-    pub highlight_color: u8,
+    pub theme: Theme,
+    pub keymap: Keymap,
+    // Profile of the collection, shown when not the default one
+    pub profile: String,
+    pub stack_sort: SortOrder,
+    pub card_sort: CardSort,
+    pub card_filter: String,
+    pub card_filter_focus: bool,
+    pub marked_cards: HashSet<i32>,
+    pub visual_anchor: Option<usize>,
+    pub visual_base: HashSet<i32>,
+    pub bulk_state: ListState,
+    pub move_state: ListState,
+    pub tag_input: String,
+    pub message: String,
+    pub replace_find: String,
+    pub replace_with: String,
+    pub replace_tag: String,
+    pub replace_regex: bool,
+    pub replace_scope: ReplaceScope,
+    pub replace_focus: ReplaceFocus,
+    pub replace_changes: Vec<Change>,
+    pub replace_state: ListState,
+    pub replace_error: String,
+    pub import_path: String,
+    pub import_delimiter: u8,
+    pub import_rows: Vec<Vec<String>>,
+    pub import_header: bool,
+    pub import_columns: Vec<Column>,
+    pub import_column: usize,
+    pub import_stack: Option<usize>,
+    pub import_stack_name: String,
+    pub import_focus: ImportFocus,
+    pub import_report: Vec<String>,
+    pub import_decks: Vec<Deck>,
+    pub import_history: bool,
+    pub paste_text: String,
+    pub paste_term: String,
+    pub paste_card: String,
+    pub paste_focus: PasteFocus,
+    pub paste_report: Vec<String>,
+    pub export_path: String,
+    pub export_format: Format,
+    pub export_scope: ExportScope,
+    pub export_focus: ExportFocus,
+    pub export_message: String,
+    pub backups: Vec<Backup>,
+    pub backups_state: ListState,
+    pub backup_message: String,
+    // Value of each setting of config::SETTINGS as typed
+    pub settings: Vec<String>,
+    pub settings_index: usize,
+    pub config_errors: Vec<ConfigError>,
 }
 
 impl App {
-    pub fn new() -> App {
+    pub fn new(db: Box<dyn Store>) -> App {
+        App::with_config(db, &config::load().0)
+    }
+
+    // App using the settings of a config instead of the config file
+    pub fn with_config(db: Box<dyn Store>, saved: &Config) -> App {
         App {
             items: vec![],
             state: ListState::default(),
-            db: init(format!("{}", config::get_db_file()).as_str()),
+            db,
             selected_window: Selected::Main,
             stack_name_input: String::new(),
             card_title_input: String::new(),
@@ -65,10 +236,57 @@ impl App {
             cards: vec![],
             cards_state: ListState::default(),
             revision_index: 0,
-            config_input_1: String::new(),
-            config_input_2: String::new(),
-            config_input_focus: ConfigFocus::DbFile,
-            highlight_color: config::get_highlight_color(),
+            theme: Theme::from_config(saved),
+            keymap: Keymap::new(&saved.keys).0,
+            profile: config::get_profile(),
+            stack_sort: sort_order(&saved.stack_sort),
+            card_sort: CardSort::Default,
+            card_filter: String::new(),
+            card_filter_focus: false,
+            marked_cards: HashSet::new(),
+            visual_anchor: None,
+            visual_base: HashSet::new(),
+            bulk_state: ListState::default(),
+            move_state: ListState::default(),
+            tag_input: String::new(),
+            message: String::new(),
+            replace_find: String::new(),
+            replace_with: String::new(),
+            replace_tag: String::new(),
+            replace_regex: false,
+            replace_scope: ReplaceScope::Stack,
+            replace_focus: ReplaceFocus::Find,
+            replace_changes: vec![],
+            replace_state: ListState::default(),
+            replace_error: String::new(),
+            import_path: String::new(),
+            import_delimiter: b',',
+            import_rows: vec![],
+            import_header: false,
+            import_columns: vec![],
+            import_column: 0,
+            import_stack: None,
+            import_stack_name: String::new(),
+            import_focus: ImportFocus::Path,
+            import_report: vec![],
+            import_decks: vec![],
+            import_history: true,
+            paste_text: String::new(),
+            paste_term: import::paste::TERM_SEPARATORS[0].1.to_string(),
+            paste_card: import::paste::CARD_SEPARATORS[0].1.to_string(),
+            paste_focus: PasteFocus::Text,
+            paste_report: vec![],
+            export_path: String::new(),
+            export_format: Format::Csv,
+            export_scope: ExportScope::Stack,
+            export_focus: ExportFocus::Path,
+            export_message: String::new(),
+            backups: vec![],
+            backups_state: ListState::default(),
+            backup_message: String::new(),
+            settings: vec![],
+            settings_index: 0,
+            config_errors: vec![],
         }
     }
 
@@ -77,43 +295,538 @@ impl App {
         let id = self.get_selected_card_id();
         let title = &self.card_title_input;
         let text = &self.card_text_input;
-        card::edit(
-            self.db.as_ref().unwrap(),
-            id,
-            title.to_string(),
-            text.to_string(),
-        )
+        self.db.edit_card(id, title.to_string(), text.to_string());
     }
 
     // Delete card
     pub fn delete_card(&mut self) {
         let id = self.get_selected_card_id();
-        card::delete(self.db.as_ref().unwrap(), id);
+        self.db.delete_card(id);
     }
 
     // Get selected card id
     pub fn get_selected_card_id(&mut self) -> i32 {
-        let _i = match self.cards_state.selected() {
-            Some(i) => return self.cards[i].id,
-            None => return 0,
-        };
+        match self.cards_state.selected() {
+            Some(i) => self.cards[i].id,
+            None => 0,
+        }
     }
 
-    // List cards
+    // List cards matching the filter in sort order
     pub fn list_cards(&mut self) {
         let stack_id = self.get_selected_id();
-        self.cards = card::list(self.db.as_ref().unwrap(), stack_id);
+        self.cards = self.db.cards(stack_id);
+
+        if !self.card_filter.is_empty() {
+            let filter = self.card_filter.to_lowercase();
+            self.cards.retain(|card| {
+                card.title.to_lowercase().contains(&filter)
+                    || card.text.to_lowercase().contains(&filter)
+            });
+        }
+
+        match self.card_sort {
+            CardSort::Default => {}
+            CardSort::Title => self.cards.sort_by_key(|i| i.title.to_lowercase()),
+            CardSort::Created => self.cards.sort_by_key(|i| std::cmp::Reverse(i.created_at)),
+            CardSort::Recent => self.cards.sort_by_key(|i| std::cmp::Reverse(i.updated_at)),
+            CardSort::Due => self.cards.sort_by_key(|i| i.schedule.due),
+            CardSort::Difficulty => self.cards.sort_by_key(|i| i.schedule.ease),
+            CardSort::Lapses => self.cards.sort_by_key(|i| std::cmp::Reverse(i.schedule.lapses)),
+        }
+    }
+
+    // Relist cards and keep the selected card selected if it is still listed. The
+    // range start of visual marking is a list index, so the range ends here.
+    pub fn refresh_cards(&mut self) {
+        let id = self.get_selected_card_id();
+        self.visual_anchor = None;
+        self.list_cards();
+        if self.cards.is_empty() {
+            self.cards_state.select(None);
+        } else {
+            let i = self.cards.iter().position(|card| card.id == id).unwrap_or(0);
+            self.cards_state.select(Some(i));
+        }
+    }
+
+    // Cycle card sort order
+    pub fn next_card_sort(&mut self) {
+        self.card_sort = self.card_sort.next();
+        self.refresh_cards();
+    }
+
+    // Mark or unmark the selected card
+    pub fn toggle_mark(&mut self) {
+        if self.cards_state.selected().is_some() {
+            let id = self.get_selected_card_id();
+            if !self.marked_cards.remove(&id) {
+                self.marked_cards.insert(id);
+            }
+        }
+    }
+
+    // Start or stop marking a range of cards
+    pub fn toggle_visual_mark(&mut self) {
+        match self.visual_anchor {
+            Some(_) => self.visual_anchor = None,
+            None => {
+                self.visual_anchor = self.cards_state.selected();
+                self.visual_base = self.marked_cards.clone();
+                self.update_visual_mark();
+            }
+        }
+    }
+
+    // Mark the cards between the range start and the selected card
+    pub fn update_visual_mark(&mut self) {
+        if let (Some(anchor), Some(i)) = (self.visual_anchor, self.cards_state.selected()) {
+            let (start, end) = if anchor < i { (anchor, i) } else { (i, anchor) };
+            self.marked_cards = self.visual_base.clone();
+            for card in &self.cards[start..=end] {
+                self.marked_cards.insert(card.id);
+            }
+        }
+    }
+
+    // Mark all listed cards, or unmark them if they are all marked
+    pub fn toggle_mark_all(&mut self) {
+        self.visual_anchor = None;
+        if self.cards.iter().all(|card| self.marked_cards.contains(&card.id)) {
+            self.marked_cards.clear();
+        } else {
+            self.marked_cards = self.cards.iter().map(|card| card.id).collect();
+        }
+    }
+
+    // Clear marks
+    pub fn clear_marks(&mut self) {
+        self.visual_anchor = None;
+        self.marked_cards.clear();
+    }
+
+    // Cards bulk actions apply to, the marked cards or else the selected card
+    pub fn bulk_cards(&self) -> Vec<Card> {
+        if self.marked_cards.is_empty() {
+            match self.cards_state.selected() {
+                Some(i) => vec![self.cards[i].clone()],
+                None => vec![],
+            }
+        } else {
+            self.cards
+                .iter()
+                .filter(|card| self.marked_cards.contains(&card.id))
+                .cloned()
+                .collect()
+        }
+    }
+
+    // Delete marked cards
+    pub fn bulk_delete(&mut self) {
+        let ids: Vec<i32> = self.bulk_cards().iter().map(|card| card.id).collect();
+        self.db.delete_cards(&ids);
+        self.message = format!("Deleted {} cards", ids.len());
+        self.clear_marks();
+        self.refresh_cards();
+    }
+
+    // Move marked cards to another stack
+    pub fn bulk_move(&mut self, stack_id: i32) {
+        let mut cards = self.bulk_cards();
+        for card in cards.iter_mut() {
+            card.stack_id = stack_id;
+        }
+        self.db.update_cards(&cards);
+        self.message = format!("Moved {} cards", cards.len());
+        self.clear_marks();
+        self.refresh_cards();
+    }
+
+    // Add tags to marked cards, tags starting with "-" are removed
+    pub fn bulk_tag(&mut self, input: &str) {
+        let mut cards = self.bulk_cards();
+        for card in cards.iter_mut() {
+            for tag in input.split_whitespace() {
+                match tag.strip_prefix('-') {
+                    Some(tag) => card.remove_tag(tag),
+                    None => card.add_tag(tag),
+                }
+            }
+        }
+        self.db.update_cards(&cards);
+        self.message = format!("Tagged {} cards", cards.len());
+        self.refresh_cards();
+    }
+
+    // Suspend marked cards, or unsuspend them if they are all suspended
+    pub fn bulk_toggle_suspend(&mut self) {
+        let mut cards = self.bulk_cards();
+        let suspend = !cards.iter().all(|card| card.suspended);
+        for card in cards.iter_mut() {
+            card.suspended = suspend;
+        }
+        self.db.update_cards(&cards);
+        self.message = match suspend {
+            true => format!("Suspended {} cards", cards.len()),
+            false => format!("Unsuspended {} cards", cards.len()),
+        };
+        self.refresh_cards();
+    }
+
+    // Reset scheduling of marked cards
+    pub fn bulk_reset(&mut self) {
+        let mut cards = self.bulk_cards();
+        for card in cards.iter_mut() {
+            card.schedule = Schedule::default();
+        }
+        self.db.update_cards(&cards);
+        self.message = format!("Reset {} cards", cards.len());
+        self.refresh_cards();
+    }
+
+    // Export marked cards as JSON into the export directory
+    pub fn bulk_export(&mut self) {
+        let cards = self.bulk_cards();
+        let stack = self.items[self.state.selected().unwrap_or(0)].clone();
+        // Named after the card, or the stack and the number of cards
+        let name = match cards.as_slice() {
+            [card] => card.title.to_string(),
+            _ => format!("{} {} cards", stack.name, cards.len()),
+        };
+        let dir = config::get_export_dir();
+        let path = dir.join(format!(
+            "{}-{}.json",
+            export::file_name(&name),
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let content = export::json::to_string(&[(stack, cards)]);
+        self.message = match fs::create_dir_all(&dir).and_then(|_| fs::write(&path, content)) {
+            Ok(_) => format!("Exported to {}", path.display()),
+            Err(err) => format!("Export failed: {}", err),
+        };
+    }
+
+    // Preview find and replace, returns false if the query is invalid
+    pub fn preview_replace(&mut self) -> bool {
+        let scope = match self.replace_scope {
+            ReplaceScope::Stack => Scope::Stack(self.get_selected_id()),
+            ReplaceScope::Tag => Scope::Tag(self.replace_tag.trim().to_string()),
+            ReplaceScope::All => Scope::All,
+        };
+        let query = Replace {
+            find: self.replace_find.to_string(),
+            replace: self.replace_with.to_string(),
+            regex: self.replace_regex,
+            scope,
+        };
+        match replace::preview(self.db.as_ref(), &query) {
+            Ok(changes) => {
+                self.replace_changes = changes;
+                self.replace_error = String::new();
+                self.replace_state.select(None);
+                true
+            }
+            Err(err) => {
+                self.replace_error = err.to_string();
+                false
+            }
+        }
+    }
+
+    // Apply previewed find and replace
+    pub fn apply_replace(&mut self) {
+        replace::apply(self.db.as_mut(), &self.replace_changes);
+        self.replace_changes = vec![];
+    }
+
+    // Read the import file, detecting the delimiter unless one is given
+    pub fn load_import(&mut self, delimiter: Option<u8>) {
+        self.import_rows = vec![];
+        self.import_columns = vec![];
+        self.import_column = 0;
+        self.import_report = vec![];
+        self.import_decks = vec![];
+
+        let path = import::expand_path(self.import_path.trim());
+        if import::anki::is_package(&path) {
+            match import::anki::read(&path) {
+                Ok(decks) => self.import_decks = decks,
+                Err(err) => self.import_report = vec![format!("Can't read {}: {}", path, err)],
+            }
+            return;
+        }
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) => {
+                self.import_report = vec![format!("Can't read {}: {}", path, err)];
+                return;
+            }
+        };
+        if import::json::is_json(&path) {
+            match import::json::parse(&content) {
+                Ok(decks) if decks.is_empty() => self.import_report = vec![format!("No stacks in {}", path)],
+                Ok(decks) => self.import_decks = decks,
+                Err(err) => self.import_report = vec![format!("Can't parse {}: {}", path, err)],
+            }
+            return;
+        }
+        if import::org::is_org(&path) {
+            self.import_decks = import::org::parse(&content, &import::markdown::stack_name(&path));
+            if self.import_decks.is_empty() {
+                self.import_report = vec![format!("No drill cards in {}", path)];
+            }
+            return;
+        }
+        if import::markdown::is_markdown(&path) {
+            self.import_decks = import::markdown::parse(&content, &import::markdown::stack_name(&path));
+            if self.import_decks.is_empty() {
+                self.import_report = vec![format!("No cards in {}", path)];
+            }
+            return;
+        }
+        self.import_delimiter = delimiter.unwrap_or_else(|| import::csv::detect_delimiter(&path, &content));
+        match import::csv::parse(&content, self.import_delimiter) {
+            Ok(rows) => {
+                if delimiter.is_none() {
+                    self.import_header = import::csv::detect_header(&rows);
+                }
+                self.import_columns = import::csv::default_columns(&rows, self.import_header);
+                self.import_rows = rows;
+            }
+            Err(err) => self.import_report = vec![format!("Can't parse {}: {}", path, err)],
+        }
+    }
+
+    // Import the loaded rows and report what was left out
+    pub fn run_import(&mut self) {
+        if !self.import_decks.is_empty() {
+            self.run_deck_import();
+            return;
+        }
+        let target = match self.import_stack {
+            Some(i) => Target::Stack(self.items[i].id),
+            None => Target::New(self.import_stack_name.trim().to_string()),
+        };
+        let report = import::csv::import(
+            self.db.as_mut(),
+            &target,
+            &self.import_rows,
+            &self.import_columns,
+            self.import_header,
+        );
+        self.import_report = vec![format!(
+            "Imported {} cards, skipped {} rows",
+            report.added,
+            report.skipped.len()
+        )];
+        self.import_report.extend(
+            report
+                .skipped
+                .iter()
+                .map(|skipped| format!("row {}: {}", skipped.row, skipped.reason)),
+        );
+        self.import_rows = vec![];
+        self.get_items();
+    }
+
+    // Import the loaded decks and report what was left out
+    fn run_deck_import(&mut self) {
+        let decks = std::mem::take(&mut self.import_decks);
+        let reports = import::import_decks(self.db.as_mut(), decks, self.import_history);
+        self.import_report = vec![format!(
+            "Imported {} cards into {} stacks, skipped {} cards",
+            reports.iter().map(|(_, report)| report.added).sum::<usize>(),
+            reports.len(),
+            reports.iter().map(|(_, report)| report.skipped.len()).sum::<usize>()
+        )];
+        for (name, report) in &reports {
+            self.import_report.push(format!(
+                "{}: {} added, {} skipped",
+                name,
+                report.added,
+                report.skipped.len()
+            ));
+        }
+        for (name, report) in &reports {
+            self.import_report.extend(
+                report
+                    .skipped
+                    .iter()
+                    .map(|skipped| format!("{} card {}: {}", name, skipped.row, skipped.reason)),
+            );
+        }
+        self.get_items();
+    }
+
+    // Import the pasted cards into the selected stack
+    pub fn run_paste_import(&mut self) {
+        let target = Target::Stack(self.get_selected_id());
+        let report = import::paste::import(
+            self.db.as_mut(),
+            &target,
+            &self.paste_text,
+            &self.paste_term,
+            &self.paste_card,
+        );
+        self.paste_report = vec![format!(
+            "Imported {} cards into {}, skipped {}",
+            report.added,
+            self.get_selected_name(),
+            report.skipped.len()
+        )];
+        self.paste_report.extend(
+            report
+                .skipped
+                .iter()
+                .map(|skipped| format!("card {}: {}", skipped.row, skipped.reason)),
+        );
+        self.paste_text = String::new();
+    }
+
+    // Default export file name for the export scope and format
+    pub fn default_export_path(&mut self) {
+        let name = match self.export_scope {
+            ExportScope::All => "cards".to_string(),
+            _ => export::file_name(&self.get_selected_name()),
+        };
+        self.export_path = format!("{}.{}", name, self.export_format.extension());
+    }
+
+    // Export the selected stack or every stack
+    pub fn run_export(&mut self) {
+        let stack_id = match self.export_scope {
+            ExportScope::All => None,
+            _ => Some(self.get_selected_id()),
+        };
+        let substacks = self.export_scope == ExportScope::SubStacks;
+        let path = import::expand_path(self.export_path.trim());
+        self.export_message = match export::to_file(self.db.as_ref(), stack_id, substacks, &path, self.export_format) {
+            Ok(count) => format!("Exported {} cards to {}", count, path),
+            Err(err) => format!("Export failed: {}", err),
+        };
+    }
+
+    // Open the settings with the saved values and their errors
+    pub fn open_settings(&mut self) {
+        let (saved, mut errors) = config::load();
+        let file = config::get_config_file();
+        self.settings = config::SETTINGS
+            .iter()
+            .map(|setting| {
+                config::setting_text(&saved, setting.key).unwrap_or_else(|reason| {
+                    errors.push(ConfigError {
+                        file: file.clone(),
+                        key: Some(setting.key.to_string()),
+                        reason,
+                    });
+                    String::new()
+                })
+            })
+            .collect();
+        self.config_errors = errors;
+        self.selected_window = Selected::Settings;
+    }
+
+    // Change the selected setting to the next or previous value of its kind
+    pub fn step_setting(&mut self, forward: bool) {
+        let setting = &config::SETTINGS[self.settings_index];
+        let text = &mut self.settings[self.settings_index];
+        match setting.kind {
+            Kind::Bool => *text = (text != "true").to_string(),
+            Kind::Choice(choices) => {
+                let i = choices.iter().position(|choice| choice == text).unwrap_or(0);
+                let i = match forward {
+                    true => (i + 1) % choices.len(),
+                    false => (i + choices.len() - 1) % choices.len(),
+                };
+                *text = choices[i].to_string();
+            }
+            Kind::Number { .. } | Kind::Color => {
+                let (min, max) = match setting.kind {
+                    Kind::Number { min, max } => (min, max),
+                    _ => (0, 255),
+                };
+                if let Ok(number) = text.trim().parse::<i64>() {
+                    let number = if forward { number + 1 } else { number - 1 };
+                    *text = number.clamp(min, max).to_string();
+                }
+            }
+            Kind::Text => {}
+        }
+    }
+
+    // Save the settings and use them, keeping the window open on errors
+    pub fn save_settings(&mut self) -> bool {
+        let values: Vec<(&str, &str)> = config::SETTINGS
+            .iter()
+            .zip(&self.settings)
+            .map(|(setting, text)| (setting.key, text.as_str()))
+            .collect();
+        let (db_file, backups) = (config::get_db_file(), config::load().0.backups);
+        if let Err(errors) = config::set_config(&values) {
+            self.config_errors = errors;
+            return false;
+        }
+
+        let saved = config::load().0;
+        self.config_errors.clear();
+        // The store is only reopened for another database or backup setting, a
+        // database that can't be opened leaves the one in use
+        if config::get_db_file() != db_file || saved.backups != backups {
+            match backup::open_store(false) {
+                Ok(store) => self.db = Box::new(store),
+                Err(err) => self.config_errors.push(ConfigError {
+                    file: config::get_config_file(),
+                    key: Some("db_file".to_string()),
+                    reason: format!("could not be opened, still using {}: {}", db_file, err),
+                }),
+            }
+        }
+        self.theme = Theme::from_config(&saved);
+        self.keymap = Keymap::new(&saved.keys).0;
+        self.stack_sort = sort_order(&saved.stack_sort);
+        self.state.select(None);
+        self.get_items();
+        self.config_errors.is_empty()
+    }
+
+    // List the backups, selecting the newest
+    pub fn load_backups(&mut self) {
+        self.backups = backup::list(&config::get_backup_dir());
+        self.backups_state.select(match self.backups.is_empty() {
+            true => None,
+            false => Some(0),
+        });
+    }
+
+    // Replace the collection with the selected backup
+    pub fn restore_backup(&mut self) {
+        let i = match self.backups_state.selected() {
+            Some(i) => i,
+            None => return,
+        };
+        let name = backup::name(&self.backups[i]);
+        self.backup_message = match self.db.restore(&self.backups[i].path) {
+            Ok(()) => format!("Restored {}", name),
+            Err(err) => format!("Restore failed: {}", err),
+        };
+        self.get_items();
+        self.state.select(match self.items.is_empty() {
+            true => None,
+            false => Some(0),
+        });
+        self.load_backups();
     }
 
     // Add card
     pub fn add_card(&mut self, title: String, text: String) {
         let stack_id = self.get_selected_id();
-        card::add(self.db.as_ref().unwrap(), stack_id, title, text);
+        self.db.add_card(stack_id, title, text);
     }
 
     // Next card
     pub fn next_card(&mut self) {
-        if self.cards.len() > 0 {
+        if !self.cards.is_empty() {
             let i = match self.cards_state.selected() {
                 Some(i) => {
                     if i >= self.cards.len() - 1 {
@@ -130,7 +843,7 @@ impl App {
 
     // Previous card
     pub fn back_card(&mut self) {
-        if self.cards.len() > 0 {
+        if !self.cards.is_empty() {
             let i = match self.cards_state.selected() {
                 Some(i) => {
                     if i == 0 {
@@ -147,45 +860,61 @@ impl App {
 
     // Get stacks
     pub fn get_items(&mut self) {
-        self.items = stack::get_all(self.db.as_ref().unwrap());
+        self.items = self.db.stacks();
+        if self.stack_sort == SortOrder::Recent {
+            self.items.sort_by_key(|i| std::cmp::Reverse(i.created_at));
+        }
+    }
+
+    // Toggle stack sort order and keep the selected stack selected
+    pub fn toggle_stack_sort(&mut self) {
+        let selected = self.state.selected().map(|_| self.get_selected_id());
+        self.stack_sort = match self.stack_sort {
+            SortOrder::Default => SortOrder::Recent,
+            SortOrder::Recent => SortOrder::Default,
+        };
+        self.get_items();
+        if let Some(id) = selected {
+            self.state.select(self.items.iter().position(|stack| stack.id == id));
+        }
     }
 
     // Add stack
     pub fn add_stack(&mut self, name: String) {
-        stack::add(self.db.as_ref().unwrap(), name);
+        self.db.add_stack(name);
     }
 
     // Delete stack
     pub fn delete_stack(&mut self, id: i32) {
-        stack::delete(self.db.as_ref().unwrap(), id);
+        self.db.delete_stack(id);
     }
 
     // Edit stack
     pub fn edit_stack(&mut self) {
         let id = self.get_selected_id();
         let name = &self.stack_name_input;
-        stack::edit(self.db.as_ref().unwrap(), id, name.to_string());
+        self.db.edit_stack(id, name.to_string());
     }
 
     // Get id from selected stack
     pub fn get_selected_id(&mut self) -> i32 {
-        let _i = match self.state.selected() {
-            Some(i) => return self.items[i].id,
-            None => return 0,
-        };
+        match self.state.selected() {
+            Some(i) => self.items[i].id,
+            None => 0,
+        }
     }
 
     // Get name from selected stack
     pub fn get_selected_name(&mut self) -> String {
-        let _i = match self.state.selected() {
-            Some(i) => return self.items[i].name.to_string(),
-            None => return "".to_string(),
-        };
+        match self.state.selected() {
+            Some(i) => self.items[i].name.to_string(),
+            None => "".to_string(),
+        }
     }
 
     // Select next stack
     pub fn next(&mut self) {
-        if self.items.len() > 0 {
+        if !self.items.is_empty() {
             let i = match self.state.selected() {
                 Some(i) => {
                     if i == 0 {
@@ -202,7 +931,7 @@ impl App {
 
     // Select previous stack
     pub fn back(&mut self) {
-        if self.items.len() > 0 {
+        if !self.items.is_empty() {
             let i = match self.state.selected() {
                 Some(i) => {
                     if i >= self.items.len() - 1 {
@@ -0,0 +1,360 @@
+use crate::db::card::{Card, Schedule};
+use crate::db::stack::Stack;
+use crate::db::Store;
+use crate::export::{self, markdown};
+use crate::import::markdown::{self as parse, normalize_text, normalize_title};
+use crate::import::NewCard;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Two-way sync between the collection and a directory of Markdown deck files
+//
+// Cards in the files carry their id in a "<!-- id: 12 -->" line. The content
+// of every card at the last sync is kept as a hash in a state file in the
+// directory, which tells which side changed a card since. Cards changed on
+// both sides are conflicts and are left as they are on both sides.
+
+// File in the deck directory keeping the state of the last sync
+pub const STATE_FILE: &str = ".cards-sync.json";
+
+// Cards and stacks as they were after the last sync
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+    // Hash of the content of each card by id
+    cards: HashMap<i32, String>,
+    // Stacks that had a file
+    stacks: Vec<String>,
+}
+
+// Outcome of a sync
+#[derive(Default)]
+pub struct Report {
+    pub added_to_db: usize,
+    pub updated_in_db: usize,
+    pub deleted_from_db: usize,
+    pub added_to_files: usize,
+    pub updated_in_files: usize,
+    pub deleted_from_files: usize,
+    pub conflicts: Vec<String>,
+}
+
+// Card read from a deck file
+struct FileCard {
+    path: PathBuf,
+    stack: String,
+    card: NewCard,
+}
+
+// Hash of what a deck file keeps of a card
+fn hash(stack: &str, title: &str, text: &str, tags: &str) -> String {
+    let tags = tags.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let content = format!(
+        "{}\x1f{}\x1f{}\x1f{}",
+        normalize_title(stack),
+        normalize_title(title),
+        normalize_text(text),
+        tags
+    );
+    sha1_smol::Sha1::from(content).digest().to_string()
+}
+
+// Hash of a card in the collection
+fn db_hash(stack: &str, card: &Card) -> String {
+    hash(stack, &card.title, &card.text, &card.tags)
+}
+
+// Hash of a card in a deck file
+fn file_hash(file_card: &FileCard) -> String {
+    let card = &file_card.card;
+    hash(&file_card.stack, &card.title, &card.text, &card.tags.join(" "))
+}
+
+// Whether a stack is in one of the files
+fn has_file(files: &[(PathBuf, Vec<String>)], name: &str) -> bool {
+    files.iter().any(|(_, names)| names.iter().any(|other| other == name))
+}
+
+// Id of the stack with a name, adding it if needed
+fn stack_id(store: &mut dyn Store, stacks: &mut Vec<Stack>, name: &str) -> i32 {
+    if let Some(stack) = stacks.iter().find(|stack| normalize_title(&stack.name) == name) {
+        return stack.id;
+    }
+    store.add_stack(name.to_string());
+    *stacks = store.stacks();
+    stacks.iter().find(|stack| stack.name == name).unwrap().id
+}
+
+// Sync the collection with the Markdown files in dir
+pub fn sync(store: &mut dyn Store, dir: &str) -> io::Result<Report> {
+    fs::create_dir_all(dir)?;
+    let state_path = Path::new(dir).join(STATE_FILE);
+    let state: State = fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let mut report = Report::default();
+
+    // Read the deck files, a stack belongs to the first file it is in
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && parse::is_markdown(&path.to_string_lossy()))
+        .collect();
+    paths.sort();
+    let mut files: Vec<(PathBuf, Vec<String>)> = vec![];
+    let mut file_cards: Vec<FileCard> = vec![];
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        let mut names = vec![];
+        for deck in parse::parse(&content, &parse::stack_name(&path.to_string_lossy())) {
+            if !files.iter().any(|(_, other)| other.contains(&deck.name)) && !names.contains(&deck.name) {
+                names.push(deck.name.to_string());
+            }
+            for (_, card) in deck.cards {
+                file_cards.push(FileCard {
+                    path: path.clone(),
+                    stack: deck.name.to_string(),
+                    card,
+                });
+            }
+        }
+        files.push((path, names));
+    }
+
+    // Later copies of an id are new cards
+    let mut file_index: HashMap<i32, usize> = HashMap::new();
+    for (i, file_card) in file_cards.iter_mut().enumerate() {
+        if let Some(id) = file_card.card.id {
+            match file_index.entry(id) {
+                Entry::Occupied(_) => file_card.card.id = None,
+                Entry::Vacant(entry) => {
+                    entry.insert(i);
+                }
+            }
+        }
+    }
+
+    // Cards of the collection with the name of their stack
+    let mut stacks = store.stacks();
+    let mut db_cards: HashMap<i32, (String, Card)> = HashMap::new();
+    for stack in &stacks {
+        for card in store.cards(stack.id) {
+            db_cards.insert(card.id, (normalize_title(&stack.name), card));
+        }
+    }
+
+    // Compare both sides with the last sync
+    let mut ids: Vec<i32> = state
+        .cards
+        .keys()
+        .chain(file_index.keys())
+        .chain(db_cards.keys())
+        .cloned()
+        .collect::<HashSet<i32>>()
+        .into_iter()
+        .collect();
+    ids.sort();
+    let mut conflicts: HashSet<i32> = HashSet::new();
+    let mut new_from_files: Vec<usize> = file_cards
+        .iter()
+        .enumerate()
+        .filter(|(_, file_card)| file_card.card.id.is_none())
+        .map(|(i, _)| i)
+        .collect();
+    let mut updates: Vec<Card> = vec![];
+    let mut deletes: Vec<i32> = vec![];
+    for id in ids {
+        let file_card = file_index.get(&id).map(|i| &file_cards[*i]);
+        let db_card = db_cards.get(&id);
+        let base = state.cards.get(&id);
+        match (file_card, db_card) {
+            (Some(file_card), Some((stack, card))) => {
+                let file_hash = file_hash(file_card);
+                let db_hash = db_hash(stack, card);
+                if file_hash == db_hash {
+                    continue;
+                }
+                if base == Some(&db_hash) {
+                    let mut card = card.clone();
+                    card.title = file_card.card.title.to_string();
+                    card.text = file_card.card.text.to_string();
+                    card.tags = file_card.card.tags.join(" ");
+                    card.stack_id = stack_id(store, &mut stacks, &file_card.stack);
+                    updates.push(card);
+                } else if base == Some(&file_hash) {
+                    report.updated_in_files += 1;
+                } else {
+                    conflicts.insert(id);
+                    report.conflicts.push(format!(
+                        "card {} \"{}\" was changed in {} and in the collection",
+                        id,
+                        card.title,
+                        file_card.path.display()
+                    ));
+                }
+            }
+            (Some(file_card), None) => {
+                if base == Some(&file_hash(file_card)) {
+                    report.deleted_from_files += 1;
+                } else if base.is_some() {
+                    conflicts.insert(id);
+                    report.conflicts.push(format!(
+                        "card {} \"{}\" was deleted from the collection but changed in {}",
+                        id,
+                        file_card.card.title,
+                        file_card.path.display()
+                    ));
+                } else {
+                    // Unknown id, like from another collection
+                    new_from_files.push(file_index[&id]);
+                }
+            }
+            (None, Some((stack, card))) => {
+                if base == Some(&db_hash(stack, card)) {
+                    deletes.push(id);
+                } else if base.is_some() {
+                    conflicts.insert(id);
+                    report.conflicts.push(format!(
+                        "card {} \"{}\" was deleted from the files but changed in the collection",
+                        id, card.title
+                    ));
+                } else {
+                    report.added_to_files += 1;
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    // Apply the changes of the files to the collection, keeping the schedule of cards
+    report.updated_in_db = updates.len();
+    store.update_cards(&updates);
+    report.deleted_from_db = deletes.len();
+    store.delete_cards(&deletes);
+    new_from_files.sort();
+    for i in new_from_files {
+        let stack_id = stack_id(store, &mut stacks, &file_cards[i].stack);
+        let card = &file_cards[i].card;
+        let id = store.add_card(stack_id, card.title.to_string(), card.text.to_string());
+        if !card.tags.is_empty() {
+            if let Some(mut added) = store.cards(stack_id).into_iter().find(|added| added.id == id) {
+                added.tags = card.tags.join(" ");
+                store.update_cards(&[added]);
+            }
+        }
+        file_cards[i].card.id = Some(id);
+        report.added_to_db += 1;
+    }
+
+    // Stacks whose file was deleted are deleted once they have no cards left
+    for stack in store.stacks() {
+        let name = normalize_title(&stack.name);
+        if state.stacks.contains(&name) && !has_file(&files, &name) && store.cards(stack.id).is_empty() {
+            store.delete_stack(stack.id);
+        }
+    }
+
+    // Write the files from the collection, conflicts keep what the files have
+    let stacks = store.stacks();
+    let mut final_cards: HashMap<i32, (String, Card)> = HashMap::new();
+    for stack in &stacks {
+        for card in store.cards(stack.id) {
+            final_cards.insert(card.id, (normalize_title(&stack.name), card));
+        }
+    }
+    for stack in &stacks {
+        let name = normalize_title(&stack.name);
+        if !has_file(&files, &name) {
+            let has_cards = final_cards.values().any(|(other, card)| *other == name && !conflicts.contains(&card.id));
+            if !state.stacks.contains(&name) || has_cards {
+                let mut path = Path::new(dir).join(format!("{}.md", export::file_name(&name)));
+                if files.iter().any(|(other, _)| *other == path) {
+                    path = Path::new(dir).join(format!("{}-{}.md", export::file_name(&name), stack.id));
+                }
+                files.push((path, vec![name]));
+            }
+        }
+    }
+
+    let mut emitted: HashSet<i32> = HashSet::new();
+    let mut new_state = State::default();
+    for (path, names) in &files {
+        let mut sections = vec![];
+        for name in names {
+            let mut section = format!("# {}\n", name);
+            let mut cards = 0;
+            for file_card in file_cards.iter().filter(|file_card| file_card.path == *path && file_card.stack == *name) {
+                let id = match file_card.card.id {
+                    Some(id) => id,
+                    None => continue,
+                };
+                if conflicts.contains(&id) {
+                    let card = Card {
+                        id,
+                        title: file_card.card.title.to_string(),
+                        text: file_card.card.text.to_string(),
+                        stack_id: 0,
+                        created_at: 0,
+                        updated_at: 0,
+                        tags: file_card.card.tags.join(" "),
+                        suspended: false,
+                        schedule: Schedule::default(),
+                    };
+                    section.push_str(&markdown::card_to_string(&card, &[("id", id.to_string())]));
+                    emitted.insert(id);
+                    cards += 1;
+                } else if let Some((stack, card)) = final_cards.get(&id) {
+                    if stack == name && emitted.insert(id) {
+                        section.push_str(&markdown::card_to_string(card, &[("id", id.to_string())]));
+                        new_state.cards.insert(id, db_hash(stack, card));
+                        cards += 1;
+                    }
+                }
+            }
+
+            // Cards added to the collection or moved to this stack
+            let mut rest: Vec<&(String, Card)> = final_cards
+                .values()
+                .filter(|(stack, card)| stack == name && !emitted.contains(&card.id) && !conflicts.contains(&card.id))
+                .collect();
+            rest.sort_by_key(|(_, card)| card.id);
+            for (stack, card) in rest {
+                section.push_str(&markdown::card_to_string(card, &[("id", card.id.to_string())]));
+                new_state.cards.insert(card.id, db_hash(stack, card));
+                emitted.insert(card.id);
+                cards += 1;
+            }
+
+            let in_db = stacks.iter().any(|stack| normalize_title(&stack.name) == *name);
+            if in_db || cards > 0 {
+                new_state.stacks.push(name.to_string());
+                sections.push(section);
+            }
+        }
+
+        // Files of stacks that were deleted from the collection go with them
+        if sections.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            continue;
+        }
+        let content = sections.join("\n");
+        if fs::read_to_string(path).ok().as_deref() != Some(content.as_str()) {
+            fs::write(path, content)?;
+        }
+    }
+
+    // Conflicts keep the state of the last sync until they are resolved
+    for id in &conflicts {
+        if let Some(hash) = state.cards.get(id) {
+            new_state.cards.insert(*id, hash.to_string());
+        }
+    }
+    fs::write(state_path, serde_json::to_string_pretty(&new_state).unwrap())?;
+
+    Ok(report)
+}
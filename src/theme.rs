@@ -0,0 +1,206 @@
+use crate::config::Config;
+use std::env;
+use tui::style::{Color, Modifier, Style};
+
+// Styles of the ui, picked with the theme setting. The highlight color of the
+// config is used for focused windows in every theme but mono, which has no
+// colors and is always used when NO_COLOR is set (https://no-color.org).
+// The [colors] table of the config replaces colors of the theme by style name.
+
+// Names of the built-in themes
+pub const THEMES: &[&str] = &["default", "light", "high-contrast", "mono"];
+
+// Names of the styles a [colors] table can change
+pub const STYLES: &[&str] = &["text", "border", "highlight", "title", "selection", "error", "success", "popup"];
+
+// Named terminal colors, the others are given by number
+const COLORS: &[(&str, Color)] = &[
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("gray", Color::Gray),
+    ("darkgray", Color::DarkGray),
+    ("lightred", Color::LightRed),
+    ("lightgreen", Color::LightGreen),
+    ("lightyellow", Color::LightYellow),
+    ("lightblue", Color::LightBlue),
+    ("lightmagenta", Color::LightMagenta),
+    ("lightcyan", Color::LightCyan),
+    ("white", Color::White),
+];
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    // Text and values
+    pub text: Style,
+    // Borders of windows without focus
+    pub border: Style,
+    // Borders of the window with focus and highlighted text
+    pub highlight: Style,
+    pub title: Style,
+    // Selected item of a list
+    pub selection: Style,
+    pub error: Style,
+    // Added text, like in the find and replace preview
+    pub success: Style,
+    // Background of popup windows, drawn over the windows below
+    pub popup: Style,
+    // Whether colors may be shown as samples
+    pub colors: bool,
+}
+
+impl Theme {
+    // Built-in theme by name, the default one for unknown names
+    pub fn named(name: &str, highlight_color: u8) -> Theme {
+        let highlight = Style::default().fg(Color::Indexed(highlight_color));
+        match name {
+            "light" => Theme {
+                text: Style::default().fg(Color::Black),
+                border: Style::default().fg(Color::DarkGray),
+                highlight,
+                title: Style::default().fg(Color::Black),
+                selection: Style::default().fg(Color::White).bg(Color::Black),
+                error: Style::default().fg(Color::Red),
+                success: Style::default().fg(Color::Indexed(22)),
+                popup: Style::default().bg(Color::Indexed(255)),
+                colors: true,
+            },
+            "high-contrast" => Theme {
+                text: Style::default().fg(Color::Indexed(15)),
+                border: Style::default().fg(Color::Indexed(15)),
+                highlight: highlight.add_modifier(Modifier::BOLD),
+                title: Style::default().fg(Color::Indexed(11)).add_modifier(Modifier::BOLD),
+                selection: Style::default().fg(Color::Black).bg(Color::Indexed(11)),
+                error: Style::default().fg(Color::Indexed(9)).add_modifier(Modifier::BOLD),
+                success: Style::default().fg(Color::Indexed(10)).add_modifier(Modifier::BOLD),
+                popup: Style::default().bg(Color::Black),
+                colors: true,
+            },
+            "mono" => Theme {
+                text: Style::default(),
+                border: Style::default(),
+                highlight: Style::default().add_modifier(Modifier::BOLD),
+                title: Style::default().add_modifier(Modifier::BOLD),
+                selection: Style::default().add_modifier(Modifier::REVERSED),
+                error: Style::default().add_modifier(Modifier::BOLD),
+                success: Style::default().add_modifier(Modifier::UNDERLINED),
+                popup: Style::default(),
+                colors: false,
+            },
+            _ => Theme {
+                text: Style::default().fg(Color::White),
+                border: Style::default().fg(Color::White),
+                highlight,
+                title: Style::default().fg(Color::White),
+                selection: Style::default().fg(Color::Black).bg(Color::White),
+                error: Style::default().fg(Color::Indexed(1)),
+                success: Style::default().fg(Color::Indexed(2)),
+                popup: Style::default(),
+                colors: true,
+            },
+        }
+    }
+
+    // Theme of the config, mono when NO_COLOR is set
+    pub fn from_config(config: &Config) -> Theme {
+        Theme::with_no_color(config, env::var("NO_COLOR").ok().as_deref())
+    }
+
+    // Theme of the config for a value of NO_COLOR, mono unless it is unset or empty
+    pub fn with_no_color(config: &Config, no_color: Option<&str>) -> Theme {
+        match no_color {
+            Some(value) if !value.is_empty() => Theme::named("mono", config.highlight_color),
+            _ => Theme::named(&config.theme, config.highlight_color).with_colors(&config.colors).0,
+        }
+    }
+
+    // Theme with the colors of a [colors] table, with the errors of the table as
+    // setting and reason. Mono keeps having no colors.
+    pub fn with_colors(mut self, colors: &toml::Table) -> (Theme, Vec<(String, String)>) {
+        let mut errors = vec![];
+        let has_colors = self.colors;
+        for (name, value) in colors {
+            let setting = format!("colors.{}", name);
+            let (fg, bg) = match parse_colors(value) {
+                Ok(colors) => colors,
+                Err(reason) => {
+                    errors.push((setting, reason));
+                    continue;
+                }
+            };
+            let style = match self.style_mut(name) {
+                Some(style) => style,
+                None => {
+                    errors.push((setting, format!("unknown style, use one of {}", STYLES.join(", "))));
+                    continue;
+                }
+            };
+            if has_colors {
+                *style = Style { fg: fg.or(style.fg), bg: bg.or(style.bg), ..*style };
+            }
+        }
+        (self, errors)
+    }
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        match name {
+            "text" => Some(&mut self.text),
+            "border" => Some(&mut self.border),
+            "highlight" => Some(&mut self.highlight),
+            "title" => Some(&mut self.title),
+            "selection" => Some(&mut self.selection),
+            "error" => Some(&mut self.error),
+            "success" => Some(&mut self.success),
+            "popup" => Some(&mut self.popup),
+            _ => None,
+        }
+    }
+
+    // Border of a window, highlighted when it has focus
+    pub fn border(&self, focused: bool) -> Style {
+        match focused {
+            true => self.highlight,
+            false => self.border,
+        }
+    }
+}
+
+// Check the [colors] table of the config, the errors as setting and reason
+pub fn check_colors(colors: &toml::Table) -> Vec<(String, String)> {
+    Theme::named("default", 0).with_colors(colors).1
+}
+
+// Color of a style: a terminal color number from 0 to 255 or a color name
+pub fn parse_color(value: &toml::Value) -> Result<Color, String> {
+    match value {
+        toml::Value::Integer(number @ 0..=255) => Ok(Color::Indexed(*number as u8)),
+        toml::Value::String(name) => COLORS
+            .iter()
+            .find(|(color, _)| color == name)
+            .map(|(_, color)| *color)
+            .ok_or_else(|| format!("unknown color \"{}\", use a number from 0 to 255 or a color name", name)),
+        _ => Err(format!("must be a number from 0 to 255 or a color name, got {}", value)),
+    }
+}
+
+// Foreground and background of a style: a color for the foreground, or a table
+// with fg and bg
+fn parse_colors(value: &toml::Value) -> Result<(Option<Color>, Option<Color>), String> {
+    let table = match value {
+        toml::Value::Table(table) => table,
+        value => return Ok((Some(parse_color(value)?), None)),
+    };
+    let mut colors = (None, None);
+    for (key, value) in table {
+        match key.as_str() {
+            "fg" => colors.0 = Some(parse_color(value)?),
+            "bg" => colors.1 = Some(parse_color(value)?),
+            _ => return Err(format!("unknown key {}, use fg and bg", key)),
+        }
+    }
+    Ok(colors)
+}
@@ -0,0 +1,41 @@
+use chrono::{Local, TimeZone};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Current unix time in seconds
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// Unix time of the local midnight starting the day of time
+pub fn start_of_day(time: i64) -> i64 {
+    Local
+        .timestamp_opt(time, 0)
+        .single()
+        .and_then(|date| date.date_naive().and_hms_opt(0, 0, 0))
+        .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+        .map(|midnight| midnight.timestamp())
+        .unwrap_or(time)
+}
+
+// Format unix time as local date, "-" if unknown
+pub fn format_date(time: i64) -> String {
+    format_with(time, "%Y-%m-%d")
+}
+
+// Format unix time as local date and time, "-" if unknown
+pub fn format_date_time(time: i64) -> String {
+    format_with(time, "%Y-%m-%d %H:%M")
+}
+
+fn format_with(time: i64, format: &str) -> String {
+    if time <= 0 {
+        return "-".to_string();
+    }
+    match Local.timestamp_opt(time, 0).single() {
+        Some(date) => date.format(format).to_string(),
+        None => "-".to_string(),
+    }
+}
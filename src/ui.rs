@@ -1,13 +1,21 @@
+use crate::config;
+use crate::backup;
+use crate::keymap::Action;
 use crate::state::App;
 use crate::state::Selected;
+use crate::theme::Theme;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use std::{error::Error, io};
 use tui::{
     backend::{Backend, CrosstermBackend},
+    layout::Rect,
+    widgets::{Block, Clear},
     Frame, Terminal,
 };
 
@@ -23,20 +31,36 @@ pub mod delete_card;
 pub mod edit_card;
 pub mod revision_title;
 pub mod revision_text;
-pub mod config_options;
+pub mod settings;
 pub mod size_error;
+pub mod bulk_actions;
+pub mod move_cards;
+pub mod tag_cards;
+pub mod find_replace;
+pub mod replace_preview;
+pub mod import_file;
+pub mod export_file;
+pub mod restore_backup;
+pub mod paste_import;
 
 // Run the ui
 pub fn run_ui() -> Result<(), Box<dyn Error>> {
+    // open database, backing it up first if it changed since the last backup
+    let store = backup::open_store(true)?;
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // create app and run it
-    let app = App::new();
+    let mut app = App::new(Box::new(store));
+    // Settings that could not be used are shown right away
+    if !config::load().1.is_empty() {
+        app.open_settings();
+    }
     let res = run_app(&mut terminal, app);
 
     // restore terminal
@@ -44,7 +68,8 @@ pub fn run_ui() -> Result<(), Box<dyn Error>> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
@@ -58,37 +83,59 @@ pub fn run_ui() -> Result<(), Box<dyn Error>> {
 // Runs the app main loop
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
     app.get_items();
-    if app.items.len() > 0 {
+    if !app.items.is_empty() {
         app.state.select(Some(0));
     }
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
 
-        if let Event::Key(key) = event::read()? {
+        let event = event::read()?;
+
+        // Pasted text arrives at once
+        if let (Event::Paste(text), Selected::PasteImport) = (&event, &app.selected_window) {
+            crate::ui::paste_import::handle_paste(text, &mut app);
+        }
+
+        if let Event::Key(key) = event {
             match app.selected_window {
-                Selected::Main => match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    _ => crate::ui::main::handle_events(key.code, &mut app),
+                Selected::Main => match app.keymap.action("main", key) {
+                    Some(Action::Quit) => return Ok(()),
+                    _ => crate::ui::main::handle_events(key, &mut app),
                 },
-                Selected::Side => match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    _ => crate::ui::side::handle_events(key.code, &mut app), 
+                Selected::Side => match app.keymap.action("side", key) {
+                    Some(Action::Quit) => return Ok(()),
+                    _ => crate::ui::side::handle_events(key, &mut app), 
                 },
-                Selected::StackNameInput => crate::ui::stack_name_input::handle_events(key.code, &mut app),
-                Selected::DeleteStackPopup => crate::ui::delete_stack_popup::handle_events(key.code, &mut app),
-                Selected::EditStackPopup => crate::ui::edit_stack_popup::handle_events(key.code, &mut app),
-                Selected::AddCard => crate::ui::add_card::handle_events(key.code, &mut app),
-                Selected::CardList => crate::ui::card_list::handle_events(key.code, &mut app),
-                Selected::DeleteCard => crate::ui::delete_card::handle_events(key.code, &mut app), 
-                Selected::EditCard => crate::ui::edit_card::handle_events(key.code, &mut app),
-                Selected::RevisionTitle => crate::ui::revision_title::handle_events(key.code, &mut app),
-                Selected::RevisionText => crate::ui::revision_text::handle_events(key.code, &mut app),
-                Selected::ConfigOptions => crate::ui::config_options::handle_events(key.code, &mut app),
+                Selected::StackNameInput => crate::ui::stack_name_input::handle_events(key, &mut app),
+                Selected::DeleteStackPopup => crate::ui::delete_stack_popup::handle_events(key, &mut app),
+                Selected::EditStackPopup => crate::ui::edit_stack_popup::handle_events(key, &mut app),
+                Selected::AddCard => crate::ui::add_card::handle_events(key, &mut app),
+                Selected::CardList => crate::ui::card_list::handle_events(key, &mut app),
+                Selected::DeleteCard => crate::ui::delete_card::handle_events(key, &mut app), 
+                Selected::EditCard => crate::ui::edit_card::handle_events(key, &mut app),
+                Selected::RevisionTitle => crate::ui::revision_title::handle_events(key, &mut app),
+                Selected::RevisionText => crate::ui::revision_text::handle_events(key, &mut app),
+                Selected::Settings => crate::ui::settings::handle_events(key, &mut app),
+                Selected::BulkActions => crate::ui::bulk_actions::handle_events(key, &mut app),
+                Selected::MoveCards => crate::ui::move_cards::handle_events(key, &mut app),
+                Selected::TagCards => crate::ui::tag_cards::handle_events(key, &mut app),
+                Selected::FindReplace => crate::ui::find_replace::handle_events(key, &mut app),
+                Selected::ReplacePreview => crate::ui::replace_preview::handle_events(key, &mut app),
+                Selected::ImportFile => crate::ui::import_file::handle_events(key, &mut app),
+                Selected::ExportFile => crate::ui::export_file::handle_events(key, &mut app),
+                Selected::RestoreBackup => crate::ui::restore_backup::handle_events(key, &mut app),
+                Selected::PasteImport => crate::ui::paste_import::handle_events(key, &mut app),
             }
         }
     }
 }
 
+// Clear the area of a popup window and fill it with the popup style of the theme
+pub fn popup<B: Backend>(f: &mut Frame<B>, area: Rect, theme: &Theme) {
+    f.render_widget(Clear, area);
+    f.render_widget(Block::default().style(theme.popup), area);
+}
+
 // Ui code
 fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     
@@ -111,7 +158,16 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
             Selected::RevisionText => crate::ui::revision_text::render(f, app),
             Selected::EditStackPopup => crate::ui::edit_stack_popup::render(f, app),
             Selected::EditCard => crate::ui::edit_card::render(f, app),
-            Selected::ConfigOptions => crate::ui::config_options::render(f, app),
+            Selected::Settings => crate::ui::settings::render(f, app),
+            Selected::BulkActions => crate::ui::bulk_actions::render(f, app),
+            Selected::MoveCards => crate::ui::move_cards::render(f, app),
+            Selected::TagCards => crate::ui::tag_cards::render(f, app),
+            Selected::FindReplace => crate::ui::find_replace::render(f, app),
+            Selected::ReplacePreview => crate::ui::replace_preview::render(f, app),
+            Selected::ImportFile => crate::ui::import_file::render(f, app),
+            Selected::ExportFile => crate::ui::export_file::render(f, app),
+            Selected::RestoreBackup => crate::ui::restore_backup::render(f, app),
+            Selected::PasteImport => crate::ui::paste_import::render(f, app),
             _ => {}
         }
     } else {
@@ -0,0 +1,123 @@
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::Span,
+    widgets::{Block, BorderType, Borders, List, ListItem},
+    Frame,
+};
+
+// Bulk actions in menu order
+const ACTIONS: [&str; 6] = [
+    "Delete",
+    "Move to stack",
+    "Tag",
+    "Suspend / Unsuspend",
+    "Reset scheduling",
+    "Export selection",
+];
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    let selected = app.bulk_state.selected().unwrap_or(0);
+    match app.keymap.action("bulk_actions", key) {
+        Some(Action::Back) => {
+            app.selected_window = Selected::CardList;
+        }
+        Some(Action::Down) => {
+            app.bulk_state.select(Some((selected + 1) % ACTIONS.len()));
+        }
+        Some(Action::Up) => {
+            app.bulk_state.select(Some((selected + ACTIONS.len() - 1) % ACTIONS.len()));
+        }
+        Some(Action::Confirm) => match selected {
+            0 => app.selected_window = Selected::DeleteCard,
+            1 => {
+                app.move_state.select(None);
+                if !app.items.is_empty() {
+                    app.move_state.select(Some(0));
+                }
+                app.selected_window = Selected::MoveCards;
+            }
+            2 => {
+                app.tag_input = String::new();
+                app.selected_window = Selected::TagCards;
+            }
+            3 => {
+                app.bulk_toggle_suspend();
+                app.selected_window = Selected::CardList;
+            }
+            4 => {
+                app.bulk_reset();
+                app.selected_window = Selected::CardList;
+            }
+            _ => {
+                app.bulk_export();
+                app.selected_window = Selected::CardList;
+            }
+        },
+        _ => {}
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Bulk actions box
+    let bulk_actions_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(
+            format!(" {} cards ", app.bulk_cards().len()),
+            app.theme.text,
+        ))
+        .title_alignment(Alignment::Center);
+
+    // Bulk actions layout
+    let bulk_actions_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(2)
+        .horizontal_margin(6)
+        .constraints([Constraint::Percentage(100)])
+        .split(center_col_layout[1]);
+
+    // Bulk actions list
+    let actions: Vec<ListItem> = ACTIONS
+        .iter()
+        .map(|i| ListItem::new(Span::from(*i)).style(app.theme.text))
+        .collect();
+    let actions = List::new(actions).highlight_style(
+        app.theme.selection.add_modifier(Modifier::BOLD),
+    );
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(bulk_actions_block, center_col_layout[1]);
+    f.render_stateful_widget(actions, bulk_actions_layout[0], &mut app.bulk_state);
+}
@@ -1,40 +1,82 @@
-use crate::ui::App;
+use crate::scheduler;
+use crate::time;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    text::Span,
-    widgets::{Block, BorderType, Borders, List, ListItem},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Esc => {
-            app.selected_window = Selected::Side;
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    let action = app.keymap.action("card_list", key);
+
+    // Filter box input
+    if app.card_filter_focus {
+        if let Some(c) = keymap::typed(key) {
+            app.card_filter.push(c);
+            app.refresh_cards();
+            return;
         }
-        KeyCode::Char('j') => app.next_card(),
-        KeyCode::Char('k') => app.back_card(),
-        KeyCode::Up => app.back_card(),
-        KeyCode::Down => app.next_card(),
-        KeyCode::Char('d') => {
-            if app.cards.len() > 0 {
-                app.selected_window = Selected::DeleteCard;
+        match action {
+            Some(Action::Back | Action::Confirm | Action::Next) => app.card_filter_focus = false,
+            _ => {
+                if let KeyCode::Backspace = key.code {
+                    app.card_filter.pop();
+                    app.refresh_cards();
+                }
             }
         }
-        KeyCode::Char('e') => {
-            if app.cards.len() > 0 {
-                match app.cards_state.selected() {
-                    Some(i) => {
-                        app.card_title_input = app.cards[i].title.as_str().to_string();
-                        app.card_text_input = app.cards[i].text.as_str().to_string();
-                    }
-                    None => {}
-                }
-                app.selected_window = Selected::EditCard;
+        return;
+    }
+
+    app.message = String::new();
+    match action {
+        Some(Action::Back) => {
+            if app.visual_anchor.is_some() {
+                app.visual_anchor = None;
+            } else if !app.marked_cards.is_empty() {
+                app.clear_marks();
+            } else {
+                app.card_filter = String::new();
+                app.selected_window = Selected::Side;
+            }
+        }
+        Some(Action::Down) => {
+            app.next_card();
+            app.update_visual_mark();
+        }
+        Some(Action::Up) => {
+            app.back_card();
+            app.update_visual_mark();
+        }
+        Some(Action::Mark) => app.toggle_mark(),
+        Some(Action::Visual) => app.toggle_visual_mark(),
+        Some(Action::MarkAll) => app.toggle_mark_all(),
+        Some(Action::Bulk) if !app.bulk_cards().is_empty() => {
+            app.visual_anchor = None;
+            app.bulk_state.select(Some(0));
+            app.selected_window = Selected::BulkActions;
+        }
+        Some(Action::Filter) => {
+            app.visual_anchor = None;
+            app.card_filter_focus = true;
+        }
+        Some(Action::Sort) => app.next_card_sort(),
+        Some(Action::Delete) if !app.cards.is_empty() => {
+            app.selected_window = Selected::DeleteCard;
+        }
+        Some(Action::Edit) if !app.cards.is_empty() => {
+            if let Some(i) = app.cards_state.selected() {
+                app.card_title_input = app.cards[i].title.as_str().to_string();
+                app.card_text_input = app.cards[i].text.as_str().to_string();
             }
+            app.selected_window = Selected::EditCard;
         }
         _ => {}
     }
@@ -46,9 +88,9 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .direction(Direction::Horizontal)
         .constraints(
             [
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
             ]
             .as_ref(),
         )
@@ -57,48 +99,182 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
             ]
             .as_ref(),
         )
         .split(center_row_layout[1]);
+
+    // Card list title
+    let card_list_title = match (app.marked_cards.len(), app.visual_anchor) {
+        (0, _) => format!(" Cards ({}) ", app.card_sort.name()),
+        (marked, None) => format!(" Cards ({}, {} marked) ", app.card_sort.name(), marked),
+        (marked, Some(_)) => format!(" Cards ({}, {} marked, visual) ", app.card_sort.name(), marked),
+    };
+
     // Card list box
     let card_list_block = Block::default()
         .borders(Borders::ALL)
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)))
-        .title(Span::styled(" Cards ", Style::default().fg(Color::White)))
+        .style(app.theme.highlight)
+        .title(Span::styled(card_list_title, app.theme.title))
         .title_alignment(Alignment::Center)
         .border_type(BorderType::Rounded);
 
+    // Card list and details layout
+    let card_list_columns_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .vertical_margin(1)
+        .horizontal_margin(2)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(center_col_layout[1]);
+
     // Card list layout
     let card_list_layout = Layout::default()
         .direction(Direction::Vertical)
-        .vertical_margin(2)
-        .horizontal_margin(3)
-        .constraints([Constraint::Percentage(100)])
-        .split(center_col_layout[1]);
+        .horizontal_margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(card_list_columns_layout[0]);
+
+    // Card filter box
+    let card_filter_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(match app.card_filter_focus {
+            true => app.theme.highlight,
+            false => app.theme.text,
+        });
+    let card_filter = Paragraph::new(Spans::from(vec![
+        Span::styled(
+            "filter: ",
+            app.theme.text.add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(app.card_filter.as_str(), app.theme.text),
+    ]))
+    .block(card_filter_block);
 
     // Card list list
     let cards: Vec<ListItem> = app
         .cards
         .iter()
         .map(|i| {
-            let text = Span::from(Span::styled(&i.title, Style::default()));
-            ListItem::new(text).style(Style::default().fg(Color::White))
+            let mark = match (app.marked_cards.contains(&i.id), i.suspended) {
+                (true, _) => "● ",
+                (false, true) => "- ",
+                (false, false) => "  ",
+            };
+            let text = Spans::from(vec![
+                Span::styled(mark, app.theme.highlight),
+                Span::styled(format!("{:<32}", i.title), Style::default()),
+                Span::styled(
+                    time::format_date(i.created_at),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ]);
+            ListItem::new(text).style(app.theme.text)
         })
         .collect();
 
     // Render Cards in a list
     let cards = List::new(cards).highlight_style(
-        Style::default()
-            .bg(Color::White)
-            .fg(Color::Black)
-            .add_modifier(Modifier::BOLD),
+        app.theme.selection.add_modifier(Modifier::BOLD),
     );
 
+    // Card list options
+    let key = |action: Action| app.keymap.key("card_list", action);
+    let card_list_options_text = match app.message.is_empty() {
+        true => format!(
+            "{}: Filter  {}: Sort  {}: Edit  {}: Delete  {}/{}/{}: Mark  {}: Bulk",
+            key(Action::Filter),
+            key(Action::Sort),
+            key(Action::Edit),
+            key(Action::Delete),
+            key(Action::Mark),
+            key(Action::Visual),
+            key(Action::MarkAll),
+            key(Action::Bulk)
+        ),
+        false => app.message.to_string(),
+    };
+    let card_list_options = Paragraph::new(Span::styled(
+        card_list_options_text,
+        app.theme.text.add_modifier(Modifier::DIM),
+    ));
+
+    // Card details box
+    let card_details_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(" Details ", app.theme.title))
+        .title_alignment(Alignment::Center)
+        .style(app.theme.border);
+
+    // Card details
+    let card_details_text = match app.cards_state.selected() {
+        Some(i) if i < app.cards.len() => details(app, i),
+        _ => vec![Spans::from("No card selected")],
+    };
+    let card_details = Paragraph::new(card_details_text)
+        .wrap(Wrap { trim: false })
+        .block(card_details_block);
+
     // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
     f.render_widget(card_list_block, center_col_layout[1]);
-    f.render_stateful_widget(cards, card_list_layout[0], &mut app.cards_state);
+    f.render_widget(card_filter, card_list_layout[0]);
+    f.render_stateful_widget(cards, card_list_layout[1], &mut app.cards_state);
+    f.render_widget(card_list_options, card_list_layout[2]);
+    f.render_widget(card_details, card_list_columns_layout[1]);
+}
+
+// Back text and stats of a card
+fn details(app: &App, i: usize) -> Vec<Spans<'static>> {
+    let card = &app.cards[i];
+    let schedule = &card.schedule;
+    let reviews = app.db.reviews(card.id);
+    let label = Style::default().add_modifier(Modifier::BOLD);
+    let stat = |name: &str, value: String| {
+        Spans::from(vec![
+            Span::styled(format!("{:<11}", name), label),
+            Span::raw(value),
+        ])
+    };
+
+    let mut lines = vec![Spans::from(Span::styled(card.title.clone(), label)), Spans::from("")];
+    lines.extend(card.text.lines().map(|line| Spans::from(line.to_string())));
+    lines.push(Spans::from(""));
+    lines.push(stat("tags:", card.tags.to_string()));
+    if card.suspended {
+        lines.push(stat("suspended:", "yes".to_string()));
+    }
+    lines.push(stat("created:", time::format_date_time(card.created_at)));
+    lines.push(stat("updated:", time::format_date_time(card.updated_at)));
+    lines.push(stat(
+        "due:",
+        match schedule.reps {
+            0 => "new".to_string(),
+            _ => time::format_date_time(schedule.due),
+        },
+    ));
+    lines.push(stat("interval:", format!("{} days", schedule.interval)));
+    lines.push(stat("ease:", format!("{}%", schedule.ease / 10)));
+    lines.push(stat("reviews:", reviews.len().to_string()));
+    lines.push(stat("lapses:", schedule.lapses.to_string()));
+    if let Some(review) = reviews.last() {
+        lines.push(stat(
+            "last:",
+            format!(
+                "{} ({})",
+                time::format_date_time(review.time),
+                scheduler::grade_name(review.grade)
+            ),
+        ));
+    }
+
+    lines
 }
@@ -1,23 +1,27 @@
-use crate::ui::App;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style},
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Esc => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    match app.keymap.action("delete_card", key) {
+        Some(Action::Back) => {
             app.selected_window = Selected::CardList;
         }
-        KeyCode::Enter => {
-            app.delete_card();
-            app.list_cards();
+        Some(Action::Confirm) => {
+            if app.marked_cards.is_empty() {
+                app.delete_card();
+                app.refresh_cards();
+            } else {
+                app.bulk_delete();
+            }
             app.selected_window = Selected::CardList;
         }
         _ => {}
@@ -53,7 +57,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let delete_card_block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)));
+        .style(app.theme.highlight);
 
     // Delete card layout
     let delete_card_layout = Layout::default()
@@ -71,7 +75,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // Delete card promt
     let delete_card_promt = Paragraph::new(Span::styled(
         "Are you sure?",
-        Style::default().fg(Color::White),
+        app.theme.text,
     ))
     .alignment(Alignment::Center);
 
@@ -79,7 +83,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let delete_card_button_block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .style(Style::default().fg(Color::White));
+        .style(app.theme.border);
 
     // Delete card button layout
     let delete_card_button_layout = Layout::default()
@@ -89,10 +93,11 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Delete card button text
     let delete_card_button_text =
-        Paragraph::new(Span::styled("Yes", Style::default().fg(Color::White)))
+        Paragraph::new(Span::styled("Yes", app.theme.text))
             .alignment(Alignment::Center);
 
     // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
     f.render_widget(delete_card_block, center_col_layout[1]);
     f.render_widget(delete_card_promt, delete_card_layout[1]);
     f.render_widget(delete_card_button_block, delete_card_layout[2]);
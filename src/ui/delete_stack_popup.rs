@@ -1,25 +1,26 @@
-use crate::ui::App;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Enter => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    match app.keymap.action("delete_stack_popup", key) {
+        Some(Action::Confirm) => {
             let id = app.get_selected_id();
             app.state.select(None);
             app.delete_stack(id);
             app.get_items();
             app.selected_window = Selected::Main;
         }
-        KeyCode::Esc => {
+        Some(Action::Back) => {
             app.selected_window = Selected::Main;
         }
         _ => {}
@@ -53,13 +54,13 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Delete Stack popup
     let delete_stack_popup_block = Block::default()
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)))
+        .style(app.theme.highlight)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
     // Delete Stack question
     let delete_stack_popup_text = Paragraph::new(Span::from("Are you Sure?"))
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.text)
         .alignment(Alignment::Center);
 
     // Delete Stack Layout
@@ -87,7 +88,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Delete Stack Button
     let delete_stack_button = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
@@ -103,6 +104,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .style(Style::default().add_modifier(Modifier::BOLD));
 
     // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
     f.render_widget(delete_stack_popup_block, center_col_layout[1]);
     f.render_widget(delete_stack_popup_text, delete_stack_popup_layout_col_1[1]);
     f.render_widget(delete_stack_button, delete_stack_popup_layout_col_1[2]);
@@ -1,25 +1,42 @@
 use crate::state::CardInputFocus;
-use crate::ui::App;
+use crate::time;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph, Wrap},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Esc => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    if let Some(c) = keymap::typed(key) {
+        match &app.card_input_focus {
+            CardInputFocus::Title => {
+                if app.card_title_input.len() < 30 {
+                    app.card_title_input.push(c)
+                }
+            }
+            CardInputFocus::Text => {
+                if app.card_text_input.len() < 100 {
+                    app.card_text_input.push(c)
+                }
+            }
+        }
+        return;
+    }
+    match app.keymap.action("edit_card", key) {
+        Some(Action::Back) => {
             app.selected_window = Selected::CardList;
             app.card_text_input = String::new();
             app.card_title_input = String::new();
             app.card_input_focus = CardInputFocus::Title;
         }
-        KeyCode::Tab => match &app.card_input_focus {
+        Some(Action::Next) => match &app.card_input_focus {
             CardInputFocus::Title => {
                 app.card_input_focus = CardInputFocus::Text;
             }
@@ -27,37 +44,26 @@ pub fn handle_events(key_code: KeyCode, app: &mut App) {
                 app.card_input_focus = CardInputFocus::Title;
             }
         },
-        KeyCode::Enter => {
-            if app.card_text_input != "".to_string() && app.card_title_input != "".to_string() {
-                app.edit_card();
-                app.card_text_input = String::new();
-                app.card_title_input = String::new();
-                app.card_input_focus = CardInputFocus::Title;
-                app.list_cards();
-                app.selected_window = Selected::CardList;
-            }
+        Some(Action::Confirm) if !app.card_text_input.is_empty() && !app.card_title_input.is_empty() => {
+            app.edit_card();
+            app.card_text_input = String::new();
+            app.card_title_input = String::new();
+            app.card_input_focus = CardInputFocus::Title;
+            app.refresh_cards();
+            app.selected_window = Selected::CardList;
         }
-        KeyCode::Backspace => match &app.card_input_focus {
-            CardInputFocus::Title => {
-                app.card_title_input.pop();
-            }
-            CardInputFocus::Text => {
-                app.card_text_input.pop();
-            }
-        },
-        KeyCode::Char(c) => match &app.card_input_focus {
-            CardInputFocus::Title => {
-                if app.card_title_input.len() < 30 {
-                    app.card_title_input.push(c)
-                }
-            }
-            CardInputFocus::Text => {
-                if app.card_text_input.len() < 100 {
-                    app.card_text_input.push(c)
+        _ => {
+            if let KeyCode::Backspace = key.code {
+                match &app.card_input_focus {
+                    CardInputFocus::Title => {
+                        app.card_title_input.pop();
+                    }
+                    CardInputFocus::Text => {
+                        app.card_text_input.pop();
+                    }
                 }
             }
-        },
-        _ => {}
+        }
     }
 }
 
@@ -99,11 +105,11 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // Add card title input box
     let add_card_title_input_box = match app.card_input_focus {
         CardInputFocus::Title => Block::default()
-            .style(Style::default().fg(Color::Indexed(app.highlight_color)))
+            .style(app.theme.highlight)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded),
         CardInputFocus::Text => Block::default()
-            .style(Style::default().fg(Color::White))
+            .style(app.theme.border)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded),
     };
@@ -111,11 +117,11 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // Add card text input box
     let add_card_text_input_box = match app.card_input_focus {
         CardInputFocus::Text => Block::default()
-            .style(Style::default().fg(Color::Indexed(app.highlight_color)))
+            .style(app.theme.highlight)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded),
         CardInputFocus::Title => Block::default()
-            .style(Style::default().fg(Color::White))
+            .style(app.theme.border)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded),
     };
@@ -146,52 +152,44 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let add_card_title_input_promt = match app.card_input_focus {
         CardInputFocus::Title => Paragraph::new(Span::from("title: "))
             .style(
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
+                app.theme.text.add_modifier(Modifier::BOLD),
             )
             .block(
                 Block::default()
                     .borders(Borders::BOTTOM)
-                    .style(Style::default().fg(Color::Indexed(app.highlight_color))),
+                    .style(app.theme.highlight),
             ),
         CardInputFocus::Text => Paragraph::new(Span::from("title: ")).style(
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
+            app.theme.text.add_modifier(Modifier::BOLD),
         ),
     };
 
     // Add card title input box value
     let add_card_title_input_value = match app.card_input_focus {
         CardInputFocus::Title => Paragraph::new(Span::from(app.card_title_input.as_ref()))
-            .style(Style::default().fg(Color::White))
+            .style(app.theme.text)
             .block(
                 Block::default()
                     .borders(Borders::BOTTOM)
-                    .style(Style::default().fg(Color::Indexed(app.highlight_color))),
+                    .style(app.theme.highlight),
             ),
         CardInputFocus::Text => Paragraph::new(Span::from(app.card_title_input.as_ref()))
-            .style(Style::default().fg(Color::White)),
+            .style(app.theme.text),
     };
 
     // Add card text input box promt
     let add_card_text_input_promt = match app.card_input_focus {
         CardInputFocus::Text => Paragraph::new(Span::from("text: "))
             .style(
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
+                app.theme.text.add_modifier(Modifier::BOLD),
             )
             .block(
                 Block::default()
                     .borders(Borders::BOTTOM)
-                    .style(Style::default().fg(Color::Indexed(app.highlight_color))),
+                    .style(app.theme.highlight),
             ),
         CardInputFocus::Title => Paragraph::new(Span::from("text: ")).style(
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
+            app.theme.text.add_modifier(Modifier::BOLD),
         ),
     };
 
@@ -199,29 +197,53 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let add_card_text_input_value = match app.card_input_focus {
         CardInputFocus::Text => Paragraph::new(Span::from(app.card_text_input.as_ref()))
             .wrap(Wrap { trim: true })
-            .style(Style::default().fg(Color::White))
+            .style(app.theme.text)
             .block(
                 Block::default()
                     .borders(Borders::BOTTOM)
-                    .style(Style::default().fg(Color::Indexed(app.highlight_color))),
+                    .style(app.theme.highlight),
             ),
         CardInputFocus::Title => Paragraph::new(Span::from(app.card_text_input.as_ref()))
             .wrap(Wrap { trim: true })
-            .style(Style::default().fg(Color::White)),
+            .style(app.theme.border),
+    };
+
+    // Edit card dates layout
+    let edit_card_dates_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(1)
+        .horizontal_margin(7)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(add_card_center_layout[1]);
+
+    // Edit card dates
+    let edit_card_dates_text = match app.cards_state.selected() {
+        Some(i) => format!(
+            "created: {}   updated: {}",
+            time::format_date_time(app.cards[i].created_at),
+            time::format_date_time(app.cards[i].updated_at)
+        ),
+        None => String::new(),
     };
+    let edit_card_dates = Paragraph::new(Span::styled(
+        edit_card_dates_text,
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
 
     // Edit card box
     let edit_card_block = Block::default()
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)))
+        .style(app.theme.highlight)
         .borders(Borders::ALL)
         .title(Span::styled(
             " Edit Card ",
-            Style::default().fg(Color::White),
+            app.theme.title,
         ))
         .title_alignment(Alignment::Center)
         .border_type(BorderType::Rounded);
 
     // Render
+    ui::popup(f, add_card_center_layout[1], &app.theme);
     f.render_widget(edit_card_block, add_card_center_layout[1]);
     f.render_widget(add_card_title_input_box, add_card_layout[0]);
     f.render_widget(add_card_text_input_box, add_card_layout[1]);
@@ -229,4 +251,5 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     f.render_widget(add_card_text_input_promt, add_card_text_input_layout[0]);
     f.render_widget(add_card_title_input_value, add_card_title_input_layout[1]);
     f.render_widget(add_card_text_input_value, add_card_text_input_layout[1]);
+    f.render_widget(edit_card_dates, edit_card_dates_layout[1]);
 }
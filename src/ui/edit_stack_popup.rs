@@ -1,38 +1,40 @@
-use crate::ui::App;
+use crate::time;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Esc => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    if let Some(c) = keymap::typed(key) {
+        if app.stack_name_input.len() < 22 {
+            app.stack_name_input.push(c)
+        }
+        return;
+    }
+    match app.keymap.action("edit_stack_popup", key) {
+        Some(Action::Back) => {
             app.selected_window = Selected::Main;
             app.stack_name_input = String::new();
         }
-        KeyCode::Char(c) => {
-            if app.stack_name_input.len() < 22 {
-                app.stack_name_input.push(c)
-            }
-        }
-        KeyCode::Backspace => {
-            app.stack_name_input.pop();
+        Some(Action::Confirm) if !app.stack_name_input.is_empty() => {
+            app.edit_stack();
+            app.get_items();
+            app.stack_name_input = String::new();
+            app.selected_window = Selected::Main;
         }
-        KeyCode::Enter => {
-            if app.stack_name_input.to_string() != "" {
-                app.edit_stack();
-                app.get_items();
-                app.stack_name_input = String::new();
-                app.selected_window = Selected::Main;
+        _ => {
+            if let KeyCode::Backspace = key.code {
+                app.stack_name_input.pop();
             }
         }
-        _ => {}
     }
 }
 
@@ -99,38 +101,61 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Add Stack Input Text
     let add_stack_input = Paragraph::new(Span::from(app.stack_name_input.as_ref()))
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.text)
         .alignment(Alignment::Left);
 
     // Add Stack "name:" text
     let add_stack_input_text = Paragraph::new(Span::from("name:"))
         .style(
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
+            app.theme.text.add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Right);
 
     // Add Stack input outline
     let add_stack_input_outline = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
+    // Edit stack dates layout
+    let edit_stack_dates_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(1)
+        .horizontal_margin(3)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(center_col_layout[1]);
+
+    // Edit stack dates
+    let edit_stack_dates_text = match app.state.selected() {
+        Some(i) => format!(
+            "created: {}   updated: {}",
+            time::format_date_time(app.items[i].created_at),
+            time::format_date_time(app.items[i].updated_at)
+        ),
+        None => String::new(),
+    };
+    let edit_stack_dates = Paragraph::new(Span::styled(
+        edit_stack_dates_text,
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
     // Edit stack box
     let edit_stack_popup_block = Block::default()
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)))
+        .style(app.theme.highlight)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .title(Span::styled(
             " Edit Stack ",
-            Style::default().fg(Color::White),
+            app.theme.title,
         ))
         .title_alignment(Alignment::Center);
 
     // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
     f.render_widget(edit_stack_popup_block, center_col_layout[1]);
     f.render_widget(add_stack_input_outline, add_stack_popup_input_layout[1]);
     f.render_widget(add_stack_input, add_stack_popup_layout_col_1[1]);
     f.render_widget(add_stack_input_text, add_stack_popup_layout_col_0[1]);
+    f.render_widget(edit_stack_dates, edit_stack_dates_layout[1]);
 }
@@ -0,0 +1,189 @@
+use crate::export::Format;
+use crate::state::{ExportFocus, ExportScope};
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    app.export_message = String::new();
+    // The file is typed in
+    if let (Some(c), ExportFocus::Path) = (keymap::typed(key), &app.export_focus) {
+        return app.export_path.push(c);
+    }
+    let action = app.keymap.action("export_file", key);
+    match action {
+        Some(Action::Back) => {
+            app.export_focus = ExportFocus::Path;
+            app.selected_window = Selected::Main;
+        }
+        Some(Action::Next) => {
+            app.export_focus = match app.export_focus {
+                ExportFocus::Path => ExportFocus::Format,
+                ExportFocus::Format => ExportFocus::Scope,
+                ExportFocus::Scope => ExportFocus::Path,
+            }
+        }
+        Some(Action::Confirm) if !app.export_path.trim().is_empty() => app.run_export(),
+        _ => match app.export_focus {
+            ExportFocus::Path => {
+                if let KeyCode::Backspace = key.code {
+                    app.export_path.pop();
+                }
+            }
+            ExportFocus::Format => {
+                if let Some(Action::Toggle | Action::Left | Action::Right) = action {
+                    app.export_format = app.export_format.next();
+                    // Keep the extension in line with the format
+                    if Format::from_path(&app.export_path).is_some() {
+                        let stem = app.export_path.rsplit_once('.').map(|(stem, _)| stem.to_string());
+                        if let Some(stem) = stem {
+                            app.export_path = format!("{}.{}", stem, app.export_format.extension());
+                        }
+                    }
+                }
+            }
+            ExportFocus::Scope => {
+                if let Some(Action::Toggle | Action::Left | Action::Right) = action {
+                    if app.state.selected().is_some() {
+                        app.export_scope = match app.export_scope {
+                            ExportScope::Stack => ExportScope::SubStacks,
+                            ExportScope::SubStacks => ExportScope::All,
+                            ExportScope::All => ExportScope::Stack,
+                        };
+                        app.default_export_path();
+                    }
+                }
+            }
+        },
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Export box
+    let export_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(" Export ", app.theme.title))
+        .title_alignment(Alignment::Center);
+
+    // Export layout
+    let export_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(2)
+        .horizontal_margin(4)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(center_col_layout[1]);
+
+    let stack_name = app.get_selected_name();
+    let focused = |focus: ExportFocus| match focus == app.export_focus {
+        true => app.theme.highlight,
+        false => app.theme.text,
+    };
+    let promt = app.theme.text.add_modifier(Modifier::BOLD);
+
+    // Path input
+    let path_input = Paragraph::new(Spans::from(vec![
+        Span::styled("file: ", promt),
+        Span::styled(app.export_path.as_str(), app.theme.text),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(focused(ExportFocus::Path)),
+    );
+
+    // Format selector
+    let format = Paragraph::new(Spans::from(vec![
+        Span::styled("  format: ", promt),
+        Span::styled(
+            format!("< {} >", app.export_format.extension()),
+            focused(ExportFocus::Format),
+        ),
+    ]));
+
+    // Scope selector
+    let scope = Paragraph::new(Spans::from(vec![
+        Span::styled("  stacks: ", promt),
+        Span::styled(
+            match app.export_scope {
+                ExportScope::Stack => format!("< {} >", stack_name),
+                ExportScope::SubStacks => format!("< {} and child stacks >", stack_name),
+                ExportScope::All => "< all stacks >".to_string(),
+            },
+            focused(ExportFocus::Scope),
+        ),
+    ]));
+
+    // Message
+    let message = Paragraph::new(Span::styled(
+        app.export_message.as_str(),
+        app.theme.text,
+    ))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+
+    // Help line
+    let key = |action: Action| app.keymap.key("export_file", action);
+    let help = Paragraph::new(Span::styled(
+        format!(
+            "{}: next  {}/{}/{}: change  {}: export",
+            key(Action::Next),
+            key(Action::Toggle),
+            key(Action::Left),
+            key(Action::Right),
+            key(Action::Confirm)
+        ),
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(export_block, center_col_layout[1]);
+    f.render_widget(path_input, export_layout[0]);
+    f.render_widget(format, export_layout[1]);
+    f.render_widget(scope, export_layout[2]);
+    f.render_widget(message, export_layout[3]);
+    f.render_widget(help, export_layout[4]);
+}
@@ -0,0 +1,231 @@
+use crate::state::{ReplaceFocus, ReplaceScope};
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    // Keys bound to actions are still typed in the find and replace fields
+    if keymap::typed(key).is_some() {
+        match app.replace_focus {
+            ReplaceFocus::Find => return edit_input(key, &mut app.replace_find),
+            ReplaceFocus::Replace => return edit_input(key, &mut app.replace_with),
+            _ => {}
+        }
+    }
+    let action = app.keymap.action("find_replace", key);
+    match action {
+        Some(Action::Back) => {
+            app.replace_focus = ReplaceFocus::Find;
+            app.selected_window = Selected::Main;
+        }
+        Some(Action::Next) => {
+            app.replace_focus = match app.replace_focus {
+                ReplaceFocus::Find => ReplaceFocus::Replace,
+                ReplaceFocus::Replace => ReplaceFocus::Scope,
+                ReplaceFocus::Scope => ReplaceFocus::Regex,
+                ReplaceFocus::Regex => ReplaceFocus::Find,
+            }
+        }
+        Some(Action::Previous) => {
+            app.replace_focus = match app.replace_focus {
+                ReplaceFocus::Find => ReplaceFocus::Regex,
+                ReplaceFocus::Replace => ReplaceFocus::Find,
+                ReplaceFocus::Scope => ReplaceFocus::Replace,
+                ReplaceFocus::Regex => ReplaceFocus::Scope,
+            }
+        }
+        Some(Action::Confirm) if !app.replace_find.is_empty() => {
+            if app.preview_replace() {
+                app.selected_window = Selected::ReplacePreview;
+            }
+        }
+        _ => match app.replace_focus {
+            ReplaceFocus::Find => edit_input(key, &mut app.replace_find),
+            ReplaceFocus::Replace => edit_input(key, &mut app.replace_with),
+            ReplaceFocus::Scope => match action {
+                Some(Action::Left | Action::Right | Action::Toggle) => {
+                    let has_stack = app.state.selected().is_some();
+                    let left = action == Some(Action::Left);
+                    app.replace_scope = match (&app.replace_scope, left) {
+                        (ReplaceScope::Stack, true) => ReplaceScope::All,
+                        (ReplaceScope::Stack, _) => ReplaceScope::Tag,
+                        (ReplaceScope::Tag, true) if has_stack => ReplaceScope::Stack,
+                        (ReplaceScope::Tag, true) => ReplaceScope::All,
+                        (ReplaceScope::Tag, _) => ReplaceScope::All,
+                        (ReplaceScope::All, true) => ReplaceScope::Tag,
+                        (ReplaceScope::All, _) if has_stack => ReplaceScope::Stack,
+                        (ReplaceScope::All, _) => ReplaceScope::Tag,
+                    }
+                }
+                _ => {
+                    if let ReplaceScope::Tag = app.replace_scope {
+                        edit_input(key, &mut app.replace_tag);
+                    }
+                }
+            },
+            ReplaceFocus::Regex => {
+                if let Some(Action::Toggle) = action {
+                    app.replace_regex = !app.replace_regex;
+                }
+            }
+        },
+    }
+}
+
+// Type into a text input
+fn edit_input(key: KeyEvent, input: &mut String) {
+    match (keymap::typed(key), key.code) {
+        (Some(c), _) if input.len() < 100 => input.push(c),
+        (None, KeyCode::Backspace) => {
+            input.pop();
+        }
+        _ => {}
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Find and replace box
+    let find_replace_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(
+            " Find and Replace ",
+            app.theme.title,
+        ))
+        .title_alignment(Alignment::Center);
+
+    // Find and replace layout
+    let find_replace_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(1)
+        .horizontal_margin(4)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(center_col_layout[1]);
+
+    let stack_name = app.get_selected_name();
+    let focused = |focus: ReplaceFocus| {
+        match focus == app.replace_focus {
+            true => app.theme.highlight,
+            false => app.theme.text,
+        }
+    };
+    let promt = app.theme.text.add_modifier(Modifier::BOLD);
+
+    // Find input
+    let find_input = Paragraph::new(Spans::from(vec![
+        Span::styled("find:    ", promt),
+        Span::styled(app.replace_find.as_str(), app.theme.text),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(focused(ReplaceFocus::Find)),
+    );
+
+    // Replace input
+    let replace_input = Paragraph::new(Spans::from(vec![
+        Span::styled("replace: ", promt),
+        Span::styled(app.replace_with.as_str(), app.theme.text),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(focused(ReplaceFocus::Replace)),
+    );
+
+    // Scope selector
+    let scope = match app.replace_scope {
+        ReplaceScope::Stack => format!("< stack: {} >", stack_name),
+        ReplaceScope::Tag => format!("< tag: {} >", app.replace_tag),
+        ReplaceScope::All => "< all cards >".to_string(),
+    };
+    let scope_input = Paragraph::new(Spans::from(vec![
+        Span::styled("  scope:   ", promt),
+        Span::styled(scope, focused(ReplaceFocus::Scope)),
+    ]));
+
+    // Regex toggle
+    let regex_input = Paragraph::new(Spans::from(vec![
+        Span::styled("  regex:   ", promt),
+        Span::styled(
+            match app.replace_regex {
+                true => "[x]",
+                false => "[ ]",
+            },
+            focused(ReplaceFocus::Regex),
+        ),
+    ]));
+
+    // Error or help line
+    let key = |action: Action| app.keymap.key("find_replace", action);
+    let help = match app.replace_error.is_empty() {
+        true => Paragraph::new(Span::styled(
+            format!(
+                "{}: next  {}/{}/{}: change  {}: preview",
+                key(Action::Next),
+                key(Action::Toggle),
+                key(Action::Left),
+                key(Action::Right),
+                key(Action::Confirm)
+            ),
+            app.theme.text.add_modifier(Modifier::DIM),
+        )),
+        false => Paragraph::new(Span::styled(
+            app.replace_error.lines().last().unwrap_or_default(),
+            app.theme.error,
+        )),
+    }
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(find_replace_block, center_col_layout[1]);
+    f.render_widget(find_input, find_replace_layout[0]);
+    f.render_widget(replace_input, find_replace_layout[1]);
+    f.render_widget(scope_input, find_replace_layout[2]);
+    f.render_widget(regex_input, find_replace_layout[3]);
+    f.render_widget(help, find_replace_layout[5]);
+}
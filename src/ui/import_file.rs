@@ -0,0 +1,331 @@
+use crate::import;
+use crate::state::ImportFocus;
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+
+// Delimiters to pick from
+const DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    // The file and the name of a new stack are typed in
+    if let Some(c) = keymap::typed(key) {
+        match app.import_focus {
+            ImportFocus::Path => return app.import_path.push(c),
+            ImportFocus::Stack if app.import_stack.is_none() => {
+                if app.import_stack_name.len() < 22 {
+                    app.import_stack_name.push(c);
+                }
+                return;
+            }
+            _ => {}
+        }
+    }
+    let action = app.keymap.action("import_file", key);
+    match action {
+        Some(Action::Back) => {
+            app.import_focus = ImportFocus::Path;
+            app.import_rows = vec![];
+            app.import_decks = vec![];
+            app.import_report = vec![];
+            app.selected_window = Selected::Main;
+        }
+        // Decks only have the review history option of Anki packages and Org files
+        Some(Action::Next) if !app.import_decks.is_empty() => {
+            if !import::has_history(&app.import_path) {
+                return;
+            }
+            app.import_focus = match app.import_focus {
+                ImportFocus::Path => ImportFocus::History,
+                _ => ImportFocus::Path,
+            }
+        }
+        Some(Action::Next) => {
+            app.import_focus = match app.import_focus {
+                ImportFocus::Path => ImportFocus::Delimiter,
+                ImportFocus::Delimiter => ImportFocus::Header,
+                ImportFocus::Header => ImportFocus::Stack,
+                ImportFocus::Stack => ImportFocus::Columns,
+                ImportFocus::Columns | ImportFocus::History => ImportFocus::Path,
+            }
+        }
+        Some(Action::Confirm) if app.import_focus == ImportFocus::Path => app.load_import(None),
+        Some(Action::Confirm) if !app.import_decks.is_empty() => app.run_import(),
+        Some(Action::Confirm) => {
+            let has_target = app.import_stack.is_some() || !app.import_stack_name.trim().is_empty();
+            if !app.import_rows.is_empty() && has_target {
+                app.run_import();
+            }
+        }
+        _ => match app.import_focus {
+            ImportFocus::Path => {
+                if let KeyCode::Backspace = key.code {
+                    app.import_path.pop();
+                }
+            }
+            ImportFocus::Delimiter => {
+                if let Some(Action::Toggle | Action::Left | Action::Right) = action {
+                    let i = DELIMITERS
+                        .iter()
+                        .position(|delimiter| *delimiter == app.import_delimiter)
+                        .unwrap_or(0);
+                    app.load_import(Some(DELIMITERS[(i + 1) % DELIMITERS.len()]));
+                }
+            }
+            ImportFocus::Header => {
+                if let Some(Action::Toggle) = action {
+                    app.import_header = !app.import_header;
+                    app.load_import(Some(app.import_delimiter));
+                }
+            }
+            ImportFocus::Stack => match action {
+                Some(Action::Left | Action::Right) => {
+                    // Cycle through the stacks with "new stack" at the end
+                    let len = app.items.len() + 1;
+                    let i = app.import_stack.unwrap_or(app.items.len());
+                    let i = match action {
+                        Some(Action::Left) => (i + len - 1) % len,
+                        _ => (i + 1) % len,
+                    };
+                    app.import_stack = if i < app.items.len() { Some(i) } else { None };
+                }
+                _ => {
+                    if let (KeyCode::Backspace, None) = (key.code, app.import_stack) {
+                        app.import_stack_name.pop();
+                    }
+                }
+            },
+            ImportFocus::Columns => {
+                let len = app.import_columns.len();
+                match action {
+                    Some(Action::Left) if len > 0 => {
+                        app.import_column = (app.import_column + len - 1) % len;
+                    }
+                    Some(Action::Right) if len > 0 => {
+                        app.import_column = (app.import_column + 1) % len;
+                    }
+                    Some(Action::Toggle) if len > 0 => {
+                        app.import_columns[app.import_column] = app.import_columns[app.import_column].next();
+                    }
+                    _ => {}
+                }
+            }
+            ImportFocus::History => {
+                if let Some(Action::Toggle) = action {
+                    app.import_history = !app.import_history;
+                }
+            }
+        },
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Import box
+    let import_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(" Import CSV / TSV / Markdown / Org / Anki ", app.theme.title))
+        .title_alignment(Alignment::Center);
+
+    // Import layout
+    let import_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(1)
+        .horizontal_margin(3)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(6),
+            Constraint::Length(1),
+        ])
+        .split(center_col_layout[1]);
+
+    let focused = |focus: ImportFocus| match focus == app.import_focus {
+        true => app.theme.highlight,
+        false => app.theme.text,
+    };
+    let promt = app.theme.text.add_modifier(Modifier::BOLD);
+
+    // Path input
+    let path_input = Paragraph::new(Spans::from(vec![
+        Span::styled("file: ", promt),
+        Span::styled(app.import_path.as_str(), app.theme.text),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(focused(ImportFocus::Path)),
+    );
+
+    // Options
+    let delimiter = match app.import_delimiter {
+        b'\t' => "tab".to_string(),
+        delimiter => (delimiter as char).to_string(),
+    };
+    let stack = match app.import_stack {
+        Some(i) => format!("< {} >", app.items[i].name),
+        None => format!("< new: {} >", app.import_stack_name),
+    };
+    let checkbox = |checked: bool| match checked {
+        true => "[x]",
+        false => "[ ]",
+    };
+    let options = Paragraph::new(Spans::from(match app.import_decks.is_empty() {
+        true => vec![
+            Span::styled("delimiter: ", promt),
+            Span::styled(format!("< {} >", delimiter), focused(ImportFocus::Delimiter)),
+            Span::styled("   header: ", promt),
+            Span::styled(checkbox(app.import_header), focused(ImportFocus::Header)),
+            Span::styled("   stack: ", promt),
+            Span::styled(stack, focused(ImportFocus::Stack)),
+        ],
+        false if import::has_history(&app.import_path) => vec![
+            Span::styled("review history: ", promt),
+            Span::styled(checkbox(app.import_history), focused(ImportFocus::History)),
+            Span::styled(
+                "   decks are imported into stacks of the same name",
+                app.theme.text.add_modifier(Modifier::DIM),
+            ),
+        ],
+        false => vec![Span::styled(
+            "decks are imported into stacks of the same name",
+            app.theme.text.add_modifier(Modifier::DIM),
+        )],
+    }));
+
+    // Column roles
+    let columns_title = Paragraph::new(Span::styled(
+        match app.import_decks.is_empty() {
+            true => format!("{} rows, map columns:", app.import_rows.len()),
+            false => format!(
+                "{} decks, {} cards:",
+                app.import_decks.len(),
+                app.import_decks.iter().map(|deck| deck.cards.len()).sum::<usize>()
+            ),
+        },
+        focused(ImportFocus::Columns),
+    ));
+
+    // Preview of the first rows with the column roles as header
+    let widths: Vec<Constraint> = app
+        .import_columns
+        .iter()
+        .map(|_| Constraint::Ratio(1, app.import_columns.len().max(1) as u32))
+        .collect();
+    let header = Row::new(app.import_columns.iter().enumerate().map(|(i, column)| {
+        let style = match app.import_focus == ImportFocus::Columns && i == app.import_column {
+            true => app.theme.selection,
+            false => app.theme.highlight,
+        };
+        Span::styled(format!("[{}]", column.name()), style.add_modifier(Modifier::BOLD))
+    }));
+    let rows = app.import_rows.iter().take(20).enumerate().map(|(i, row)| {
+        let style = match i == 0 && app.import_header {
+            true => app.theme.text.add_modifier(Modifier::DIM),
+            false => app.theme.text,
+        };
+        Row::new(row.iter().map(|field| field.replace('\n', " "))).style(style)
+    });
+    let preview = Table::new(rows)
+        .header(header.bottom_margin(1))
+        .widths(&widths)
+        .column_spacing(2);
+
+    // Decks of an Anki package
+    let deck_widths = [
+        Constraint::Percentage(55),
+        Constraint::Percentage(15),
+        Constraint::Percentage(15),
+        Constraint::Percentage(15),
+    ];
+    let deck_header = Row::new(["Deck", "Cards", "Reviewed", "Skipped"])
+        .style(app.theme.highlight.add_modifier(Modifier::BOLD));
+    let deck_rows = app.import_decks.iter().map(|deck| {
+        Row::new(vec![
+            deck.name.to_string(),
+            deck.cards.len().to_string(),
+            deck.reviewed().to_string(),
+            deck.skipped.len().to_string(),
+        ])
+        .style(app.theme.text)
+    });
+    let decks = Table::new(deck_rows)
+        .header(deck_header.bottom_margin(1))
+        .widths(&deck_widths)
+        .column_spacing(2);
+
+    // Report
+    let report = Paragraph::new(
+        app.import_report
+            .iter()
+            .map(|line| Spans::from(line.as_str()))
+            .collect::<Vec<Spans>>(),
+    )
+    .style(app.theme.text)
+    .wrap(Wrap { trim: false });
+
+    // Help line
+    let key = |action: Action| app.keymap.key("import_file", action);
+    let help = Paragraph::new(Span::styled(
+        format!(
+            "{}: next  {}: load file / import  {}/{}/{}: change",
+            key(Action::Next),
+            key(Action::Confirm),
+            key(Action::Toggle),
+            key(Action::Left),
+            key(Action::Right)
+        ),
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(import_block, center_col_layout[1]);
+    f.render_widget(path_input, import_layout[0]);
+    f.render_widget(options, import_layout[1]);
+    f.render_widget(columns_title, import_layout[2]);
+    match app.import_decks.is_empty() {
+        true => f.render_widget(preview, import_layout[3]),
+        false => f.render_widget(decks, import_layout[3]),
+    }
+    f.render_widget(report, import_layout[4]);
+    f.render_widget(help, import_layout[5]);
+}
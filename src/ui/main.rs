@@ -1,47 +1,77 @@
 use crate::config;
+use crate::state::{ExportScope, PasteFocus, ReplaceScope};
 use crate::ui::App;
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::Span,
     widgets::{Block, BorderType, Borders, List, ListItem, Row, Table},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Up => app.next(),
-        KeyCode::Down => app.back(),
-        KeyCode::Tab => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    let action = match app.keymap.action("main", key) {
+        Some(action) => action,
+        None => return,
+    };
+    match action {
+        Action::Up => app.next(),
+        Action::Down => app.back(),
+        Action::Next => {
             app.selected_window = Selected::Side;
         }
-        KeyCode::Char('k') => app.next(),
-        KeyCode::Char('j') => app.back(),
-        KeyCode::Char('a') => {
+        Action::Add => {
             app.state.select(None);
             app.selected_window = Selected::StackNameInput;
         }
-        KeyCode::Char('d') => {
+        Action::Delete => {
             app.selected_window = Selected::DeleteStackPopup;
         }
-        KeyCode::Enter => match app.state.selected() {
-            Some(_i) => {
-                app.selected_window = Selected::Side;
-            }
-            None => {}
-        },
-        KeyCode::Char('e') => {
+        Action::Confirm if app.state.selected().is_some() => {
+            app.selected_window = Selected::Side;
+        }
+        Action::Edit => {
             app.stack_name_input = app.get_selected_name();
             app.selected_window = Selected::EditStackPopup;
         }
-        KeyCode::Char('c') => {
-            app.config_input_1 = config::get_db_file_raw();
-            app.config_input_2 = app.highlight_color.to_string();
-            app.selected_window = Selected::ConfigOptions;
+        Action::Sort => app.toggle_stack_sort(),
+        Action::Import => {
+            app.import_stack = app.state.selected();
+            app.import_report = vec![];
+            app.selected_window = Selected::ImportFile;
+        }
+        Action::Paste if app.state.selected().is_some() => {
+            app.paste_focus = PasteFocus::Text;
+            app.paste_report = vec![];
+            app.selected_window = Selected::PasteImport;
+        }
+        Action::Export => {
+            app.export_scope = match app.state.selected() {
+                Some(_) => ExportScope::Stack,
+                None => ExportScope::All,
+            };
+            app.export_message = String::new();
+            app.default_export_path();
+            app.selected_window = Selected::ExportFile;
+        }
+        Action::Backups => {
+            app.backup_message = String::new();
+            app.load_backups();
+            app.selected_window = Selected::RestoreBackup;
+        }
+        Action::Replace => {
+            app.replace_scope = match app.state.selected() {
+                Some(_) => ReplaceScope::Stack,
+                None => ReplaceScope::All,
+            };
+            app.replace_error = String::new();
+            app.selected_window = Selected::FindReplace;
         }
+        Action::Settings => app.open_settings(),
         _ => {}
     }
 }
@@ -64,25 +94,24 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .split(block_layout[0]);
 
     // Draw Main block
-    let main_block;
-    match app.selected_window {
-        Selected::Main => {
-            main_block = Block::default()
-                .borders(Borders::ALL)
-                .title(Span::styled(" Stacks ", Style::default().fg(Color::White)))
-                .title_alignment(Alignment::Center)
-                .border_type(BorderType::Rounded)
-                .style(Style::default().fg(Color::Indexed(app.highlight_color)));
-        }
-        _ => {
-            main_block = Block::default()
-                .borders(Borders::ALL)
-                .title(Span::styled(" Stacks ", Style::default().fg(Color::White)))
-                .title_alignment(Alignment::Center)
-                .border_type(BorderType::Rounded)
-                .style(Style::default().fg(Color::White));
-        }
-    }
+    let title = match app.profile.as_str() {
+        config::DEFAULT_PROFILE => " Stacks ".to_string(),
+        profile => format!(" Stacks ({}) ", profile),
+    };
+    let main_block = match app.selected_window {
+        Selected::Main => Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(title.as_str(), app.theme.title))
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(app.theme.highlight),
+        _ => Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(title.as_str(), app.theme.title))
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(app.theme.border),
+    };
     f.render_widget(main_block, block_layout[0]);
 
     // Stacks
@@ -90,17 +119,14 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .items
         .iter()
         .map(|i| {
-            let text = Span::from(Span::styled(&i.name, Style::default()));
-            ListItem::new(text).style(Style::default().fg(Color::White))
+            let text = Span::styled(&i.name, Style::default());
+            ListItem::new(text).style(app.theme.text)
         })
         .collect();
 
     // Render Stacks in a list
     let stacks = List::new(stacks).highlight_style(
-        Style::default()
-            .bg(Color::White)
-            .fg(Color::Black)
-            .add_modifier(Modifier::BOLD),
+        app.theme.selection.add_modifier(Modifier::BOLD),
     );
     f.render_stateful_widget(stacks, main_block_layout[0], &mut app.state);
 
@@ -109,7 +135,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .borders(Borders::ALL)
         .title(" Options ")
         .title_alignment(Alignment::Center)
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .border_type(BorderType::Rounded);
     f.render_widget(main_block_options, main_block_layout[1]);
 
@@ -120,19 +146,33 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(main_block_layout[1]);
 
-    // Render Options
+    // Render Options in two rows
+    let widths = [
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+    ];
+    let key = |action: Action| app.keymap.key("main", action);
+    let options = Table::new(vec![Row::new(vec![
+        format!("{}: Add new", key(Action::Add)),
+        format!("{}: Delete", key(Action::Delete)),
+        format!("{}: Edit", key(Action::Edit)),
+        format!("{}: Sort by recent", key(Action::Sort)),
+        format!("<{}, {}>: up, down", key(Action::Up), key(Action::Down)),
+    ])
+    .style(Style::default())])
+    .widths(&widths);
+    f.render_widget(options, main_block_options_layout[0]);
     let options = Table::new(vec![Row::new(vec![
-        "a: Add new",
-        "d: Delete",
-        "e: Edit",
-        "<j, k>: up, down",
+        format!("{}: Find and replace", key(Action::Replace)),
+        format!("{}: Import", key(Action::Import)),
+        format!("{}: Paste", key(Action::Paste)),
+        format!("{}: Export", key(Action::Export)),
+        format!("{}: Backups", key(Action::Backups)),
     ])
     .style(Style::default())])
-    .widths(&[
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-    ]);
+    .widths(&widths);
     f.render_widget(options, main_block_options_layout[1]);
 }
@@ -0,0 +1,94 @@
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::Span,
+    widgets::{Block, BorderType, Borders, List, ListItem},
+    Frame,
+};
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    let len = app.items.len();
+    match app.keymap.action("move_cards", key) {
+        Some(Action::Back) => {
+            app.selected_window = Selected::BulkActions;
+        }
+        Some(Action::Down) if len > 0 => {
+            let i = app.move_state.selected().map(|i| (i + 1) % len).unwrap_or(0);
+            app.move_state.select(Some(i));
+        }
+        Some(Action::Up) if len > 0 => {
+            let i = app.move_state.selected().map(|i| (i + len - 1) % len).unwrap_or(0);
+            app.move_state.select(Some(i));
+        }
+        Some(Action::Confirm) => {
+            if let Some(i) = app.move_state.selected() {
+                let stack_id = app.items[i].id;
+                app.bulk_move(stack_id);
+                app.selected_window = Selected::CardList;
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Move cards box
+    let move_cards_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(" Move to Stack ", app.theme.title))
+        .title_alignment(Alignment::Center);
+
+    // Move cards layout
+    let move_cards_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(2)
+        .horizontal_margin(6)
+        .constraints([Constraint::Percentage(100)])
+        .split(center_col_layout[1]);
+
+    // Stacks list
+    let stacks: Vec<ListItem> = app
+        .items
+        .iter()
+        .map(|i| ListItem::new(Span::from(i.name.as_str())).style(app.theme.text))
+        .collect();
+    let stacks = List::new(stacks).highlight_style(
+        app.theme.selection.add_modifier(Modifier::BOLD),
+    );
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(move_cards_block, center_col_layout[1]);
+    f.render_stateful_widget(stacks, move_cards_layout[0], &mut app.move_state);
+}
@@ -0,0 +1,224 @@
+use crate::import::paste::{self, CARD_SEPARATORS, TERM_SEPARATORS};
+use crate::state::PasteFocus;
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    app.paste_report = vec![];
+    let action = app.keymap.action("paste_import", key);
+    match app.paste_focus {
+        // Typed keys, Enter and Tab are part of the text, back leaves it
+        PasteFocus::Text => match (action, keymap::typed(key), key.code) {
+            (_, Some(c), _) => app.paste_text.push(c),
+            (Some(Action::Back), _, _) => app.paste_focus = PasteFocus::Term,
+            (_, _, KeyCode::Enter) => app.paste_text.push('\n'),
+            (_, _, KeyCode::Tab) => app.paste_text.push('\t'),
+            (_, _, KeyCode::Backspace) => {
+                app.paste_text.pop();
+            }
+            _ => {}
+        },
+        PasteFocus::Term | PasteFocus::Card => match action {
+            Some(Action::Back) => {
+                app.paste_focus = PasteFocus::Text;
+                app.selected_window = Selected::Main;
+            }
+            Some(Action::Next) => {
+                app.paste_focus = match app.paste_focus {
+                    PasteFocus::Term => PasteFocus::Card,
+                    _ => PasteFocus::Text,
+                }
+            }
+            Some(Action::Confirm) if !app.paste_text.trim().is_empty() => app.run_paste_import(),
+            _ => {
+                let (separator, presets) = match app.paste_focus {
+                    PasteFocus::Term => (&mut app.paste_term, &TERM_SEPARATORS[..]),
+                    _ => (&mut app.paste_card, &CARD_SEPARATORS[..]),
+                };
+                let is_preset = presets.iter().any(|(_, preset)| preset == separator);
+                match (action, keymap::typed(key), key.code) {
+                    (Some(Action::Left), _, _) => *separator = paste::next_separator(separator, presets, true),
+                    (Some(Action::Right), _, _) => *separator = paste::next_separator(separator, presets, false),
+                    // Typing starts a custom separator
+                    (_, Some(c), _) => {
+                        if is_preset {
+                            separator.clear();
+                        }
+                        separator.push(c);
+                    }
+                    (_, _, KeyCode::Backspace) => {
+                        separator.pop();
+                    }
+                    _ => {}
+                }
+            }
+        },
+    }
+}
+
+// Add pasted text
+pub fn handle_paste(text: &str, app: &mut App) {
+    app.paste_report = vec![];
+    app.paste_focus = PasteFocus::Text;
+    app.paste_text.push_str(&text.replace("\r\n", "\n").replace('\r', "\n"));
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Paste box
+    let paste_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(
+            format!(" Paste into {} ", app.get_selected_name()),
+            app.theme.text,
+        ))
+        .title_alignment(Alignment::Center);
+
+    // Paste layout
+    let paste_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(1)
+        .horizontal_margin(3)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Percentage(40),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .split(center_col_layout[1]);
+
+    let focused = |focus: PasteFocus| match focus == app.paste_focus {
+        true => app.theme.highlight,
+        false => app.theme.text,
+    };
+    let promt = app.theme.text.add_modifier(Modifier::BOLD);
+
+    // Separators
+    let options = Paragraph::new(Spans::from(vec![
+        Span::styled("term / definition: ", promt),
+        Span::styled(
+            format!("< {} >", paste::separator_name(&app.paste_term, &TERM_SEPARATORS)),
+            focused(PasteFocus::Term),
+        ),
+        Span::styled("   between cards: ", promt),
+        Span::styled(
+            format!("< {} >", paste::separator_name(&app.paste_card, &CARD_SEPARATORS)),
+            focused(PasteFocus::Card),
+        ),
+    ]));
+
+    // Pasted text, scrolled to its end with tabs made visible
+    let text_height = paste_layout[1].height.saturating_sub(2) as usize;
+    let lines: Vec<String> = app
+        .paste_text
+        .split('\n')
+        .map(|line| line.replace('\t', " ⇥ "))
+        .collect();
+    let text = Paragraph::new(
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(text_height))
+            .map(|line| Spans::from(line.as_str()))
+            .collect::<Vec<Spans>>(),
+    )
+    .style(app.theme.text)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(focused(PasteFocus::Text)),
+    );
+
+    // Live preview of the parsed cards
+    let (cards, skipped) = paste::parse(&app.paste_text, &app.paste_term, &app.paste_card);
+    let preview_title = Paragraph::new(Span::styled(
+        format!("{} cards, {} without separator:", cards.len(), skipped.len()),
+        promt,
+    ));
+    let widths = [Constraint::Percentage(35), Constraint::Percentage(65)];
+    let header = Row::new(["Term", "Definition"])
+        .style(app.theme.highlight.add_modifier(Modifier::BOLD));
+    let rows = cards.iter().take(50).map(|(_, card)| {
+        Row::new(vec![card.title.to_string(), card.text.replace('\n', " ")])
+            .style(app.theme.text)
+    });
+    let preview = Table::new(rows)
+        .header(header.bottom_margin(1))
+        .widths(&widths)
+        .column_spacing(2);
+
+    // Report
+    let report = Paragraph::new(
+        app.paste_report
+            .iter()
+            .map(|line| Spans::from(line.as_str()))
+            .collect::<Vec<Spans>>(),
+    )
+    .style(app.theme.text)
+    .wrap(Wrap { trim: false });
+
+    // Help line
+    let key = |action: Action| app.keymap.key("paste_import", action);
+    let help = Paragraph::new(Span::styled(
+        match app.paste_focus {
+            PasteFocus::Text => format!("Paste or type cards  {}: separators", key(Action::Back)),
+            _ => format!(
+                "{}: next  {}/{}: preset  Type: custom (\\t tab, \\n new line)  {}: import  {}: close",
+                key(Action::Next),
+                key(Action::Left),
+                key(Action::Right),
+                key(Action::Confirm),
+                key(Action::Back)
+            ),
+        },
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(paste_block, center_col_layout[1]);
+    f.render_widget(options, paste_layout[0]);
+    f.render_widget(text, paste_layout[1]);
+    f.render_widget(preview_title, paste_layout[2]);
+    f.render_widget(preview, paste_layout[3]);
+    f.render_widget(report, paste_layout[4]);
+    f.render_widget(help, paste_layout[5]);
+}
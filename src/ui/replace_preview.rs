@@ -0,0 +1,126 @@
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    let len = app.replace_changes.len();
+    match app.keymap.action("replace_preview", key) {
+        Some(Action::Back) => {
+            app.selected_window = Selected::FindReplace;
+        }
+        Some(Action::Down) if len > 0 => {
+            let i = app.replace_state.selected().map(|i| (i + 1).min(len - 1)).unwrap_or(0);
+            app.replace_state.select(Some(i));
+        }
+        Some(Action::Up) if len > 0 => {
+            let i = app.replace_state.selected().map(|i| i.saturating_sub(1)).unwrap_or(0);
+            app.replace_state.select(Some(i));
+        }
+        Some(Action::Confirm) => {
+            app.apply_replace();
+            app.replace_find = String::new();
+            app.replace_with = String::new();
+            app.selected_window = Selected::Main;
+        }
+        _ => {}
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Preview box
+    let preview_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(
+            format!(" {} cards will change ", app.replace_changes.len()),
+            app.theme.text,
+        ))
+        .title_alignment(Alignment::Center);
+
+    // Preview layout
+    let preview_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(1)
+        .horizontal_margin(3)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(center_col_layout[1]);
+
+    // Changes with before and after text
+    let removed = app.theme.error;
+    let added = app.theme.success;
+    let changes: Vec<ListItem> = app
+        .replace_changes
+        .iter()
+        .map(|change| {
+            let mut lines = vec![Spans::from(Span::styled(
+                change.old_title.to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ))];
+            if change.old_title != change.card.title {
+                lines.push(Spans::from(Span::styled(format!("- title: {}", change.old_title), removed)));
+                lines.push(Spans::from(Span::styled(format!("+ title: {}", change.card.title), added)));
+            }
+            if change.old_text != change.card.text {
+                lines.extend(change.old_text.lines().map(|line| Spans::from(Span::styled(format!("- {}", line), removed))));
+                lines.extend(change.card.text.lines().map(|line| Spans::from(Span::styled(format!("+ {}", line), added))));
+            }
+            lines.push(Spans::from(""));
+            ListItem::new(lines).style(app.theme.text)
+        })
+        .collect();
+    let changes = List::new(changes).highlight_symbol("> ");
+
+    // Help line
+    let key = |action: Action| app.keymap.key("replace_preview", action);
+    let help = Paragraph::new(Span::styled(
+        format!(
+            "{}: apply all  {}: back  <{}, {}>: scroll",
+            key(Action::Confirm),
+            key(Action::Back),
+            key(Action::Down),
+            key(Action::Up)
+        ),
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(preview_block, center_col_layout[1]);
+    f.render_stateful_widget(changes, preview_layout[0], &mut app.replace_state);
+    f.render_widget(help, preview_layout[1]);
+}
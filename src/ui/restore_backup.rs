@@ -0,0 +1,114 @@
+use crate::backup;
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::Span,
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    let len = app.backups.len();
+    match app.keymap.action("restore_backup", key) {
+        Some(Action::Back) => {
+            app.selected_window = Selected::Main;
+        }
+        Some(Action::Down) if len > 0 => {
+            let i = app.backups_state.selected().map(|i| (i + 1) % len).unwrap_or(0);
+            app.backups_state.select(Some(i));
+        }
+        Some(Action::Up) if len > 0 => {
+            let i = app.backups_state.selected().map(|i| (i + len - 1) % len).unwrap_or(0);
+            app.backups_state.select(Some(i));
+        }
+        Some(Action::Confirm) => app.restore_backup(),
+        _ => {}
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Backups box
+    let backups_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(" Restore Backup ", app.theme.title))
+        .title_alignment(Alignment::Center);
+
+    // Backups layout
+    let backups_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(2)
+        .horizontal_margin(4)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .split(center_col_layout[1]);
+
+    // Backups list
+    let backups: Vec<ListItem> = match app.backups.is_empty() {
+        true => vec![ListItem::new(Span::from("No backups yet")).style(app.theme.text)],
+        false => app
+            .backups
+            .iter()
+            .map(|b| ListItem::new(Span::from(backup::name(b))).style(app.theme.text))
+            .collect(),
+    };
+    let backups = List::new(backups).highlight_style(
+        app.theme.selection.add_modifier(Modifier::BOLD),
+    );
+
+    // Message
+    let message = Paragraph::new(Span::styled(
+        app.backup_message.as_str(),
+        app.theme.text,
+    ))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+
+    // Help line
+    let key = |action: Action| app.keymap.key("restore_backup", action);
+    let help = Paragraph::new(Span::styled(
+        format!("{}: restore  {}: close", key(Action::Confirm), key(Action::Back)),
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(backups_block, center_col_layout[1]);
+    f.render_stateful_widget(backups, backups_layout[0], &mut app.backups_state);
+    f.render_widget(message, backups_layout[1]);
+    f.render_widget(help, backups_layout[2]);
+}
@@ -1,25 +1,25 @@
-use crate::ui::App;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style},
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Esc => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    match app.keymap.action("revision_text", key) {
+        Some(Action::Back) => {
             if app.revision_index == app.cards.len() - 1 {
                 app.selected_window = Selected::Side;
             } else {
                 app.selected_window = Selected::RevisionTitle;
             }
         }
-        KeyCode::Enter => {
+        Some(Action::Confirm) => {
             if app.revision_index == app.cards.len() - 1 {
                 app.selected_window = Selected::Side;
                 app.revision_index = 0;
@@ -61,9 +61,9 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let revision_text_box = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .title(Span::styled(" Back ", Style::default().fg(Color::White)))
+        .title(Span::styled(" Back ", app.theme.title))
         .title_alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)));
+        .style(app.theme.highlight);
 
     // Revision text box layout
     let revision_text_layout = Layout::default()
@@ -74,17 +74,15 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .split(center_col_layout[1]);
 
     // Revision text promt
-    let revision_text_promt;
-    if app.cards.len() > 0 {
-        revision_text_promt = Paragraph::new(Span::styled(
+    let revision_text_promt = if !app.cards.is_empty() {
+        Paragraph::new(Span::styled(
             app.cards[app.revision_index].text.as_str(),
-            Style::default().fg(Color::White),
+            app.theme.text,
         ))
-        .alignment(Alignment::Center);
+        .alignment(Alignment::Center)
     } else {
-        revision_text_promt =
-            Paragraph::new(Span::styled("No text", Style::default().fg(Color::White)))
-    }
+        Paragraph::new(Span::styled("No text", app.theme.text))
+    };
 
     // Revision cards index layout col
     let revision_cards_index_layout_col = Layout::default()
@@ -124,11 +122,12 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // Revision cards index promt
     let revision_cards_index_promt = Paragraph::new(Span::styled(
         format!("{}/{}", app.revision_index + 1, app.cards.len()),
-        Style::default().fg(Color::White),
+        app.theme.text,
     ))
     .alignment(Alignment::Center);
 
     // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
     f.render_widget(revision_text_box, center_col_layout[1]);
     f.render_widget(revision_text_promt, revision_text_layout[1]);
     f.render_widget(revision_cards_index_block, revision_cards_index_layout[1]);
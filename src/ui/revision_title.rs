@@ -1,22 +1,22 @@
-use crate::ui::App;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style},
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Esc => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    match app.keymap.action("revision_title", key) {
+        Some(Action::Back) => {
             app.selected_window = Selected::Side;
             app.revision_index = 0;
         }
-        KeyCode::Enter => {
+        Some(Action::Confirm) => {
             app.selected_window = Selected::RevisionText;
         }
         _ => {}
@@ -52,9 +52,9 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     let revision_title_box = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .title(Span::styled(" Front ", Style::default().fg(Color::White)))
+        .title(Span::styled(" Front ", app.theme.title))
         .title_alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)));
+        .style(app.theme.highlight);
 
     // Revision title box layout
     let revision_title_layout = Layout::default()
@@ -65,18 +65,16 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .split(center_col_layout[1]);
 
     // Revision title promt
-    let revision_title_promt;
-    if app.cards.len() > 0 {
-        revision_title_promt = Paragraph::new(Span::styled(
+    let revision_title_promt = if !app.cards.is_empty() {
+        Paragraph::new(Span::styled(
             app.cards[app.revision_index].title.as_str(),
-            Style::default().fg(Color::White),
+            app.theme.text,
         ))
-        .alignment(Alignment::Center);
+        .alignment(Alignment::Center)
     } else {
-        revision_title_promt =
-            Paragraph::new(Span::styled("No title", Style::default().fg(Color::White)))
-                .alignment(Alignment::Center);
-    }
+        Paragraph::new(Span::styled("No title", app.theme.text))
+            .alignment(Alignment::Center)
+    };
 
     // Revision cards index layout col
     let revision_cards_index_layout_col = Layout::default()
@@ -116,11 +114,12 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // Revision cards index promt
     let revision_cards_index_promt = Paragraph::new(Span::styled(
         format!("{}/{}", app.revision_index + 1, app.cards.len()),
-        Style::default().fg(Color::White),
+        app.theme.text,
     ))
     .alignment(Alignment::Center);
 
     // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
     f.render_widget(revision_title_box, center_col_layout[1]);
     f.render_widget(revision_title_promt, revision_title_layout[1]);
     f.render_widget(revision_cards_index_block, revision_cards_index_layout[1]);
@@ -0,0 +1,215 @@
+use crate::config::{self, Kind};
+use crate::theme::Theme;
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+// Settings window built from config::SETTINGS, each kind of setting is edited its
+// own way: text and numbers are typed, booleans toggled and choices picked with
+// the arrows, numbers can be stepped with them too.
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    let len = config::SETTINGS.len();
+    let kind = &config::SETTINGS[app.settings_index].kind;
+    if let (Some(c), Kind::Text | Kind::Number { .. } | Kind::Color) = (keymap::typed(key), kind) {
+        app.settings[app.settings_index].push(c);
+        return;
+    }
+    match app.keymap.action("settings", key) {
+        Some(Action::Back) => {
+            app.config_errors.clear();
+            app.selected_window = Selected::Main;
+        }
+        // The window stays open when a setting can't be saved
+        Some(Action::Confirm) if app.save_settings() => app.selected_window = Selected::Main,
+        Some(Action::Down | Action::Next) => app.settings_index = (app.settings_index + 1) % len,
+        Some(Action::Up | Action::Previous) => app.settings_index = (app.settings_index + len - 1) % len,
+        Some(Action::Right) => app.step_setting(true),
+        Some(Action::Left) => app.step_setting(false),
+        Some(Action::Toggle) if matches!(kind, Kind::Bool | Kind::Choice(_)) => app.step_setting(true),
+        _ => {
+            if let KeyCode::Backspace = key.code {
+                app.settings[app.settings_index].pop();
+            }
+        }
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Settings box
+    let settings_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(app.theme.highlight)
+        .title(Span::styled(" Settings ", app.theme.title))
+        .title_alignment(Alignment::Center);
+
+    // Settings layout
+    let settings_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(2)
+        .horizontal_margin(4)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .split(center_col_layout[1]);
+
+    // Errors of the file and of settings not in the window come first
+    let error_style = app.theme.error;
+    let mut lines: Vec<Spans> = app
+        .config_errors
+        .iter()
+        .filter(|error| !matches!(&error.key, Some(key) if config::setting(key).is_some()))
+        .map(|error| match &error.key {
+            Some(key) => Spans::from(Span::styled(format!("{}: {}", key, error.reason), error_style)),
+            None => Spans::from(Span::styled(error.reason.to_string(), error_style)),
+        })
+        .collect();
+
+    // Settings grouped by section, with their error below them
+    let width = config::SETTINGS.iter().map(|setting| setting.key.len()).max().unwrap_or(0) + 2;
+    let mut section = "";
+    let mut selected_line = 0;
+    for (i, setting) in config::SETTINGS.iter().enumerate() {
+        if setting.section != section {
+            if !section.is_empty() {
+                lines.push(Spans::default());
+            }
+            section = setting.section;
+            lines.push(Spans::from(Span::styled(
+                section,
+                app.theme.text.add_modifier(Modifier::BOLD),
+            )));
+        }
+
+        let selected = i == app.settings_index;
+        let label_style = match selected {
+            true => app.theme.highlight.add_modifier(Modifier::BOLD),
+            false => app.theme.text,
+        };
+        let marker = if selected { "> " } else { "  " };
+        let mut spans = vec![Span::styled(format!("{}{:<width$}", marker, setting.key, width = width), label_style)];
+        spans.extend(value(&app.theme, &setting.kind, &app.settings[i], selected));
+        if selected {
+            selected_line = lines.len();
+        }
+        lines.push(Spans::from(spans));
+
+        if let Some(error) = app.config_errors.iter().find(|error| error.key.as_deref() == Some(setting.key)) {
+            lines.push(Spans::from(Span::styled(
+                format!("  {:<width$}{}", "", error.reason, width = width),
+                error_style,
+            )));
+        }
+    }
+
+    // Keep the selected setting in view
+    let height = settings_layout[0].height as usize;
+    let scroll = (selected_line + 1).saturating_sub(height) as u16;
+    let settings = Paragraph::new(lines).scroll((scroll, 0));
+
+    // Help of the selected setting
+    let help = Paragraph::new(Span::styled(
+        config::SETTINGS[app.settings_index].help,
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .wrap(Wrap { trim: true });
+
+    // Help line
+    let key = |action: Action| app.keymap.key("settings", action);
+    let keys = Paragraph::new(Span::styled(
+        format!(
+            "{}/{}: select  {}/{}: change  {}: save  {}: close",
+            key(Action::Up),
+            key(Action::Down),
+            key(Action::Left),
+            key(Action::Right),
+            key(Action::Confirm),
+            key(Action::Back)
+        ),
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(settings_block, center_col_layout[1]);
+    f.render_widget(settings, settings_layout[0]);
+    f.render_widget(help, settings_layout[1]);
+    f.render_widget(keys, settings_layout[2]);
+}
+
+// Value of a setting as shown for its kind
+fn value(theme: &Theme, kind: &Kind, text: &str, selected: bool) -> Vec<Span<'static>> {
+    let style = theme.text;
+    match kind {
+        Kind::Bool => {
+            let on = text == "true";
+            vec![Span::styled(if on { "[x] on" } else { "[ ] off" }, style)]
+        }
+        // Every choice, the current one reversed
+        Kind::Choice(choices) => choices
+            .iter()
+            .flat_map(|choice| {
+                let style = match *choice == text {
+                    true => style.add_modifier(Modifier::REVERSED),
+                    false => style.add_modifier(Modifier::DIM),
+                };
+                [Span::styled(format!(" {} ", choice), style), Span::raw(" ")]
+            })
+            .collect(),
+        // Typed value with a sample of the color
+        Kind::Color => {
+            let mut spans = vec![Span::styled(format!("{}{}", text, cursor(selected)), style)];
+            if let (true, Ok(color)) = (theme.colors, config::check_highlight_color(text)) {
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled("      ", Style::default().bg(Color::Indexed(color))));
+            }
+            spans
+        }
+        Kind::Text | Kind::Number { .. } => vec![Span::styled(format!("{}{}", text, cursor(selected)), style)],
+    }
+}
+
+// Cursor after a typed value
+fn cursor(selected: bool) -> &'static str {
+    match selected {
+        true => "_",
+        false => "",
+    }
+}
@@ -1,35 +1,43 @@
 use crate::ui::App;
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::Action;
+use crossterm::event::KeyEvent;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::Span,
     widgets::{Block, BorderType, Borders},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Tab => {
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    match app.keymap.action("side", key) {
+        Some(Action::Next) => {
             app.selected_window = Selected::Main;
         }
-        KeyCode::Char('a') => app.selected_window = Selected::AddCard,
-        KeyCode::Char('l') => {
+        Some(Action::Add) => app.selected_window = Selected::AddCard,
+        Some(Action::List) => {
+            app.clear_marks();
+            app.card_filter = String::new();
+            app.card_filter_focus = false;
             app.list_cards();
-            if app.cards.len() > 0 {
+            if !app.cards.is_empty() {
                 app.cards_state.select(Some(0));
             }
             app.selected_window = Selected::CardList;
         }
-        KeyCode::Char('s') => {
-            app.list_cards();
-            if app.cards.len() > 0 {
+        Some(Action::Study) => {
+            // Study the whole stack, not what the card list shows
+            let stack_id = app.get_selected_id();
+            app.cards = app.db.cards(stack_id);
+            app.cards.retain(|card| !card.suspended);
+            app.revision_index = 0;
+            if !app.cards.is_empty() {
                 app.selected_window = Selected::RevisionTitle;
             }
         }
-        KeyCode::Esc => {
+        Some(Action::Back) => {
             app.selected_window = Selected::Main;
         }
         _ => {}
@@ -46,31 +54,26 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .split(f.size());
 
     // Draw Side block
-    let side_block;
-    match app.selected_window {
-        Selected::Side => {
-            side_block = Block::default()
-                .borders(Borders::ALL)
-                .title(Span::styled(
-                    " Selected Stack ",
-                    Style::default().fg(Color::White),
-                ))
-                .title_alignment(Alignment::Center)
-                .border_type(BorderType::Rounded)
-                .style(Style::default().fg(Color::Indexed(app.highlight_color)));
-        }
-        _ => {
-            side_block = Block::default()
-                .borders(Borders::ALL)
-                .title(Span::styled(
-                    " Selected Stack ",
-                    Style::default().fg(Color::White),
-                ))
-                .title_alignment(Alignment::Center)
-                .border_type(BorderType::Rounded)
-                .style(Style::default().fg(Color::White));
-        }
-    }
+    let side_block = match app.selected_window {
+        Selected::Side => Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(
+                " Selected Stack ",
+                app.theme.title,
+            ))
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(app.theme.highlight),
+        _ => Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(
+                " Selected Stack ",
+                app.theme.title,
+            ))
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(app.theme.border),
+    };
     f.render_widget(side_block, block_layout[1]);
 
     // Side block layout
@@ -108,13 +111,13 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Side block selected stack name box
     let side_block_name_box = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
     // Side block selected stack name
     let side_block_name = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .title(Span::styled(
             app.get_selected_name(),
             Style::default().add_modifier(Modifier::BOLD),
@@ -139,15 +142,15 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Side block option blocks
     let side_block_option_1 = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
     let side_block_option_2 = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
     let side_block_option_3 = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
@@ -172,37 +175,35 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .split(side_block_options_layout[2]);
 
     // Side block option text
+    let key = |action: Action| app.keymap.key("side", action);
     let side_block_name_1 = Block::default()
         .title(Span::styled(
-            "a: Add Card",
+            format!("{}: Add Card", key(Action::Add)),
             Style::default().add_modifier(Modifier::BOLD),
         ))
         .title_alignment(Alignment::Center);
     let side_block_name_2 = Block::default()
         .title(Span::styled(
-            "s: Start Revision",
+            format!("{}: Start Revision", key(Action::Study)),
             Style::default().add_modifier(Modifier::BOLD),
         ))
         .title_alignment(Alignment::Center);
     let side_block_name_3 = Block::default()
         .title(Span::styled(
-            "l: List Cards",
+            format!("{}: List Cards", key(Action::List)),
             Style::default().add_modifier(Modifier::BOLD),
         ))
         .title_alignment(Alignment::Center);
 
     // Render side block widgets
-    match app.state.selected() {
-        Some(_i) => {
-            f.render_widget(side_block_name_box, side_block_layout[1]);
-            f.render_widget(side_block_name, side_block_name_layout[1]);
-            f.render_widget(side_block_option_1, side_block_options_layout[1]);
-            f.render_widget(side_block_option_2, side_block_options_layout[3]);
-            f.render_widget(side_block_option_3, side_block_options_layout[2]);
-            f.render_widget(side_block_name_1, side_block_options_text_layout_1[1]);
-            f.render_widget(side_block_name_2, side_block_options_text_layout_2[1]);
-            f.render_widget(side_block_name_3, side_block_options_text_layout_3[1]);
-        }
-        None => {}
+    if app.state.selected().is_some() {
+        f.render_widget(side_block_name_box, side_block_layout[1]);
+        f.render_widget(side_block_name, side_block_name_layout[1]);
+        f.render_widget(side_block_option_1, side_block_options_layout[1]);
+        f.render_widget(side_block_option_2, side_block_options_layout[3]);
+        f.render_widget(side_block_option_3, side_block_options_layout[2]);
+        f.render_widget(side_block_name_1, side_block_options_text_layout_1[1]);
+        f.render_widget(side_block_name_2, side_block_options_text_layout_2[1]);
+        f.render_widget(side_block_name_3, side_block_options_text_layout_3[1]);
     }
 }
@@ -2,13 +2,13 @@ use crate::ui::App;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
 
-pub fn render<B: Backend>(f: &mut Frame<B>, _app: &mut App) {
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // Center layout
     let center_col_layout = Layout::default()
         .vertical_margin(1)
@@ -25,7 +25,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, _app: &mut App) {
         .border_type(BorderType::Rounded)
         .title(Span::styled(" Error ", Style::default().add_modifier(Modifier::BOLD)))
         .title_alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Indexed(1)));
+        .style(app.theme.error);
 
     // Promt layout
     let promt_layout = Layout::default()
@@ -35,7 +35,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, _app: &mut App) {
 
     // Promt
     let promt = Paragraph::new(
-        Span::styled("SizeError: Not enough space to render widgets", Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+        Span::styled("SizeError: Not enough space to render widgets", app.theme.text.add_modifier(Modifier::BOLD))
     ) 
         .alignment(Alignment::Center);
 
@@ -1,38 +1,39 @@
-use crate::ui::App;
+use crate::ui::{self, App};
 use crate::ui::Selected;
-use crossterm::event::KeyCode;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
 
-pub fn handle_events(key_code: KeyCode, app: &mut App) {
-    match key_code {
-        KeyCode::Char(c) => {
-            if app.stack_name_input.len() < 22 {
-                app.stack_name_input.push(c)
-            }
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    if let Some(c) = keymap::typed(key) {
+        if app.stack_name_input.len() < 22 {
+            app.stack_name_input.push(c)
         }
-        KeyCode::Esc => {
+        return;
+    }
+    match app.keymap.action("stack_name_input", key) {
+        Some(Action::Back) => {
             app.stack_name_input = String::new();
             app.selected_window = Selected::Main;
         }
-        KeyCode::Enter => {
-            if app.stack_name_input.to_string() != "" {
-                app.add_stack(app.stack_name_input.to_string());
-                app.get_items();
-                app.stack_name_input = String::new();
-                app.selected_window = Selected::Main;
-            }
+        Some(Action::Confirm) if !app.stack_name_input.is_empty() => {
+            app.add_stack(app.stack_name_input.to_string());
+            app.get_items();
+            app.stack_name_input = String::new();
+            app.selected_window = Selected::Main;
         }
-        KeyCode::Backspace => {
-            app.stack_name_input.pop();
+        _ => {
+            if let KeyCode::Backspace = key.code {
+                app.stack_name_input.pop();
+            }
         }
-        _ => {}
     }
 }
 
@@ -63,12 +64,12 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Add Stack Popub window
     let add_stack_popup_block = Block::default()
-        .style(Style::default().fg(Color::Indexed(app.highlight_color)))
+        .style(app.theme.highlight)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .title(Span::styled(
             " Add Stack ",
-            Style::default().fg(Color::White),
+            app.theme.title,
         ))
         .title_alignment(Alignment::Center);
 
@@ -110,25 +111,24 @@ pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     // Add Stack Input Text
     let add_stack_input = Paragraph::new(Span::from(app.stack_name_input.as_ref()))
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.text)
         .alignment(Alignment::Left);
 
     // Add Stack "name:" text
     let add_stack_input_text = Paragraph::new(Span::from("name:"))
         .style(
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
+            app.theme.text.add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Right);
 
     // Add Stack input outline
     let add_stack_input_outline = Block::default()
-        .style(Style::default().fg(Color::White))
+        .style(app.theme.border)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
     // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
     f.render_widget(add_stack_popup_block, center_col_layout[1]);
     f.render_widget(add_stack_input_outline, add_stack_popup_input_layout[1]);
     f.render_widget(add_stack_input, add_stack_popup_layout_col_1[1]);
@@ -0,0 +1,109 @@
+use crate::ui::{self, App};
+use crate::ui::Selected;
+use crate::keymap::{self, Action};
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Modifier,
+    text::Span,
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+pub fn handle_events(key: KeyEvent, app: &mut App) {
+    if let Some(c) = keymap::typed(key) {
+        if app.tag_input.len() < 60 {
+            app.tag_input.push(c);
+        }
+        return;
+    }
+    match app.keymap.action("tag_cards", key) {
+        Some(Action::Back) => {
+            app.tag_input = String::new();
+            app.selected_window = Selected::BulkActions;
+        }
+        Some(Action::Confirm) if !app.tag_input.trim().is_empty() => {
+            let input = app.tag_input.to_string();
+            app.bulk_tag(&input);
+            app.tag_input = String::new();
+            app.selected_window = Selected::CardList;
+        }
+        _ => {
+            if let KeyCode::Backspace = key.code {
+                app.tag_input.pop();
+            }
+        }
+    }
+}
+
+pub fn render<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // Center Layout for pupup window
+    let center_row_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let center_col_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ]
+            .as_ref(),
+        )
+        .split(center_row_layout[1]);
+
+    // Tag cards box
+    let tag_cards_block = Block::default()
+        .style(app.theme.highlight)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(" Tag Cards ", app.theme.title))
+        .title_alignment(Alignment::Center);
+
+    // Tag cards layout
+    let tag_cards_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .vertical_margin(2)
+        .horizontal_margin(6)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(center_col_layout[1]);
+
+    // Tag input box
+    let tag_input_block = Block::default()
+        .style(app.theme.border)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+
+    // Tag input
+    let tag_input = Paragraph::new(Span::from(app.tag_input.as_str()))
+        .style(app.theme.text)
+        .block(tag_input_block);
+
+    // Tag input help
+    let tag_input_help = Paragraph::new(Span::styled(
+        "tags separated by spaces, -tag removes",
+        app.theme.text.add_modifier(Modifier::DIM),
+    ))
+    .alignment(Alignment::Center);
+
+    // Render
+    ui::popup(f, center_col_layout[1], &app.theme);
+    f.render_widget(tag_cards_block, center_col_layout[1]);
+    f.render_widget(tag_input, tag_cards_layout[1]);
+    f.render_widget(tag_input_help, tag_cards_layout[3]);
+}
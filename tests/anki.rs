@@ -0,0 +1,153 @@
+use cards::db::card::Schedule;
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::export::{self, Format};
+use cards::import::anki::{self, TempFile};
+use rusqlite::Connection;
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+// Path of a file in a temporary directory
+fn temp_path(name: &str) -> String {
+    let dir = env::temp_dir().join(format!("cards-anki-test-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(name).display().to_string()
+}
+
+// Package with the given files
+fn package(name: &str, files: &[(&str, &[u8])]) -> String {
+    let path = temp_path(name);
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+    for (name, data) in files {
+        zip.start_file(*name, FileOptions::default()).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap();
+    path
+}
+
+// Collection with the tables and columns an import reads
+fn collection() -> Vec<u8> {
+    let tmp = TempFile::new("anki2").unwrap();
+    let conn = Connection::open(tmp.path()).unwrap();
+    conn.execute_batch(
+        r#"
+        CREATE TABLE col (crt INTEGER, decks TEXT);
+        CREATE TABLE notes (id INTEGER PRIMARY KEY, flds TEXT, tags TEXT);
+        CREATE TABLE cards (id INTEGER PRIMARY KEY, nid INTEGER, did INTEGER, odid INTEGER, ord INTEGER,
+            type INTEGER, queue INTEGER, due INTEGER, ivl INTEGER, factor INTEGER, reps INTEGER, lapses INTEGER);
+        CREATE TABLE revlog (id INTEGER PRIMARY KEY, cid INTEGER, ease INTEGER);
+        INSERT INTO col VALUES (1700000000, '{"1": {"name": "Default"}, "2": {"name": "Spanish::Animals"}}');
+        INSERT INTO notes VALUES (1, 'perro<br>(m.)' || char(31) || '<b>dog</b> &amp; hound', ' animals ');
+        INSERT INTO notes VALUES (2, '{{c1::gato}} is cat' || char(31) || '', '');
+        INSERT INTO cards VALUES (10, 1, 2, 0, 0, 2, 2, 3, 3, 2600, 2, 0);
+        INSERT INTO cards VALUES (11, 1, 2, 0, 1, 0, -1, 0, 0, 0, 0, 0);
+        INSERT INTO cards VALUES (12, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0);
+        INSERT INTO revlog VALUES (1700000000000, 10, 3);
+        INSERT INTO revlog VALUES (1700100000000, 10, 0);
+        INSERT INTO revlog VALUES (1700200000000, 10, 4);
+        "#,
+    )
+    .unwrap();
+    drop(conn);
+    fs::read(tmp.path()).unwrap()
+}
+
+#[test]
+fn read_package() {
+    let path = package("deck.apkg", &[("collection.anki2", &collection())]);
+    let decks = anki::read(&path).ok().unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(decks.len(), 2);
+
+    let default = &decks[0];
+    assert_eq!(default.name, "Default");
+    assert!(default.cards.is_empty());
+    assert_eq!(default.skipped[0].reason, "cloze notes are not supported");
+
+    let animals = &decks[1];
+    assert_eq!(animals.name, "Spanish::Animals");
+    let (_, card) = &animals.cards[0];
+    assert_eq!((card.title.as_str(), card.text.as_str()), ("perro\n(m.)", "dog & hound"));
+    assert_eq!(card.tags, vec!["animals"]);
+    assert_eq!(card.schedule.due, 1_700_000_000 + 3 * 24 * 60 * 60);
+    assert_eq!((card.schedule.interval, card.schedule.ease, card.schedule.reps), (3, 2600, 2));
+    assert_eq!(card.reviews, vec![(3, 1_700_000_000), (4, 1_700_200_000)]);
+
+    // The reverse card is new and suspended
+    let (_, reverse) = &animals.cards[1];
+    assert_eq!((reverse.title.as_str(), reverse.text.as_str()), ("dog & hound", "perro\n(m.)"));
+    assert!(reverse.suspended);
+    assert_eq!(reverse.schedule.reps, 0);
+}
+
+#[test]
+fn read_errors() {
+    let empty = package("empty.apkg", &[("media", b"{}")]);
+    assert_eq!(anki::read(&empty).err().unwrap().to_string(), "no Anki collection in the package");
+    let broken = package("broken.apkg", &[("collection.anki2", b"not a database")]);
+    assert!(anki::read(&broken).is_err());
+    fs::remove_file(&empty).unwrap();
+    fs::remove_file(&broken).unwrap();
+    assert!(anki::read("missing.apkg").is_err());
+}
+
+#[test]
+fn temp_files_are_new_and_removed() {
+    let first = TempFile::new("db").unwrap();
+    let second = TempFile::new("db").unwrap();
+    assert_ne!(first.path(), second.path());
+    assert!(first.path().exists());
+
+    let path = first.path().to_path_buf();
+    let journal = format!("{}-journal", path.display());
+    fs::write(&journal, b"").unwrap();
+    drop(first);
+    assert!(!path.exists());
+    assert!(!Path::new(&journal).exists());
+}
+
+#[test]
+fn export_round_trip() {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust".to_string());
+    store.add_card(rust, "Box<T>".to_string(), "Heap pointer\n& more".to_string());
+    store.add_card(rust, "Rc".to_string(), "Shared pointer".to_string());
+    let traits = store.add_stack("Rust::Traits".to_string());
+    store.add_card(traits, "Send".to_string(), "Moves between threads".to_string());
+    let mut cards = store.cards(rust);
+    cards[0].tags = "smart heap".to_string();
+    cards[1].suspended = true;
+    cards[1].schedule = Schedule {
+        due: 1_700_086_400,
+        interval: 6,
+        ease: 2360,
+        reps: 3,
+        lapses: 1,
+    };
+    store.update_cards(&cards);
+
+    let path = temp_path("export.apkg");
+    assert_eq!(export::to_file(&store, Some(rust), true, &path, Format::Anki).unwrap(), 3);
+    let decks = anki::read(&path).ok().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let names: Vec<&str> = decks.iter().map(|deck| deck.name.as_str()).collect();
+    assert_eq!(names, vec!["Rust", "Rust::Traits"]);
+    let (_, boxed) = &decks[0].cards[0];
+    assert_eq!((boxed.title.as_str(), boxed.text.as_str()), ("Box<T>", "Heap pointer\n& more"));
+    assert_eq!(boxed.tags, vec!["smart", "heap"]);
+    assert_eq!(boxed.schedule.reps, 0);
+
+    // The schedule is kept to the day
+    let (_, rc) = &decks[0].cards[1];
+    assert!(rc.suspended);
+    let schedule = &rc.schedule;
+    assert_eq!((schedule.interval, schedule.ease, schedule.reps, schedule.lapses), (6, 2360, 3, 1));
+    assert!((schedule.due - 1_700_086_400).abs() < 24 * 60 * 60);
+}
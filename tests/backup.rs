@@ -0,0 +1,113 @@
+mod common;
+
+use cards::backup;
+use cards::db::sqlite::SqliteStore;
+use cards::db::Store;
+use std::fs;
+use std::path::PathBuf;
+
+// Empty directory for a test
+fn test_dir(name: &str) -> PathBuf {
+    common::test_dir("backup", name)
+}
+
+fn card_count(store: &dyn Store) -> usize {
+    store.stacks().iter().map(|stack| store.cards(stack.id).len()).sum()
+}
+
+#[test]
+fn backups_are_taken_after_enough_changes() {
+    let dir = test_dir("changes");
+    let backups = dir.join("backups").to_string_lossy().to_string();
+    let db = dir.join("cards.db").to_string_lossy().to_string();
+    let mut store = SqliteStore::open(&db).unwrap().with_backups(&backups);
+
+    // Adding the stack is the first change
+    let stack = store.add_stack("Rust".to_string());
+    for i in 1..backup::EVERY - 1 {
+        store.add_card(stack, format!("Card {}", i), String::new());
+    }
+    assert!(backup::list(&backups).is_empty());
+
+    store.add_card(stack, "Last".to_string(), String::new());
+    let list = backup::list(&backups);
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].stacks, 1);
+    assert_eq!(list[0].cards as usize, backup::EVERY - 2);
+}
+
+#[test]
+fn startup_backup_only_when_changed() {
+    let dir = test_dir("startup");
+    let backups = dir.join("backups").to_string_lossy().to_string();
+    let db = dir.join("cards.db").to_string_lossy().to_string();
+
+    // No database yet, nothing to back up
+    backup::create_on_startup(&db, &backups).unwrap();
+    assert!(backup::list(&backups).is_empty());
+
+    let mut store = SqliteStore::open(&db).unwrap();
+    store.add_stack("Rust".to_string());
+    drop(store);
+    backup::create_on_startup(&db, &backups).unwrap();
+    backup::create_on_startup(&db, &backups).unwrap();
+    assert_eq!(backup::list(&backups).len(), 1);
+}
+
+#[test]
+fn restore_replaces_the_collection() {
+    let dir = test_dir("restore");
+    let backups = dir.join("backups").to_string_lossy().to_string();
+    let db = dir.join("cards.db").to_string_lossy().to_string();
+    let mut store = SqliteStore::open(&db).unwrap().with_backups(&backups);
+
+    let stack = store.add_stack("Rust".to_string());
+    store.add_card(stack, "Box".to_string(), "Heap pointer".to_string());
+    let path = backup::create(&store.conn, &backups).unwrap();
+    store.delete_stack(stack);
+    assert_eq!(card_count(&store), 0);
+
+    store.restore(&path).unwrap();
+    assert_eq!(card_count(&store), 1);
+    assert_eq!(store.stacks()[0].name, "Rust");
+
+    // Changes after a restore go to the restored database
+    store.add_card(stack, "Rc".to_string(), String::new());
+    drop(store);
+    let store = SqliteStore::open(&db).unwrap();
+    assert_eq!(card_count(&store), 2);
+}
+
+#[test]
+fn old_backups_are_rotated() {
+    let dir = test_dir("rotate");
+    let backups = dir.join("backups");
+    fs::create_dir_all(&backups).unwrap();
+    let db = dir.join("cards.db").to_string_lossy().to_string();
+    let store = SqliteStore::open(&db).unwrap();
+
+    for i in 0..backup::KEEP + 3 {
+        fs::copy(&db, backups.join(format!("cards-20200101-0000{:02}.db", i))).unwrap();
+    }
+    backup::create(&store.conn, &backups.to_string_lossy()).unwrap();
+    assert_eq!(backup::list(&backups.to_string_lossy()).len(), backup::KEEP);
+}
+
+#[test]
+fn failed_restore_keeps_the_collection() {
+    let dir = test_dir("failed-restore");
+    let backups = dir.join("backups").to_string_lossy().to_string();
+    let db = dir.join("cards.db").to_string_lossy().to_string();
+    let mut store = SqliteStore::open(&db).unwrap();
+    let stack = store.add_stack("Rust".to_string());
+    store.add_card(stack, "Box".to_string(), "Heap pointer".to_string());
+    let path = backup::create(&store.conn, &backups).unwrap();
+    store.add_card(stack, "Rc".to_string(), String::new());
+
+    // The backup can't be written where a directory is in the way
+    fs::create_dir_all(format!("{}.restore", db)).unwrap();
+    assert!(store.restore(&path).is_err());
+    assert_eq!(card_count(&store), 2);
+    store.add_card(stack, "Arc".to_string(), String::new());
+    assert_eq!(card_count(&store), 3);
+}
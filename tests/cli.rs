@@ -0,0 +1,177 @@
+use cards::cli::{self, Command};
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(|arg| arg.to_string()).collect()
+}
+
+// Output of a command run on a store
+fn run(store: &mut MemoryStore, line: &str) -> Result<String, String> {
+    let command = cli::parse(&args(line))?;
+    let mut out = vec![];
+    cli::run(command, store, &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+fn store() -> MemoryStore {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust".to_string());
+    store.add_card(rust, "Box".to_string(), "Heap pointer".to_string());
+    let child = store.add_stack("Rust::Traits".to_string());
+    store.add_card(child, "Send".to_string(), "Safe to move between threads".to_string());
+    let spanish = store.add_stack("Spanish".to_string());
+    store.add_card(spanish, "hola".to_string(), "hello".to_string());
+    store
+}
+
+#[test]
+fn parse_commands() {
+    assert!(matches!(cli::parse(&[]), Ok(Command::Ui)));
+    assert!(matches!(cli::parse(&args("--help")), Ok(Command::Help)));
+    assert!(matches!(
+        cli::parse(&args("add --stack Rust --front=Rc --back Shared --tags ptr")),
+        Ok(Command::Add { stack, front, back, tags: Some(tags) })
+            if stack == "Rust" && front == "Rc" && back == "Shared" && tags == "ptr"
+    ));
+    assert!(matches!(
+        cli::parse(&args("import deck.apkg --no-history")),
+        Ok(Command::Import { path, stack: None, history: false }) if path == "deck.apkg"
+    ));
+    assert!(matches!(
+        cli::parse(&args("review Spanish --all")),
+        Ok(Command::Review { stack, all: true }) if stack == "Spanish"
+    ));
+    assert!(matches!(
+        cli::parse(&args("export cards.csv --stack Rust")),
+        Ok(Command::Export { path, stack: Some(stack), substacks: false }) if path == "cards.csv" && stack == "Rust"
+    ));
+
+    assert_eq!(cli::parse(&args("add --stack Rust --front Rc")).err().unwrap(), "Missing --back");
+    assert_eq!(cli::parse(&args("list --all")).err().unwrap(), "Unknown option --all");
+    assert_eq!(cli::parse(&args("list --stack")).err().unwrap(), "Missing value for --stack");
+    assert_eq!(cli::parse(&args("export")).err().unwrap(), "Missing <file>");
+    assert_eq!(cli::parse(&args("stacks extra")).err().unwrap(), "Unexpected argument \"extra\"");
+    assert_eq!(cli::parse(&args("review")).err().unwrap(), "Missing <stack>");
+    assert_eq!(cli::parse(&args("learn")).err().unwrap(), "Unknown command \"learn\"");
+    assert_eq!(cli::parse(&args("--export cards.csv")).err().unwrap(), "Unknown command \"--export\"");
+}
+
+#[test]
+fn list_stacks_and_cards() {
+    let mut store = store();
+    let ids: Vec<i32> = store.stacks().iter().map(|stack| stack.id).collect();
+    assert_eq!(
+        run(&mut store, "stacks").unwrap(),
+        format!(
+            "ID  STACK         CARDS  DUE  NEW\n\
+             {}   Rust          1      0    1\n\
+             {}   Rust::Traits  1      0    1\n\
+             {}   Spanish       1      0    1\n",
+            ids[0], ids[1], ids[2]
+        )
+    );
+
+    // A stack comes with its child stacks
+    let list = run(&mut store, "list --stack Rust").unwrap();
+    assert_eq!(list.lines().count(), 3);
+    assert!(list.contains("Box") && list.contains("Send") && !list.contains("hola"));
+
+    assert_eq!(run(&mut store, "list --stack Nope").err().unwrap(), "No stack named \"Nope\"");
+}
+
+#[test]
+fn add_card_to_new_stack() {
+    let mut store = store();
+    let out = run(&mut store, "add --stack German --front Hund --back dog --tags noun,animal").unwrap();
+    let stack = store.stacks().into_iter().find(|stack| stack.name == "German").unwrap();
+    let cards = store.cards(stack.id);
+    assert_eq!(out, format!("Added card {} to German\n", cards[0].id));
+    assert_eq!(cards[0].title, "Hund");
+    assert_eq!(cards[0].tags, "noun animal");
+}
+
+#[test]
+fn due_and_stats() {
+    let mut store = store();
+    let spanish = store.stacks()[2].id;
+    let mut cards = store.cards(spanish);
+    cards[0].suspended = true;
+    store.update_cards(&cards);
+    let rust = store.stacks()[0].id;
+    let mut cards = store.cards(rust);
+    cards[0].schedule.reps = 1;
+    store.update_cards(&cards);
+
+    // New cards are counted apart from the due ones
+    let due = run(&mut store, "due").unwrap();
+    assert!(due.contains("Box"));
+    assert!(!due.contains("Send"));
+    assert!(!due.contains("hola"));
+    assert_eq!(run(&mut store, "due --stack Spanish").unwrap(), "No cards due\n");
+
+    let stats = run(&mut store, "stats").unwrap();
+    assert!(stats.contains("Stacks:        3\n"));
+    assert!(stats.contains("Cards:         3\n"));
+    assert!(stats.contains("New:           1\n"));
+    assert!(stats.contains("Due:           1\n"));
+    assert!(stats.contains("Suspended:     1\n"));
+}
+
+#[test]
+fn json_output() {
+    let mut store = store();
+    let rust = store.stacks()[0].id;
+    let card = store.cards(rust)[0].id;
+    store.add_review(card, 3, 1_700_000_000);
+
+    let stacks: serde_json::Value = serde_json::from_str(&run(&mut store, "stacks --format json").unwrap()).unwrap();
+    assert_eq!(stacks["schema_version"], cli::JSON_SCHEMA_VERSION);
+    assert_eq!(stacks["stacks"][0]["name"], "Rust");
+    assert_eq!(stacks["stacks"][0]["cards"], 1);
+
+    let cards: serde_json::Value = serde_json::from_str(&run(&mut store, "list --stack Rust --format json").unwrap()).unwrap();
+    assert_eq!(cards["cards"].as_array().unwrap().len(), 2);
+    assert_eq!(cards["cards"][0]["title"], "Box");
+    assert_eq!(cards["cards"][0]["stack"], "Rust");
+    assert_eq!(cards["cards"][0]["schedule"]["ease"], 2500);
+    assert_eq!(cards["cards"][0]["reviews"][0]["grade"], 3);
+    assert_eq!(cards["cards"][1]["stack"], "Rust::Traits");
+
+    // No cards due is an empty list
+    let mut cards = store.cards(store.stacks()[2].id);
+    cards[0].suspended = true;
+    store.update_cards(&cards);
+    let due: serde_json::Value = serde_json::from_str(&run(&mut store, "due --stack Spanish --format json").unwrap()).unwrap();
+    assert_eq!(due["cards"], serde_json::json!([]));
+
+    let stats: serde_json::Value = serde_json::from_str(&run(&mut store, "stats --format json").unwrap()).unwrap();
+    assert_eq!(stats["stats"]["cards"], 3);
+    assert_eq!(stats["stats"]["reviews"], 1);
+    assert_eq!(stats["stats"]["average_ease"], serde_json::Value::Null);
+
+    assert_eq!(
+        cli::parse(&args("stats --format xml")).err().unwrap(),
+        "Unknown format \"xml\", use text or json"
+    );
+}
+
+// Output that was closed by the reader
+struct Closed;
+
+impl std::io::Write for Closed {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn closed_output() {
+    let mut store = store();
+    let command = cli::parse(&args("list")).unwrap();
+    assert_eq!(cli::run(command, &mut store, &mut Closed).err().unwrap(), cli::OUTPUT_CLOSED);
+}
@@ -0,0 +1,12 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+// Empty directory for a test, named after the test file and the test
+pub fn test_dir(file: &str, name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("cards-{}-{}-{}", file, name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
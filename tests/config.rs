@@ -0,0 +1,122 @@
+mod common;
+
+use cards::config;
+use std::env;
+use std::fs;
+
+#[test]
+fn check_settings() {
+    let setting = |key: &str| config::setting(key).unwrap();
+    assert_eq!(config::parse_setting(setting("backups"), "true"), Ok(toml::Value::Boolean(true)));
+    assert!(config::parse_setting(setting("backups"), "yes").is_err());
+    assert!(config::parse_setting(setting("stack_sort"), "recent").is_ok());
+    assert!(config::parse_setting(setting("stack_sort"), "size").is_err());
+    assert!(config::parse_setting(setting("theme"), "high-contrast").is_ok());
+    assert!(config::parse_setting(setting("theme"), "solarized").is_err());
+
+    assert_eq!(config::check_highlight_color(" 12 "), Ok(12));
+    assert_eq!(
+        config::check_highlight_color("300"),
+        Err("must be from 0 to 255, got 300".to_string())
+    );
+    assert_eq!(
+        config::check_highlight_color("blue"),
+        Err("must be a number from 0 to 255, got \"blue\"".to_string())
+    );
+
+    let dir = env::temp_dir();
+    assert!(config::check_db_file(dir.join("cards.db").to_str().unwrap()).is_ok());
+    assert_eq!(config::check_db_file(" "), Err("must not be empty".to_string()));
+    assert!(config::check_db_file(dir.to_str().unwrap()).unwrap_err().ends_with("is a directory"));
+    assert!(config::check_db_file("/missing-dir/cards.db")
+        .unwrap_err()
+        .starts_with("directory /missing-dir does not exist"));
+}
+
+#[test]
+fn load_and_save() {
+    let dir = common::test_dir("config", "load_and_save");
+    let file = dir.join("config.toml");
+    let load = || config::load_file(&file);
+    let set_config = |values: &[(&str, &str)]| config::set_config_file(&file, values);
+    let path = file.to_string_lossy().to_string();
+
+    // Invalid settings keep their default and are all reported
+    fs::write(&file, "db_file = \"/missing-dir/x.db\"\nhighlight_color = \"blue\"\ncolour = 3\n").unwrap();
+    let (loaded, errors) = load();
+    assert_eq!(loaded.highlight_color, config::DEFAULT_HIGHLIGHT_COLOR);
+    assert!(loaded.db_file.ends_with("cards.db"));
+    let keys: Vec<Option<&str>> = errors.iter().map(|error| error.key.as_deref()).collect();
+    assert_eq!(keys, [Some("colour"), Some("db_file"), Some("highlight_color")]);
+    assert_eq!(
+        errors[2].to_string(),
+        format!("{}: highlight_color: must be a number from 0 to 255, got \"blue\"", path)
+    );
+
+    fs::write(&file, "highlight_color = 3\ndb_file = \"x.db\n").unwrap();
+    let (loaded, errors) = load();
+    assert_eq!(loaded.highlight_color, config::DEFAULT_HIGHLIGHT_COLOR);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].key, None);
+    assert!(errors[0].reason.starts_with("line 2: invalid TOML"));
+
+    // Nothing is saved while a value is invalid
+    let errors = set_config(&[("db_file", ""), ("highlight_color", "256")]).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(fs::read_to_string(&file).unwrap().contains("db_file = \"x.db\n"));
+
+    let db_file = dir.join("cards.db").to_string_lossy().to_string();
+    set_config(&[("db_file", &db_file), ("highlight_color", "9")]).unwrap();
+    let (loaded, errors) = load();
+    assert!(errors.is_empty());
+    assert_eq!(loaded.db_file, db_file);
+    assert_eq!(loaded.highlight_color, 9);
+
+    // Every setting is written with its help, grouped by section
+    set_config(&[("backups", "false"), ("stack_sort", "recent")]).unwrap();
+    let saved = fs::read_to_string(&file).unwrap();
+    for setting in config::SETTINGS {
+        assert!(saved.contains(&format!("\n{} = ", setting.key)));
+    }
+    assert!(saved.contains("# Appearance\n"));
+    assert!(saved.contains("backups = false\n"));
+    let (loaded, _) = load();
+    assert!(!loaded.backups);
+    assert_eq!(loaded.stack_sort, "recent");
+    assert_eq!(loaded.highlight_color, 9);
+
+    fs::write(&file, "backups = \"no\"\nstack_sort = \"size\"\n").unwrap();
+    let reasons: Vec<String> = load().1.into_iter().map(|error| error.reason).collect();
+    assert_eq!(
+        reasons,
+        ["must be true or false, got \"no\"", "must be one of created, recent, got \"size\""]
+    );
+
+    // Key bindings are checked by the keymap and kept when settings are saved
+    fs::write(&file, "[keys]\ndown = \"C-n\"\nup = \"c\"\n\n[keys.card_list]\ndelete = \"x\"\n").unwrap();
+    let (loaded, errors) = load();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), format!("{}: keys.up: c is also bound to settings in main", path));
+    assert_eq!(loaded.keys["down"].as_str(), Some("C-n"));
+    set_config(&[("backups", "false")]).unwrap();
+    let saved = fs::read_to_string(&file).unwrap();
+    assert!(saved.contains("[keys]\ndown = \"C-n\"\n"));
+    assert!(saved.contains("[keys.card_list]\ndelete = \"x\"\n"));
+    assert_eq!(load().0.keys, loaded.keys);
+
+    // Colors are checked by the theme and kept like the bindings
+    fs::write(&file, "[colors]\ntext = 250\ntitle = \"teal\"\n\n[colors.popup]\nbg = 236\n").unwrap();
+    let (loaded, errors) = load();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].key.as_deref(), Some("colors.title"));
+    set_config(&[("backups", "false")]).unwrap();
+    let saved = fs::read_to_string(&file).unwrap();
+    assert!(saved.contains("[colors]\ntext = 250\n"));
+    assert!(saved.contains("[colors.popup]\nbg = 236\n"));
+    assert_eq!(load().0.colors, loaded.colors);
+
+    fs::write(&file, "keys = \"emacs\"\n").unwrap();
+    assert_eq!(load().1[0].reason, "must be a table, got \"emacs\"");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
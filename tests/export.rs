@@ -0,0 +1,107 @@
+use cards::db::card::Schedule;
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::export::{self, csv, json};
+use cards::import::{self, Target};
+
+// Rust with a tagged, a suspended and a reviewed card, Rust::Traits and Spanish
+fn sample() -> (MemoryStore, i32) {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust".to_string());
+    store.add_card(rust, "Box".to_string(), "Heap pointer, \"owned\"".to_string());
+    store.add_card(rust, "Rc".to_string(), "Shared pointer\nnot Send".to_string());
+    store.add_card(rust, "Cell".to_string(), "Interior mutability".to_string());
+    let mut cards = store.cards(rust);
+    cards[0].tags = "smart heap".to_string();
+    cards[1].suspended = true;
+    cards[2].schedule = Schedule {
+        due: 1_700_086_400,
+        interval: 6,
+        ease: 2360,
+        reps: 3,
+        lapses: 1,
+    };
+    store.update_cards(&cards);
+    let traits = store.add_stack("Rust::Traits".to_string());
+    store.add_card(traits, "Send".to_string(), "Safe to move to another thread".to_string());
+    let spanish = store.add_stack("Spanish".to_string());
+    store.add_card(spanish, "perro".to_string(), "dog".to_string());
+    (store, rust)
+}
+
+// Stack names of an export
+fn names(store: &MemoryStore, stack_id: Option<i32>, substacks: bool) -> Vec<String> {
+    export::collect(store, stack_id, substacks)
+        .into_iter()
+        .map(|(stack, _)| stack.name)
+        .collect()
+}
+
+#[test]
+fn child_stacks_are_opt_in() {
+    let (store, rust) = sample();
+    assert_eq!(names(&store, Some(rust), false), vec!["Rust"]);
+    assert_eq!(names(&store, Some(rust), true), vec!["Rust", "Rust::Traits"]);
+    assert_eq!(names(&store, None, false), vec!["Rust", "Rust::Traits", "Spanish"]);
+}
+
+#[test]
+fn json_round_trip() {
+    let (store, _) = sample();
+    let exported = json::to_string(&export::collect(&store, None, false));
+    assert!(exported.starts_with("{\n  \"schema_version\": 1,"));
+
+    let mut imported = MemoryStore::new();
+    import::import_decks(&mut imported, import::json::parse(&exported).unwrap(), true);
+    let again = json::to_string(&export::collect(&imported, None, false));
+
+    // Everything but the times the stacks and cards were made at is kept
+    let mut exported: serde_json::Value = serde_json::from_str(&exported).unwrap();
+    let mut again: serde_json::Value = serde_json::from_str(&again).unwrap();
+    for value in [&mut exported, &mut again] {
+        for stack in value["stacks"].as_array_mut().unwrap() {
+            stack["created_at"] = 0.into();
+            stack["updated_at"] = 0.into();
+            for card in stack["cards"].as_array_mut().unwrap() {
+                card["created_at"] = 0.into();
+                card["updated_at"] = 0.into();
+            }
+        }
+    }
+    assert_eq!(exported, again);
+    assert_eq!(again["stacks"][0]["cards"][2]["ease"], 2360);
+}
+
+#[test]
+fn json_schema_version() {
+    let content = r#"{"schema_version": 1, "stacks": [{"name": "Rust", "created_at": 0, "updated_at": 0, "cards": [
+        {"title": "Box", "text": "Heap pointer", "tags": [], "suspended": false, "created_at": 0,
+         "updated_at": 0, "due": 0, "interval": 0, "ease": 2500, "reps": 0, "lapses": 0}]}]}"#;
+    let decks = import::json::parse(content).unwrap();
+    assert_eq!(decks[0].name, "Rust");
+    assert_eq!(decks[0].cards[0].1.title, "Box");
+
+    let newer = content.replace("\"schema_version\": 1", "\"schema_version\": 2");
+    assert!(import::json::parse(&newer).err().unwrap().to_string().contains("newer"));
+    let unversioned = content.replace("\"schema_version\"", "\"version\"");
+    assert!(import::json::parse(&unversioned).err().unwrap().to_string().contains("schema_version"));
+}
+
+#[test]
+fn csv_round_trip() {
+    for delimiter in [b',', b'\t'] {
+        let (store, rust) = sample();
+        let exported = csv::to_string(&export::collect(&store, Some(rust), false), delimiter);
+
+        let mut imported = MemoryStore::new();
+        let rows = import::csv::parse(&exported, delimiter).unwrap();
+        let has_header = import::csv::detect_header(&rows);
+        let columns = import::csv::default_columns(&rows, has_header);
+        let report = import::csv::import(&mut imported, &Target::New("Rust".to_string()), &rows, &columns, has_header);
+        assert_eq!(report.added, 3);
+        assert!(report.skipped.is_empty());
+
+        let again = csv::to_string(&export::collect(&imported, None, false), delimiter);
+        assert_eq!(again, exported);
+    }
+}
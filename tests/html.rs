@@ -0,0 +1,23 @@
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::export::{self, html};
+
+#[test]
+fn html_escapes_cards() {
+    let mut store = MemoryStore::new();
+    let stack = store.add_stack("Web".to_string());
+    store.add_card(stack, "<b>Tags</b>".to_string(), "</script><script>alert(1)</script>\n& more".to_string());
+    let child = store.add_stack("Web::CSS".to_string());
+    store.add_card(child, "Selector".to_string(), "a > b".to_string());
+
+    let page = html::to_string(&export::collect(&store, Some(stack), true));
+    assert!(page.contains("<title>Web</title>"));
+    assert!(page.contains("<h2>Web::CSS</h2>"));
+    assert!(page.contains("<td>&lt;b&gt;Tags&lt;/b&gt;</td>"));
+    assert!(page.contains("<td>a &gt; b</td>"));
+
+    // The card data can't end its script element early
+    let data = page.split("id=\"data\">").nth(1).unwrap().split("</script>").next().unwrap();
+    assert!(data.contains("\\u003c/script>\\u003cscript>alert(1)\\u003c/script>\\n& more"));
+    assert_eq!(page.matches("<script").count(), 2);
+}
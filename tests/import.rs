@@ -0,0 +1,66 @@
+use cards::db::card::Schedule;
+use cards::db::sqlite::SqliteStore;
+use cards::db::Store;
+use cards::import::{self, Deck, NewCard};
+
+fn card(title: &str, text: &str) -> NewCard {
+    NewCard {
+        title: title.to_string(),
+        text: text.to_string(),
+        ..NewCard::default()
+    }
+}
+
+#[test]
+fn dedup_against_stack_and_earlier_rows() {
+    let existing = [("hola".to_string(), "hello".to_string())].into_iter().collect();
+    let cards = vec![
+        (1, card("hola", "hello")),
+        (2, card("perro", "dog")),
+        (3, card("perro", "")),
+        (4, card("perro", "dog")),
+        (5, card("perro", "hound")),
+    ];
+    let mut skipped = vec![];
+    let kept = import::dedup(cards, existing, &mut skipped);
+    let kept: Vec<(&str, &str)> = kept.iter().map(|card| (card.title.as_str(), card.text.as_str())).collect();
+    assert_eq!(kept, vec![("perro", "dog"), ("perro", "hound")]);
+    let rows: Vec<usize> = skipped.iter().map(|skipped| skipped.row).collect();
+    assert_eq!(rows, vec![1, 3, 4]);
+}
+
+#[test]
+fn decks_are_imported_in_one_transaction() {
+    let mut store = SqliteStore::open(":memory:").unwrap();
+    let mut deck = Deck::new("Rust");
+    deck.cards.push((1, card("Box", "Heap pointer")));
+    deck.cards.push((
+        2,
+        NewCard {
+            tags: vec!["smart".to_string()],
+            schedule: Schedule {
+                due: 1_700_000_000,
+                interval: 3,
+                ease: 2600,
+                reps: 2,
+                lapses: 0,
+            },
+            reviews: vec![(3, 1_699_000_000), (4, 1_699_500_000)],
+            ..card("Rc", "Shared pointer")
+        },
+    ));
+
+    let reports = import::import_decks(&mut store, vec![deck], true);
+    assert_eq!(reports[0].1.added, 2);
+    assert!(store.conn.is_autocommit());
+
+    let stack = store.stacks()[0].id;
+    let cards = store.cards(stack);
+    assert_eq!(cards[1].tags, "smart");
+    assert_eq!(cards[1].schedule.ease, 2600);
+    assert_eq!(store.reviews(cards[1].id).len(), 2);
+
+    // Batches after the import get transactions of their own again
+    store.delete_cards(&[cards[0].id]);
+    assert_eq!(store.cards(stack).len(), 1);
+}
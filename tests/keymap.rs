@@ -0,0 +1,175 @@
+use cards::config::Config;
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::keymap::{self, Action, Key, Keymap};
+use cards::state::{App, PasteFocus, Selected};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn ctrl(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+}
+
+// Keymap of a [keys] table
+fn keymap(keys: &str) -> (Keymap, Vec<(String, String)>) {
+    Keymap::new(&keys.parse::<toml::Table>().unwrap())
+}
+
+#[test]
+fn parse_keys() {
+    for text in ["a", "V", "/", "Space", "Enter", "BackTab", "C-n", "M-x", "C-M-Left", "S-Up", "F5", "C--"] {
+        assert_eq!(Key::parse(text).unwrap().to_string(), text);
+    }
+    assert_eq!(Key::parse("esc").unwrap(), Key::parse("Esc").unwrap());
+    assert_eq!(Key::parse("Ctl-x"), Err("unknown key \"Ctl-x\"".to_string()));
+    assert!(Key::parse("F13").is_err());
+    assert!(Key::parse("S-a").unwrap_err().contains("can't have S-"));
+
+    // Shift is part of the character
+    let upper = KeyEvent::new(KeyCode::Char('V'), KeyModifiers::SHIFT);
+    assert_eq!(Key::from_event(upper), Key::parse("V").unwrap());
+    assert_eq!(keymap::typed(upper), Some('V'));
+    assert_eq!(keymap::typed(ctrl('n')), None);
+}
+
+#[test]
+fn default_keys() {
+    let (keymap, errors) = keymap("");
+    assert!(errors.is_empty());
+    assert_eq!(keymap.action("main", key(KeyCode::Char('r'))), Some(Action::Sort));
+    assert_eq!(keymap.action("card_list", key(KeyCode::Char('r'))), Some(Action::Sort));
+    assert_eq!(keymap.action("side", key(KeyCode::Char('s'))), Some(Action::Study));
+    assert_eq!(keymap.action("import_file", key(KeyCode::Char('h'))), Some(Action::Left));
+    assert_eq!(keymap.action("settings", key(KeyCode::Char('k'))), None);
+    assert_eq!(keymap.key("card_list", Action::Mark), "Space");
+
+    // Windows typing text only bind keys that don't type, but for toggling options
+    for window in keymap::WINDOWS.iter().filter(|window| window.typing) {
+        for action in window.actions.iter().filter(|action| **action != Action::Toggle) {
+            assert!(keymap.keys(window.name, *action).iter().all(|key| !key.is_printable()));
+        }
+    }
+}
+
+#[test]
+fn overrides() {
+    let (keymap, errors) = keymap(
+        r#"
+        up = ["C-p", "Up"]
+        down = ["C-n", "Down"]
+
+        [card_list]
+        delete = "x"
+        "#,
+    );
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(keymap.action("main", ctrl('n')), Some(Action::Down));
+    assert_eq!(keymap.action("move_cards", ctrl('p')), Some(Action::Up));
+    assert_eq!(keymap.action("main", key(KeyCode::Char('j'))), None);
+    assert_eq!(keymap.action("card_list", key(KeyCode::Char('x'))), Some(Action::Delete));
+    assert_eq!(keymap.action("card_list", key(KeyCode::Char('d'))), None);
+    assert_eq!(keymap.action("main", key(KeyCode::Char('d'))), Some(Action::Delete));
+}
+
+#[test]
+fn typing_windows_keep_their_default_keys() {
+    let (keymap, errors) = keymap("back = \"q\"\nconfirm = [\"y\", \"C-y\"]");
+    for window in keymap::WINDOWS.iter().filter(|window| window.typing) {
+        assert_eq!(keymap.keys(window.name, Action::Back), vec![Key::parse("Esc").unwrap()], "{}", window.name);
+        assert!(errors.contains(&("keys.back".to_string(), format!("q is typed in {}", window.name))));
+        // Keys of the config that aren't typed are still used
+        assert_eq!(keymap.action(window.name, ctrl('y')), Some(Action::Confirm), "{}", window.name);
+        assert_eq!(keymap.action(window.name, key(KeyCode::Enter)), None);
+    }
+}
+
+#[test]
+fn conflicts_and_errors() {
+    // The binding of the config wins over the default one
+    let (keymap, errors) = keymap(
+        r#"
+        up = "c"
+        jump = "g"
+        back = ["q", "Esc"]
+        mark = 3
+
+        [main]
+        mark = "m"
+
+        [typo]
+        delete = "x"
+        "#,
+    );
+    assert_eq!(keymap.action("main", key(KeyCode::Char('c'))), Some(Action::Up));
+    assert!(keymap.keys("main", Action::Settings).is_empty());
+    assert_eq!(keymap.key("main", Action::Settings), "-");
+    // Bindings with errors keep their default keys
+    assert_eq!(keymap.action("card_list", key(KeyCode::Char(' '))), Some(Action::Mark));
+    // Typed keys are left out where text is typed
+    assert_eq!(keymap.action("add_card", key(KeyCode::Char('q'))), None);
+    assert_eq!(keymap.action("add_card", key(KeyCode::Esc)), Some(Action::Back));
+    // but printable defaults are kept for the options around the text
+    assert_eq!(keymap.action("find_replace", key(KeyCode::Char(' '))), Some(Action::Toggle));
+
+    let errors: Vec<String> = errors.into_iter().map(|(key, reason)| format!("{}: {}", key, reason)).collect();
+    for error in [
+        "keys.up: c is also bound to settings in main",
+        "keys.jump: unknown action",
+        "keys.mark: must be a key or a list of keys, got 3",
+        "keys.main.mark: main has no mark action",
+        "keys.typo: unknown window",
+        "keys.back: q is also bound to quit in side",
+        "keys.back: q is typed in add_card",
+        "keys.back: q is typed in stack_name_input",
+    ] {
+        assert!(errors.contains(&error.to_string()), "{} not in {:?}", error, errors);
+    }
+}
+
+#[test]
+fn windows_use_the_keymap() {
+    let mut store = MemoryStore::new();
+    store.add_stack("Rust".to_string());
+    store.add_stack("Spanish".to_string());
+    let config = Config {
+        keys: "down = \"C-n\"\nadd = \"C-a\"\nsettings = \"C-c\"".parse().unwrap(),
+        ..Config::default()
+    };
+    let mut app = App::with_config(Box::new(store), &config);
+    app.get_items();
+    app.state.select(Some(0));
+
+    cards::ui::main::handle_events(ctrl('n'), &mut app);
+    assert_eq!(app.state.selected(), Some(1));
+    cards::ui::main::handle_events(key(KeyCode::Char('j')), &mut app);
+    assert_eq!(app.state.selected(), Some(1));
+
+    // Keys bound elsewhere are typed in the stack name
+    cards::ui::main::handle_events(ctrl('a'), &mut app);
+    assert!(matches!(app.selected_window, Selected::StackNameInput));
+    for c in "cards".chars() {
+        cards::ui::stack_name_input::handle_events(key(KeyCode::Char(c)), &mut app);
+    }
+    assert_eq!(app.stack_name_input, "cards");
+    cards::ui::stack_name_input::handle_events(key(KeyCode::Esc), &mut app);
+    assert!(matches!(app.selected_window, Selected::Main));
+}
+
+#[test]
+fn bound_keys_are_typed_in_the_pasted_text() {
+    let config = Config {
+        keys: "back = [\"q\", \"Esc\"]".parse().unwrap(),
+        ..Config::default()
+    };
+    let mut app = App::with_config(Box::new(MemoryStore::new()), &config);
+    app.selected_window = Selected::PasteImport;
+    for c in "quit".chars() {
+        cards::ui::paste_import::handle_events(key(KeyCode::Char(c)), &mut app);
+    }
+    assert_eq!(app.paste_text, "quit");
+    cards::ui::paste_import::handle_events(key(KeyCode::Esc), &mut app);
+    assert!(matches!(app.paste_focus, PasteFocus::Term));
+}
@@ -0,0 +1,123 @@
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::export::{self, markdown};
+use cards::import::{self, markdown as parse};
+
+// Export every stack of a store as Markdown
+fn export_all(store: &dyn Store) -> String {
+    markdown::to_string(&export::collect(store, None, false))
+}
+
+// Import Markdown into an empty store
+fn import_all(content: &str) -> MemoryStore {
+    let mut store = MemoryStore::new();
+    let decks = parse::parse(content, "Default");
+    import::import_decks(&mut store, decks, false);
+    store
+}
+
+fn sample() -> MemoryStore {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust".to_string());
+    store.add_card(rust, "Borrow checker".to_string(), "Checks that references are valid.".to_string());
+    store.add_card(
+        rust,
+        "Headings in answers".to_string(),
+        "# Not a stack\n## Not a card\n\\# Escaped already\n\nSecond paragraph".to_string(),
+    );
+    store.add_card(
+        rust,
+        "Code blocks".to_string(),
+        "```rust\n## stays as is\nfn main() {}\n```\n\n- a list\n- of items".to_string(),
+    );
+    let spanish = store.add_stack("Spanish::Verbs".to_string());
+    store.add_card(spanish, "hablar".to_string(), "to speak".to_string());
+    let mut cards = store.cards(spanish);
+    cards[0].add_tag("verb");
+    cards[0].add_tag("ar");
+    store.update_cards(&cards);
+    store
+}
+
+#[test]
+fn export_then_import_keeps_cards() {
+    let store = sample();
+    let imported = import_all(&export_all(&store));
+
+    let stacks = store.stacks();
+    let imported_stacks = imported.stacks();
+    assert_eq!(
+        stacks.iter().map(|stack| &stack.name).collect::<Vec<_>>(),
+        imported_stacks.iter().map(|stack| &stack.name).collect::<Vec<_>>()
+    );
+    for (stack, imported_stack) in stacks.iter().zip(&imported_stacks) {
+        let cards = store.cards(stack.id);
+        let imported_cards = imported.cards(imported_stack.id);
+        assert_eq!(cards.len(), imported_cards.len());
+        for (card, imported_card) in cards.iter().zip(&imported_cards) {
+            assert_eq!(card.title, imported_card.title);
+            assert_eq!(card.text, imported_card.text);
+            assert_eq!(card.tag_list(), imported_card.tag_list());
+        }
+    }
+}
+
+#[test]
+fn export_is_stable() {
+    let exported = export_all(&sample());
+    let again = export_all(&import_all(&exported));
+    assert_eq!(exported, again);
+}
+
+#[test]
+fn hand_written_file_is_stable_after_one_export() {
+    let content = "Notes before the first question are left out.\n\
+                   ## What is 2 + 2?\n\
+                   4\n\
+                   ##   Spaced   title  \n\
+                   <!-- tags: math  easy -->\n\
+                   \n\
+                   \n\
+                   answer with trailing space   \n\
+                   \n\
+                   \n";
+    let exported = export_all(&import_all(content));
+    assert_eq!(
+        exported,
+        "# Default\n\n## What is 2 + 2?\n\n4\n\n## Spaced title\n<!-- tags: math easy -->\n\nanswer with trailing space   \n"
+    );
+    assert_eq!(exported, export_all(&import_all(&exported)));
+}
+
+#[test]
+fn stack_name_comes_from_heading_or_file_name() {
+    let decks = parse::parse("# Capitals\n\n## France\n\nParis\n", "capitals");
+    assert_eq!(decks.len(), 1);
+    assert_eq!(decks[0].name, "Capitals");
+    assert_eq!(parse::stack_name("decks/capitals.md"), "capitals");
+    assert_eq!(parse::parse("## France\n\nParis\n", "capitals")[0].name, "capitals");
+}
+
+#[test]
+fn unclosed_code_blocks_end_with_their_card() {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust".to_string());
+    store.add_card(rust, "Open fence".to_string(), "```rust\nfn main() {}".to_string());
+    store.add_card(rust, "Next".to_string(), "Still a card".to_string());
+
+    // The export closes the code block
+    let exported = export_all(&store);
+    assert!(exported.contains("fn main() {}\n```\n\n## Next\n"));
+    let imported = import_all(&exported);
+    let cards = imported.cards(imported.stacks()[0].id);
+    let titles: Vec<&str> = cards.iter().map(|card| card.title.as_str()).collect();
+    assert_eq!(titles, vec!["Open fence", "Next"]);
+    assert_eq!(cards[0].text, "```rust\nfn main() {}\n```");
+    assert_eq!(cards[1].text, "Still a card");
+
+    // Hand-written files get the next card too
+    let imported = import_all("# Rust\n\n## Open fence\n\n```rust\nfn main() {}\n\n## Next\n\nStill a card\n");
+    let cards = imported.cards(imported.stacks()[0].id);
+    assert_eq!(cards.len(), 2);
+    assert_eq!(cards[1].text, "Still a card");
+}
@@ -0,0 +1,132 @@
+use cards::db::card::Schedule;
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::export::{self, org};
+use cards::import::{self, org as parse};
+use cards::scheduler;
+
+const DRILL_FILE: &str = "#+TITLE: Languages
+#+STARTUP: overview
+
+Notes before any heading.
+
+* Spanish
+** Verbs
+*** hablar                                                    :drill:verb:
+SCHEDULED: <2022-12-05 Mon>
+:PROPERTIES:
+:ID:       8b1c6a3e
+:DRILL_LAST_INTERVAL: 4.0
+:DRILL_REPEATS_SINCE_FAIL: 2
+:DRILL_TOTAL_REPEATS: 5
+:DRILL_FAILURE_COUNT: 1
+:DRILL_AVERAGE_QUALITY: 3.4
+:DRILL_EASE: 2.36
+:DRILL_LAST_QUALITY: 5
+:DRILL_LAST_REVIEWED: [2022-12-01 Thu 10:00]
+:END:
+
+    Translate the verb.
+
+**** Answer
+    to speak
+
+**** Example
+    Hablo español.
+** Not a card
+   Just notes.
+* Capitals
+** France :drill:
+*** Answer
+Paris
+";
+
+fn titles(store: &dyn Store) -> Vec<String> {
+    store
+        .stacks()
+        .iter()
+        .flat_map(|stack| {
+            store
+                .cards(stack.id)
+                .into_iter()
+                .map(move |card| format!("{} / {} / {} / {}", stack.name, card.title, card.text, card.tags))
+        })
+        .collect()
+}
+
+#[test]
+fn drill_headings_become_cards() {
+    let decks = parse::parse(DRILL_FILE, "languages");
+    let names: Vec<&str> = decks.iter().map(|deck| deck.name.as_str()).collect();
+    assert_eq!(names, vec!["Spanish::Verbs", "Capitals"]);
+
+    let (_, hablar) = &decks[0].cards[0];
+    assert_eq!(hablar.title, "hablar");
+    assert_eq!(hablar.text, "Translate the verb.\n\nto speak\n\nExample\nHablo español.");
+    assert_eq!(hablar.tags, vec!["verb".to_string()]);
+    assert_eq!(hablar.schedule.interval, 4);
+    assert_eq!(hablar.schedule.ease, 2360);
+    assert_eq!(hablar.schedule.reps, 5);
+    assert_eq!(hablar.schedule.lapses, 1);
+    assert_eq!(hablar.schedule.due, parse::timestamp("<2022-12-05 Mon>").unwrap());
+    assert_eq!(hablar.reviews.len(), 1);
+    assert_eq!(hablar.reviews[0].0, scheduler::EASY);
+    assert_eq!(hablar.reviews[0].1, parse::timestamp("[2022-12-01 Thu 10:00]").unwrap());
+
+    let (_, france) = &decks[1].cards[0];
+    assert_eq!(france.text, "Paris");
+    assert_eq!(france.schedule.reps, 0);
+}
+
+#[test]
+fn timestamps_with_other_characters() {
+    let midnight = parse::timestamp("<2024-01-01 Mon>").unwrap();
+    assert_eq!(parse::timestamp("<2024-01-01 ä>"), Some(midnight));
+    assert_eq!(parse::timestamp("<2024-01-01 äää:00>"), Some(midnight));
+    assert_eq!(parse::timestamp("[2024-01-01 Mo. 09:30]"), Some(midnight + 9 * 60 * 60 + 30 * 60));
+    assert_eq!(parse::timestamp("<2024-01-0ä>"), None);
+    assert_eq!(parse::timestamp("<ä"), None);
+}
+
+#[test]
+fn cards_without_stack_heading_use_the_file_name() {
+    let decks = parse::parse("* Question :drill:\nAnswer\n", "notes");
+    assert_eq!(decks.len(), 1);
+    assert_eq!(decks[0].name, "notes");
+    assert_eq!(decks[0].cards[0].1.text, "Answer");
+}
+
+#[test]
+fn round_trip_keeps_cards_and_schedule() {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust::Basics".to_string());
+    store.add_card(rust, "Box".to_string(), "Heap pointer\n* not a heading\n,* escaped".to_string());
+    store.add_card(rust, "Rc".to_string(), "Shared ownership".to_string());
+    let mut cards = store.cards(rust);
+    cards[0].add_tag("smart-pointer");
+    store.update_cards(&cards);
+    let reviewed = scheduler::review(&Schedule::default(), scheduler::GOOD, 1_700_000_000);
+    store.set_schedule(cards[1].id, &reviewed);
+
+    let content = org::to_string(&export::collect(&store, None, false));
+    let mut imported = MemoryStore::new();
+    import::import_decks(&mut imported, parse::parse(&content, "Default"), true);
+    assert_eq!(
+        titles(&imported),
+        vec![
+            "Rust::Basics / Box / Heap pointer\n* not a heading\n,* escaped / smart_pointer".to_string(),
+            "Rust::Basics / Rc / Shared ownership / ".to_string(),
+        ]
+    );
+
+    let stack = imported.stacks()[0].id;
+    let schedule = imported.cards(stack)[1].schedule.clone();
+    assert_eq!(schedule.interval, reviewed.interval);
+    assert_eq!(schedule.ease, reviewed.ease);
+    assert_eq!(schedule.reps, reviewed.reps);
+    // Org dates have no time of day
+    assert!((schedule.due - reviewed.due).abs() < 24 * 60 * 60);
+
+    // Exporting again gives the same file
+    assert_eq!(org::to_string(&export::collect(&imported, None, false)), content);
+}
@@ -0,0 +1,66 @@
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::import::paste;
+use cards::import::Target;
+
+fn pairs(content: &str, term: &str, cards: &str) -> Vec<(String, String)> {
+    paste::parse(content, term, cards)
+        .0
+        .into_iter()
+        .map(|(_, card)| (card.title, card.text))
+        .collect()
+}
+
+#[test]
+fn quizlet_defaults() {
+    let content = "hola\thello\r\nperro\tdog\n\ngato\tcat\n";
+    assert_eq!(
+        pairs(content, "\\t", "\\n"),
+        vec![
+            ("hola".to_string(), "hello".to_string()),
+            ("perro".to_string(), "dog".to_string()),
+            ("gato".to_string(), "cat".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn custom_separators() {
+    assert_eq!(
+        pairs("a - one;b - two, too;", " - ", ";"),
+        vec![("a".to_string(), "one".to_string()), ("b".to_string(), "two, too".to_string())]
+    );
+
+    // Definitions over several lines with blank lines between cards
+    let content = "Borrow checker\nChecks references\nat compile time\n\nBox\nHeap pointer\n";
+    assert_eq!(
+        pairs(content, "\\n", "\\n\\n"),
+        vec![
+            (
+                "Borrow checker".to_string(),
+                "Checks references\nat compile time".to_string()
+            ),
+            ("Box".to_string(), "Heap pointer".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn rows_without_separator_are_skipped() {
+    let (cards, skipped) = paste::parse("a,one\nno separator\nb,two", ",", "\\n");
+    assert_eq!(cards.len(), 2);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].row, 2);
+}
+
+#[test]
+fn import_skips_duplicates() {
+    let mut store = MemoryStore::new();
+    let stack = store.add_stack("Spanish".to_string());
+    store.add_card(stack, "hola".to_string(), "hello".to_string());
+
+    let report = paste::import(&mut store, &Target::Stack(stack), "hola\thello\nperro\tdog", "\\t", "\\n");
+    assert_eq!(report.added, 1);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(store.cards(stack).len(), 2);
+}
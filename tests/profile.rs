@@ -0,0 +1,138 @@
+mod common;
+
+use cards::cli;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Empty home directory for a test
+fn test_home(name: &str) -> PathBuf {
+    let home = common::test_dir("profile", name);
+    fs::create_dir_all(home.join(".config")).unwrap();
+    home
+}
+
+// Output of the binary run with a home directory and environment variables
+fn cards(home: &Path, vars: &[(&str, &str)], args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_cards"))
+        .args(args)
+        .env("HOME", home)
+        .env_remove("CARDS_DB")
+        .env_remove("CARDS_CONFIG")
+        .env_remove("CARDS_PROFILE")
+        .envs(vars.iter().copied())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn add(home: &Path, vars: &[(&str, &str)], options: &[&str], stack: &str) {
+    let mut args = options.to_vec();
+    args.extend(["add", "--stack", stack, "--front", "front", "--back", "back"]);
+    cards(home, vars, &args);
+}
+
+#[test]
+fn global_options() {
+    let args: Vec<String> = ["--profile", "work", "--db=cards.db", "stacks", "--format", "json"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    let (vars, rest) = cli::global_options(&args).unwrap();
+    assert_eq!(vars[0], ("CARDS_PROFILE", "work".to_string()));
+    assert_eq!(vars[1].0, "CARDS_DB");
+    assert!(Path::new(&vars[1].1).is_absolute());
+    assert_eq!(rest, &args[3..]);
+
+    assert_eq!(cli::global_options(&["--db".to_string()]).err().unwrap(), "Missing value for --db");
+    assert!(cli::global_options(&["--profile=../x".to_string()]).is_err());
+}
+
+#[test]
+fn profiles_keep_separate_collections() {
+    let home = test_home("switch");
+    add(&home, &[], &[], "Personal");
+    assert_eq!(cards(&home, &[], &["profiles"]), "* default\n");
+
+    cards(&home, &[], &["profile", "work"]);
+    add(&home, &[], &[], "Work");
+    assert_eq!(cards(&home, &[], &["profiles"]), "  default\n* work\n");
+    assert!(home.join(".local/share/cards/profiles/work/cards.db").exists());
+    let stacks = cards(&home, &[], &["stacks"]);
+    assert!(stacks.contains("Work") && !stacks.contains("Personal"));
+
+    // Picked for one run with the option or the environment variable
+    let stacks = cards(&home, &[], &["--profile", "default", "stacks"]);
+    assert!(stacks.contains("Personal") && !stacks.contains("Work"));
+    let stacks = cards(&home, &[("CARDS_PROFILE", "default")], &["stacks"]);
+    assert!(stacks.contains("Personal") && !stacks.contains("Work"));
+
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn database_and_config_overrides() {
+    let home = test_home("overrides");
+    let db = home.join("other.db");
+    let db = db.to_str().unwrap();
+    add(&home, &[], &["--db", db], "Other");
+    add(&home, &[("CARDS_DB", db)], &[], "Again");
+    let stacks = cards(&home, &[], &["--db", db, "stacks"]);
+    assert!(stacks.contains("Other") && stacks.contains("Again"));
+    assert!(!cards(&home, &[], &["stacks"]).contains("Other"));
+
+    // A config file of its own, created when missing
+    let config = home.join("elsewhere/config.toml");
+    cards(&home, &[("CARDS_CONFIG", config.to_str().unwrap())], &["stacks"]);
+    assert!(config.exists());
+
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn xdg_directories_and_json_migration() {
+    let home = test_home("xdg");
+    fs::create_dir_all(home.join(".config/cards")).unwrap();
+    fs::write(
+        home.join(".config/cards/config.json"),
+        "{\"db_file\": \".config/cards/cards.db\", \"highlight_color\": 5}",
+    )
+    .unwrap();
+    cards(&home, &[], &["stacks"]);
+    let config = fs::read_to_string(home.join(".config/cards/config.toml")).unwrap();
+    assert!(config.contains("db_file = \".config/cards/cards.db\"\n"));
+    assert!(config.contains("highlight_color = 5\n"));
+    assert!(!home.join(".config/cards/config.json").exists());
+
+    let data = home.join("data");
+    let state = home.join("state");
+    let vars = [("XDG_DATA_HOME", data.to_str().unwrap()), ("XDG_STATE_HOME", state.to_str().unwrap())];
+    add(&home, &vars, &["--profile", "work"], "Work");
+    assert!(data.join("cards/profiles/work/cards.db").exists());
+    assert!(home.join(".config/cards/profiles/work/config.toml").exists());
+
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn without_home() {
+    let tmp = test_home("no-home");
+    let output = Command::new(env!("CARGO_BIN_EXE_cards"))
+        .args(["add", "--stack", "Rust", "--front", "Box", "--back", "Heap pointer"])
+        .env_remove("HOME")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env_remove("CARDS_DB")
+        .env_remove("CARDS_CONFIG")
+        .env_remove("CARDS_PROFILE")
+        .env("TMPDIR", &tmp)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("HOME is not set"));
+    assert!(tmp.join("cards/.local/share/cards/cards.db").exists());
+
+    fs::remove_dir_all(&tmp).unwrap();
+}
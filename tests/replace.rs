@@ -0,0 +1,84 @@
+use cards::db::card::Card;
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::replace::{self, Replace, Scope};
+
+// Store with a Rust and a Spanish stack, the Box card is tagged
+fn store() -> (MemoryStore, i32, i32) {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust".to_string());
+    let spanish = store.add_stack("Spanish".to_string());
+    store.add_card(rust, "Box<T>".to_string(), "Heap pointer to T".to_string());
+    store.add_card(rust, "Rc<T>".to_string(), "Counted pointer to T".to_string());
+    store.add_card(spanish, "perro".to_string(), "dog, the T animal".to_string());
+    let mut cards = store.cards(rust);
+    cards[0].tags = "pointer".to_string();
+    store.update_cards(&cards[..1]);
+    (store, rust, spanish)
+}
+
+fn query(find: &str, replace: &str, regex: bool, scope: Scope) -> Replace {
+    Replace {
+        find: find.to_string(),
+        replace: replace.to_string(),
+        regex,
+        scope,
+    }
+}
+
+fn all_cards(store: &MemoryStore) -> Vec<Card> {
+    store.stacks().iter().flat_map(|stack| store.cards(stack.id)).collect()
+}
+
+#[test]
+fn preview_changes_nothing() {
+    let (store, rust, _) = store();
+    let changes = replace::preview(&store, &query("T", "U", false, Scope::Stack(rust))).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].old_title, "Box<T>");
+    assert_eq!(changes[0].card.title, "Box<U>");
+    assert_eq!(changes[0].card.text, "Heap pointer to U");
+    assert_eq!(store.cards(rust)[0].title, "Box<T>");
+
+    // Nothing to find, nothing to change
+    assert!(replace::preview(&store, &query("", "U", false, Scope::All)).unwrap().is_empty());
+    assert!(replace::preview(&store, &query("Vec", "U", false, Scope::All)).unwrap().is_empty());
+}
+
+#[test]
+fn apply_in_scope() {
+    let (mut store, rust, spanish) = store();
+    let changes = replace::preview(&store, &query("T", "U", false, Scope::Stack(rust))).unwrap();
+    replace::apply(&mut store, &changes);
+    let titles: Vec<String> = store.cards(rust).into_iter().map(|card| card.title).collect();
+    assert_eq!(titles, vec!["Box<U>", "Rc<U>"]);
+    assert_eq!(store.cards(spanish)[0].text, "dog, the T animal");
+
+    let changes = replace::preview(&store, &query("pointer", "ref", false, Scope::Tag("pointer".to_string()))).unwrap();
+    replace::apply(&mut store, &changes);
+    let texts: Vec<String> = store.cards(rust).into_iter().map(|card| card.text).collect();
+    assert_eq!(texts, vec!["Heap ref to U", "Counted pointer to U"]);
+
+    let changes = replace::preview(&store, &query("T", "V", false, Scope::All)).unwrap();
+    replace::apply(&mut store, &changes);
+    assert_eq!(store.cards(spanish)[0].text, "dog, the V animal");
+}
+
+#[test]
+fn regex_with_capture_groups() {
+    let (mut store, rust, spanish) = store();
+    let changes = replace::preview(&store, &query(r"(\w+)<(\w)>", "$2 in $1", true, Scope::Stack(rust))).unwrap();
+    replace::apply(&mut store, &changes);
+    let titles: Vec<String> = store.cards(rust).into_iter().map(|card| card.title).collect();
+    assert_eq!(titles, vec!["T in Box", "T in Rc"]);
+
+    let changes = replace::preview(&store, &query(r"^(\w+), the", "${1}s, all", true, Scope::All)).unwrap();
+    assert_eq!(changes.len(), 1);
+    replace::apply(&mut store, &changes);
+    assert_eq!(store.cards(spanish)[0].text, "dogs, all T animal");
+
+    // Patterns are only regexes when asked for
+    assert!(replace::preview(&store, &query(r"(\w+", "", true, Scope::All)).is_err());
+    assert!(replace::preview(&store, &query(r"(\w+", "", false, Scope::All)).unwrap().is_empty());
+    assert_eq!(all_cards(&store).len(), 3);
+}
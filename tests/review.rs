@@ -0,0 +1,105 @@
+mod common;
+
+use cards::db::card::Card;
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::review;
+use cards::scheduler;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Store with a stack of two new cards
+fn store() -> (MemoryStore, Vec<(String, Card)>) {
+    let mut store = MemoryStore::new();
+    let spanish = store.add_stack("Spanish".to_string());
+    store.add_card(spanish, "hola".to_string(), "hello".to_string());
+    store.add_card(spanish, "adiós".to_string(), "goodbye".to_string());
+    let cards = store.cards(spanish).into_iter().map(|card| ("Spanish".to_string(), card)).collect();
+    (store, cards)
+}
+
+// Output of a session driven by the given input
+fn session(store: &mut MemoryStore, cards: &[(String, Card)], input: &str) -> (review::Summary, String) {
+    let mut out = vec![];
+    let summary = review::session(store, cards, &mut input.as_bytes(), &mut out).unwrap();
+    (summary, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn grades_each_card() {
+    let (mut store, cards) = store();
+    let (summary, out) = session(&mut store, &cards, "\n1\n\n\n");
+
+    assert_eq!(summary.grades, [1, 0, 1, 0]);
+    assert!(!summary.quit);
+    assert!(out.contains("Card 1 of 2 (Spanish)\nQ: hola\n"));
+    assert!(out.contains("A: hello\n"));
+    assert!(out.contains("A: goodbye\n"));
+    assert!(out.ends_with("Reviewed 2 cards: 1 again, 0 hard, 1 good, 0 easy\n"));
+
+    let reviews = store.reviews(cards[0].1.id);
+    assert_eq!(reviews.len(), 1);
+    assert_eq!(reviews[0].grade, scheduler::AGAIN);
+    let card = store.cards(cards[1].1.stack_id).into_iter().find(|card| card.id == cards[1].1.id).unwrap();
+    assert_eq!(card.schedule.reps, 1);
+}
+
+#[test]
+fn unknown_grades_are_asked_again() {
+    let (mut store, cards) = store();
+    let (summary, out) = session(&mut store, &cards, "\nmaybe\neasy\nq\n");
+
+    assert!(out.contains("Unknown grade \"maybe\""));
+    assert_eq!(summary.grades, [0, 0, 0, 1]);
+    assert!(summary.quit);
+    assert!(store.reviews(cards[1].1.id).is_empty());
+}
+
+#[test]
+fn end_of_input_keeps_grades() {
+    let (mut store, cards) = store();
+    let (summary, out) = session(&mut store, &cards, "\ns\n\n");
+
+    assert_eq!(summary.skipped, 1);
+    assert_eq!(summary.reviewed(), 0);
+    assert!(summary.quit);
+    assert!(out.ends_with("Reviewed 0 cards: 0 again, 0 hard, 0 good, 0 easy\n"));
+    assert!(store.reviews(cards[0].1.id).is_empty());
+}
+
+// Run the binary with a home directory of its own, like an expect script would
+#[test]
+fn review_command_over_pipes() {
+    let home = common::test_dir("review", "pipes");
+    fs::create_dir_all(home.join(".config")).unwrap();
+    let cards = |args: &[&str], input: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cards"))
+            .args(args)
+            .env("HOME", &home)
+            .env_remove("CARDS_DB")
+            .env_remove("CARDS_CONFIG")
+            .env_remove("CARDS_PROFILE")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    cards(&["add", "--stack", "Spanish", "--front", "hola", "--back", "hello"], "");
+    let out = cards(&["review", "Spanish"], "\n3\n");
+    assert!(out.contains("Q: hola\n"));
+    assert!(out.contains("A: hello\n"));
+    assert!(out.contains("Next review in 1 day"));
+    assert!(out.contains("Reviewed 1 cards"));
+
+    // Nothing is due after a good grade, unless all cards are asked for
+    assert_eq!(cards(&["review", "Spanish"], ""), "No cards due in Spanish\n");
+    assert!(cards(&["review", "Spanish", "--all"], "q\n").contains("Q: hola\n"));
+
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,307 @@
+use cards::config::Config;
+use cards::db::memory::MemoryStore;
+use cards::db::sqlite::SqliteStore;
+use cards::db::Store;
+use cards::state::{App, CardSort, Selected};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// App over an empty store with the default settings
+fn app() -> App {
+    App::with_config(Box::new(MemoryStore::new()), &Config::default())
+}
+
+fn names(app: &App) -> Vec<&str> {
+    app.items.iter().map(|stack| stack.name.as_str()).collect()
+}
+
+fn titles(app: &App) -> Vec<&str> {
+    app.cards.iter().map(|card| card.title.as_str()).collect()
+}
+
+#[test]
+fn stacks() {
+    let mut app = app();
+    app.add_stack("Rust".to_string());
+    app.add_stack("Spanish".to_string());
+    app.get_items();
+    assert_eq!(names(&app), vec!["Rust", "Spanish"]);
+
+    app.state.select(Some(1));
+    assert_eq!(app.get_selected_name(), "Spanish");
+    app.stack_name_input = "German".to_string();
+    app.edit_stack();
+    app.get_items();
+    assert_eq!(names(&app), vec!["Rust", "German"]);
+
+    let id = app.get_selected_id();
+    app.delete_stack(id);
+    app.get_items();
+    assert_eq!(names(&app), vec!["Rust"]);
+}
+
+#[test]
+fn cards() {
+    let mut app = app();
+    app.add_stack("Rust".to_string());
+    app.add_stack("Spanish".to_string());
+    app.get_items();
+    app.state.select(Some(0));
+    app.add_card("Box".to_string(), "Heap pointer".to_string());
+    app.add_card("Arc".to_string(), "Shared pointer".to_string());
+    app.state.select(Some(1));
+    app.add_card("perro".to_string(), "dog".to_string());
+
+    // Only the cards of the selected stack are listed
+    app.state.select(Some(0));
+    app.list_cards();
+    assert_eq!(titles(&app), vec!["Box", "Arc"]);
+
+    app.cards_state.select(Some(1));
+    app.card_title_input = "Rc".to_string();
+    app.card_text_input = "Counted pointer".to_string();
+    app.edit_card();
+    app.list_cards();
+    assert_eq!(titles(&app), vec!["Box", "Rc"]);
+    assert_eq!(app.cards[1].text, "Counted pointer");
+
+    app.cards_state.select(Some(0));
+    app.delete_card();
+    app.list_cards();
+    assert_eq!(titles(&app), vec!["Rc"]);
+
+    app.state.select(Some(1));
+    app.list_cards();
+    assert_eq!(titles(&app), vec!["perro"]);
+}
+
+#[test]
+fn filter_and_sort_cards() {
+    let mut app = app();
+    app.add_stack("Rust".to_string());
+    app.get_items();
+    app.state.select(Some(0));
+    app.add_card("Vec".to_string(), "Growable array".to_string());
+    app.add_card("Box".to_string(), "Heap pointer".to_string());
+    app.add_card("Arc".to_string(), "Shared pointer".to_string());
+
+    app.card_sort = CardSort::Title;
+    app.list_cards();
+    assert_eq!(titles(&app), vec!["Arc", "Box", "Vec"]);
+
+    app.card_filter = "POINTER".to_string();
+    app.list_cards();
+    assert_eq!(titles(&app), vec!["Arc", "Box"]);
+
+    // The selected card stays selected when the order changes
+    app.cards_state.select(Some(1));
+    app.card_sort = CardSort::Default;
+    app.refresh_cards();
+    assert_eq!(titles(&app), vec!["Box", "Arc"]);
+    assert_eq!(app.cards_state.selected(), Some(0));
+}
+
+#[test]
+fn study_ignores_the_card_list_filter() {
+    let mut app = app();
+    app.add_stack("Rust".to_string());
+    app.get_items();
+    app.state.select(Some(0));
+    app.add_card("Vec".to_string(), "Growable array".to_string());
+    app.add_card("Box".to_string(), "Heap pointer".to_string());
+    app.card_filter = "vec".to_string();
+    app.card_sort = CardSort::Title;
+    app.list_cards();
+    assert_eq!(titles(&app), vec!["Vec"]);
+
+    let study = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+    cards::ui::side::handle_events(study, &mut app);
+    assert!(matches!(app.selected_window, Selected::RevisionTitle));
+    assert_eq!(titles(&app), vec!["Vec", "Box"]);
+}
+
+// App with a Rust stack of five cards, all listed and the first selected
+fn app_with_cards() -> App {
+    let mut app = app();
+    app.add_stack("Rust".to_string());
+    app.add_stack("Spanish".to_string());
+    app.get_items();
+    app.state.select(Some(0));
+    for title in ["Vec", "Box", "Arc", "Rc", "Cell"] {
+        app.add_card(title.to_string(), format!("{} pointer", title));
+    }
+    app.list_cards();
+    app.cards_state.select(Some(0));
+    app
+}
+
+fn marked(app: &App) -> Vec<&str> {
+    let mut titles: Vec<&str> = app
+        .cards
+        .iter()
+        .filter(|card| app.marked_cards.contains(&card.id))
+        .map(|card| card.title.as_str())
+        .collect();
+    titles.sort();
+    titles
+}
+
+fn card_list(app: &mut App, code: KeyCode) {
+    cards::ui::card_list::handle_events(KeyEvent::new(code, KeyModifiers::NONE), app);
+}
+
+#[test]
+fn mark_cards() {
+    let mut app = app_with_cards();
+    app.toggle_mark();
+    app.cards_state.select(Some(2));
+    app.toggle_mark();
+    assert_eq!(marked(&app), vec!["Arc", "Vec"]);
+    app.toggle_mark();
+    assert_eq!(marked(&app), vec!["Vec"]);
+
+    // A visual range adds to the marks, and follows the selection back
+    app.cards_state.select(Some(1));
+    app.toggle_visual_mark();
+    app.next_card();
+    app.next_card();
+    app.update_visual_mark();
+    assert_eq!(marked(&app), vec!["Arc", "Box", "Rc", "Vec"]);
+    app.back_card();
+    app.update_visual_mark();
+    assert_eq!(marked(&app), vec!["Arc", "Box", "Vec"]);
+    app.toggle_visual_mark();
+    assert_eq!(app.visual_anchor, None);
+
+    app.toggle_mark_all();
+    assert_eq!(marked(&app).len(), 5);
+    app.toggle_mark_all();
+    assert!(app.marked_cards.is_empty());
+}
+
+#[test]
+fn filtering_ends_the_visual_range() {
+    let mut app = app_with_cards();
+    app.cards_state.select(Some(4));
+    card_list(&mut app, KeyCode::Char('V'));
+    assert_eq!(app.visual_anchor, Some(4));
+
+    card_list(&mut app, KeyCode::Char('/'));
+    for c in "vec".chars() {
+        card_list(&mut app, KeyCode::Char(c));
+    }
+    card_list(&mut app, KeyCode::Esc);
+    assert_eq!(titles(&app), vec!["Vec"]);
+    assert_eq!(app.visual_anchor, None);
+    card_list(&mut app, KeyCode::Char('j'));
+    assert_eq!(marked(&app), Vec::<&str>::new());
+}
+
+#[test]
+fn bulk_actions() {
+    let mut app = app_with_cards();
+    let rust = app.get_selected_id();
+    let spanish = app.items[1].id;
+
+    // Without marks the selected card is used
+    assert_eq!(app.bulk_cards().len(), 1);
+    app.bulk_tag("ptr");
+    assert_eq!(app.cards[0].tags, "ptr");
+
+    app.cards_state.select(Some(1));
+    app.toggle_mark();
+    app.cards_state.select(Some(2));
+    app.toggle_mark();
+    app.bulk_tag("smart -ptr");
+    app.bulk_toggle_suspend();
+    assert_eq!(app.message, "Suspended 2 cards");
+    let tags: Vec<&str> = app.cards.iter().map(|card| card.tags.as_str()).collect();
+    assert_eq!(tags, vec!["ptr", "smart", "smart", "", ""]);
+    let suspended: Vec<bool> = app.cards.iter().map(|card| card.suspended).collect();
+    assert_eq!(suspended, vec![false, true, true, false, false]);
+    app.bulk_toggle_suspend();
+    assert_eq!(app.message, "Unsuspended 2 cards");
+
+    let mut cards = app.db.cards(rust);
+    cards[1].schedule.reps = 3;
+    cards[3].schedule.reps = 3;
+    app.db.update_cards(&cards);
+    app.refresh_cards();
+    app.bulk_reset();
+    let reps: Vec<i32> = app.cards.iter().map(|card| card.schedule.reps).collect();
+    assert_eq!(reps, vec![0, 0, 0, 3, 0]);
+
+    app.bulk_move(spanish);
+    assert!(app.marked_cards.is_empty());
+    assert_eq!(titles(&app), vec!["Vec", "Rc", "Cell"]);
+    let moved: Vec<String> = app.db.cards(spanish).into_iter().map(|card| card.title).collect();
+    assert_eq!(moved, vec!["Box", "Arc"]);
+
+    app.toggle_mark_all();
+    app.bulk_delete();
+    assert_eq!(app.message, "Deleted 3 cards");
+    assert!(app.cards.is_empty());
+    assert_eq!(app.db.cards(spanish).len(), 2);
+}
+
+#[test]
+fn bulk_edits_bump_updated_at_of_the_marked_cards() {
+    let mut store = SqliteStore::open(":memory:").unwrap();
+    let rust = store.add_stack("Rust".to_string());
+    let spanish = store.add_stack("Spanish".to_string());
+    for title in ["Vec", "Box", "Arc", "Rc"] {
+        store.add_card(rust, title.to_string(), String::new());
+    }
+    store.conn.execute_batch("UPDATE card SET updated_at = 0").unwrap();
+    let mut app = App::with_config(Box::new(store), &Config::default());
+    app.get_items();
+    app.state.select(Some(0));
+    app.list_cards();
+    let updated = |app: &App| -> Vec<bool> { app.cards.iter().map(|card| card.updated_at > 0).collect() };
+
+    app.cards_state.select(Some(1));
+    app.toggle_mark();
+    app.bulk_tag("smart");
+    assert_eq!(updated(&app), vec![false, true, false, false]);
+    app.cards_state.select(Some(2));
+    app.toggle_mark();
+    app.bulk_toggle_suspend();
+    assert_eq!(updated(&app), vec![false, true, true, false]);
+    let suspended: Vec<bool> = app.cards.iter().map(|card| card.suspended).collect();
+    assert_eq!(suspended, vec![false, true, true, false]);
+
+    app.bulk_move(spanish);
+    assert_eq!(titles(&app), vec!["Vec", "Rc"]);
+    assert_eq!(updated(&app), vec![false, false]);
+    let moved = app.db.cards(spanish);
+    assert_eq!(moved.len(), 2);
+    assert!(moved.iter().all(|card| card.updated_at > 0));
+}
+
+#[test]
+fn recent_sort_follows_changes() {
+    let mut store = SqliteStore::open(":memory:").unwrap();
+    let rust = store.add_stack("Rust".to_string());
+    for title in ["Vec", "Box", "Arc"] {
+        store.add_card(rust, title.to_string(), String::new());
+    }
+    store.conn.execute_batch("UPDATE card SET updated_at = 100 + id").unwrap();
+    let mut app = App::with_config(Box::new(store), &Config::default());
+    app.get_items();
+    app.state.select(Some(0));
+    app.list_cards();
+    app.cards_state.select(Some(0));
+    while app.card_sort != CardSort::Recent {
+        app.next_card_sort();
+    }
+    assert_eq!(titles(&app), vec!["Arc", "Box", "Vec"]);
+
+    // An edited card comes first, and the sort stays when the list is refreshed
+    app.cards_state.select(Some(2));
+    app.card_title_input = "Vec<T>".to_string();
+    app.card_text_input = String::new();
+    app.edit_card();
+    app.refresh_cards();
+    assert_eq!(app.card_sort.name(), "recent");
+    assert_eq!(titles(&app), vec!["Vec<T>", "Arc", "Box"]);
+    assert_eq!(app.cards_state.selected(), Some(0));
+}
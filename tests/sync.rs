@@ -0,0 +1,153 @@
+mod common;
+
+use cards::db::memory::MemoryStore;
+use cards::db::Store;
+use cards::scheduler;
+use cards::sync::{self, STATE_FILE};
+use std::fs;
+use std::path::PathBuf;
+
+// Empty deck directory for a test
+fn deck_dir(name: &str) -> PathBuf {
+    common::test_dir("sync", name)
+}
+
+fn store() -> MemoryStore {
+    let mut store = MemoryStore::new();
+    let rust = store.add_stack("Rust".to_string());
+    store.add_card(rust, "Borrow checker".to_string(), "Checks references".to_string());
+    store.add_card(rust, "Box".to_string(), "Heap pointer".to_string());
+    store
+}
+
+fn titles(store: &dyn Store) -> Vec<String> {
+    let mut titles: Vec<String> = store
+        .stacks()
+        .iter()
+        .flat_map(|stack| store.cards(stack.id))
+        .map(|card| format!("{}: {}", card.title, card.text))
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn first_sync_writes_files_with_ids() {
+    let dir = deck_dir("first");
+    let mut store = store();
+    let report = sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+    assert_eq!(report.added_to_files, 2);
+
+    let content = fs::read_to_string(dir.join("Rust.md")).unwrap();
+    let id = store.cards(store.stacks()[0].id)[0].id;
+    assert!(content.starts_with("# Rust\n\n## Borrow checker\n"));
+    assert!(content.contains(&format!("<!-- id: {} -->", id)));
+    assert!(dir.join(STATE_FILE).exists());
+
+    // Nothing changes on a second sync
+    let report = sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+    assert_eq!(report.added_to_db + report.updated_in_db + report.deleted_from_db, 0);
+    assert_eq!(report.added_to_files + report.updated_in_files + report.deleted_from_files, 0);
+    assert_eq!(content, fs::read_to_string(dir.join("Rust.md")).unwrap());
+}
+
+#[test]
+fn changes_in_files_go_to_the_collection_and_keep_schedule() {
+    let dir = deck_dir("files");
+    let mut store = store();
+    let stack_id = store.stacks()[0].id;
+    let id = store.cards(stack_id)[0].id;
+    let schedule = scheduler::review(&store.cards(stack_id)[0].schedule, scheduler::GOOD, 1000);
+    store.set_schedule(id, &schedule);
+    sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+
+    let path = dir.join("Rust.md");
+    let content = fs::read_to_string(&path).unwrap()
+        .replace("Checks references", "Checks that references are valid")
+        .replace("## Box\n", "## Box<T>\n")
+        + "\n## Rc\n<!-- tags: smart -->\n\nShared pointer\n";
+    let content = content.replace(
+        &format!("\n## Box<T>\n<!-- id: {} -->\n\nHeap pointer\n", id + 1),
+        "",
+    );
+    fs::write(&path, content).unwrap();
+
+    let report = sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+    assert_eq!(report.updated_in_db, 1);
+    assert_eq!(report.added_to_db, 1);
+    assert_eq!(report.deleted_from_db, 1);
+    assert!(report.conflicts.is_empty());
+    assert_eq!(
+        titles(&store),
+        vec!["Borrow checker: Checks that references are valid", "Rc: Shared pointer"]
+    );
+    let card = store.cards(stack_id).into_iter().find(|card| card.id == id).unwrap();
+    assert_eq!(card.schedule.reps, 1);
+    assert_eq!(card.schedule.due, schedule.due);
+
+    // The new card got its id written back
+    let rc = store.cards(stack_id).into_iter().find(|card| card.title == "Rc").unwrap();
+    assert_eq!(rc.tags, "smart");
+    assert!(fs::read_to_string(&path).unwrap().contains(&format!("<!-- id: {} -->", rc.id)));
+}
+
+#[test]
+fn changes_in_the_collection_go_to_files() {
+    let dir = deck_dir("collection");
+    let mut store = store();
+    sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+
+    let stack_id = store.stacks()[0].id;
+    let cards = store.cards(stack_id);
+    store.edit_card(cards[0].id, "Borrow checker".to_string(), "Edited in the app".to_string());
+    store.delete_card(cards[1].id);
+    let spanish = store.add_stack("Spanish".to_string());
+    store.add_card(spanish, "hola".to_string(), "hello".to_string());
+
+    let report = sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+    assert_eq!(report.updated_in_files, 1);
+    assert_eq!(report.deleted_from_files, 1);
+    assert_eq!(report.added_to_files, 1);
+    let rust = fs::read_to_string(dir.join("Rust.md")).unwrap();
+    assert!(rust.contains("Edited in the app"));
+    assert!(!rust.contains("## Box"));
+    assert!(fs::read_to_string(dir.join("Spanish.md")).unwrap().contains("## hola"));
+}
+
+#[test]
+fn changes_on_both_sides_are_conflicts() {
+    let dir = deck_dir("conflict");
+    let mut store = store();
+    sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+
+    let stack_id = store.stacks()[0].id;
+    let id = store.cards(stack_id)[0].id;
+    store.edit_card(id, "Borrow checker".to_string(), "From the app".to_string());
+    let path = dir.join("Rust.md");
+    let content = fs::read_to_string(&path).unwrap().replace("Checks references", "From the file");
+    fs::write(&path, content).unwrap();
+
+    let report = sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert!(report.conflicts[0].contains("Borrow checker"));
+    assert!(fs::read_to_string(&path).unwrap().contains("From the file"));
+    assert_eq!(store.cards(stack_id)[0].text, "From the app");
+
+    // Making both sides the same resolves it
+    store.edit_card(id, "Borrow checker".to_string(), "From the file".to_string());
+    let report = sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+    assert!(report.conflicts.is_empty());
+}
+
+#[test]
+fn deleted_file_deletes_its_stack() {
+    let dir = deck_dir("deleted");
+    let mut store = store();
+    sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+
+    fs::remove_file(dir.join("Rust.md")).unwrap();
+    let report = sync::sync(&mut store, dir.to_str().unwrap()).unwrap();
+    assert_eq!(report.deleted_from_db, 2);
+    assert!(store.stacks().is_empty());
+    assert!(!dir.join("Rust.md").exists());
+}
@@ -0,0 +1,77 @@
+use cards::config::Config;
+use cards::theme::{self, Theme};
+use tui::style::{Color, Modifier};
+
+#[test]
+fn named_themes() {
+    for name in theme::THEMES {
+        let theme = Theme::named(name, 5);
+        assert_eq!(theme.colors, *name != "mono");
+        assert_eq!(theme.border(false), theme.border);
+        assert_eq!(theme.border(true), theme.highlight);
+    }
+
+    // The highlight color is used by every theme but mono
+    assert_eq!(Theme::named("light", 5).highlight.fg, Some(Color::Indexed(5)));
+    let mono = Theme::named("mono", 5);
+    assert_eq!(mono.highlight.fg, None);
+    assert!(mono.selection.add_modifier.contains(Modifier::REVERSED));
+
+    // Unknown names get the default theme
+    let default = Theme::named("default", 5);
+    assert_eq!(Theme::named("solarized", 5).selection, default.selection);
+}
+
+#[test]
+fn no_color() {
+    let config = Config {
+        theme: "light".to_string(),
+        ..Config::default()
+    };
+    assert!(Theme::with_no_color(&config, None).colors);
+    assert_eq!(Theme::with_no_color(&config, None).text.fg, Some(Color::Black));
+
+    // An empty NO_COLOR doesn't count
+    assert!(Theme::with_no_color(&config, Some("")).colors);
+
+    let theme = Theme::with_no_color(&config, Some("1"));
+    assert!(!theme.colors);
+    assert_eq!(theme.text.fg, None);
+    assert_eq!(theme.highlight.fg, None);
+}
+
+#[test]
+fn user_colors() {
+    let colors: toml::Table = "text = 250\nborder = \"darkgray\"\nselection = { fg = 0, bg = \"yellow\" }\npopup = { bg = 236 }\n"
+        .parse()
+        .unwrap();
+    let (theme, errors) = Theme::named("default", 5).with_colors(&colors);
+    assert!(errors.is_empty());
+    assert_eq!(theme.text.fg, Some(Color::Indexed(250)));
+    assert_eq!(theme.border.fg, Some(Color::DarkGray));
+    assert_eq!((theme.selection.fg, theme.selection.bg), (Some(Color::Indexed(0)), Some(Color::Yellow)));
+    assert_eq!(theme.popup.bg, Some(Color::Indexed(236)));
+    assert_eq!(theme.popup.fg, None);
+
+    // Mono and NO_COLOR keep having no colors
+    let (mono, _) = Theme::named("mono", 5).with_colors(&colors);
+    assert_eq!(mono.text.fg, None);
+    let config = Config {
+        colors,
+        ..Config::default()
+    };
+    assert_eq!(Theme::with_no_color(&config, None).text.fg, Some(Color::Indexed(250)));
+    assert_eq!(Theme::with_no_color(&config, Some("1")).text.fg, None);
+
+    // Colors with errors are left out
+    let colors: toml::Table = "text = 300\ntitle = \"teal\"\nshadow = 1\nerror = { fg = 1, underline = true }\nsuccess = 2\n"
+        .parse()
+        .unwrap();
+    let (theme, errors) = Theme::named("default", 5).with_colors(&colors);
+    assert_eq!(theme.text.fg, Some(Color::White));
+    assert_eq!(theme.success.fg, Some(Color::Indexed(2)));
+    let settings: Vec<&str> = errors.iter().map(|(setting, _)| setting.as_str()).collect();
+    assert_eq!(settings, ["colors.error", "colors.shadow", "colors.text", "colors.title"]);
+    assert_eq!(errors[1].1, format!("unknown style, use one of {}", theme::STYLES.join(", ")));
+    assert_eq!(errors[3].1, "unknown color \"teal\", use a number from 0 to 255 or a color name");
+}